@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use futures_channel::oneshot;
+
+use crate::error::ACBrError;
+
+use super::config::ConfigESocial;
+use super::executor::Executor;
+use super::ACBrLibEsocial;
+
+/// Sessão com API assíncrona, disponível com a feature `async`.
+///
+/// A sessão fica em uma thread dedicada, que executa as chamadas uma de cada vez, e os
+/// resultados voltam por um canal. As chamadas nativas nunca rodam nas threads do runtime
+/// (tokio ou outro), e cancelar o future não interrompe a chamada: ela termina na thread
+/// da sessão e o resultado é descartado.
+///
+/// Com `com_prazo`, uma chamada que passa do prazo retorna `ACBrError::Timeout` e a sessão
+/// entra em quarentena, como na `SessaoComPrazo`.
+///
+/// Os clones usam a mesma sessão, que é finalizada quando o último deles é liberado.
+#[derive(Clone)]
+pub struct SessaoAssincrona {
+    executor: Executor,
+    prazo: Option<Duration>,
+}
+
+impl SessaoAssincrona {
+    /// Equivalente assíncrono de `ACBrLibEsocial::new`.
+    pub async fn new(
+        arquivo_config: impl Into<String>,
+        chave_criptografia: impl Into<String>,
+    ) -> Result<Self, ACBrError> {
+        let (arquivo_config, chave) = (arquivo_config.into(), chave_criptografia.into());
+
+        Self::iniciar(move || ACBrLibEsocial::new(arquivo_config, chave)).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::com_config_temporaria`.
+    pub async fn com_config_temporaria(
+        chave_criptografia: impl Into<String>,
+    ) -> Result<Self, ACBrError> {
+        let chave = chave_criptografia.into();
+
+        Self::iniciar(move || ACBrLibEsocial::com_config_temporaria(chave)).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::com_modelo_config`.
+    pub async fn com_modelo_config(
+        modelo: impl Into<PathBuf>,
+        chave_criptografia: impl Into<String>,
+    ) -> Result<Self, ACBrError> {
+        let (modelo, chave) = (modelo.into(), chave_criptografia.into());
+
+        Self::iniciar(move || ACBrLibEsocial::com_modelo_config(modelo, chave)).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::com_config`.
+    pub async fn com_config(
+        config: ConfigESocial,
+        chave_criptografia: impl Into<String>,
+    ) -> Result<Self, ACBrError> {
+        let chave = chave_criptografia.into();
+
+        Self::iniciar(move || ACBrLibEsocial::com_config(&config, chave)).await
+    }
+
+    /// Leva uma sessão já criada para uma thread dedicada.
+    pub fn from_sessao(sessao: ACBrLibEsocial) -> Result<Self, ACBrError> {
+        let executor = Executor::iniciar(move || Ok(sessao), |_| ())?;
+
+        Ok(SessaoAssincrona {
+            executor,
+            prazo: None,
+        })
+    }
+
+    /// Prazo padrão de cada chamada desta sessão.
+    pub fn com_prazo(mut self, prazo: Duration) -> Self {
+        self.prazo = Some(prazo);
+        self
+    }
+
+    /// Indica se uma chamada passou do prazo e a sessão não aceita mais chamadas.
+    pub fn em_quarentena(&self) -> bool {
+        self.executor.em_quarentena()
+    }
+
+    /// Executa `operacao` na thread da sessão e aguarda o resultado.
+    pub async fn executar<R, F>(&self, operacao: F) -> Result<R, ACBrError>
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.executar_com_prazo(self.prazo, operacao).await
+    }
+
+    /// Executa `operacao` com um prazo próprio, no lugar do prazo padrão da sessão.
+    pub async fn executar_com_prazo<R, F>(
+        &self,
+        prazo: Option<Duration>,
+        operacao: F,
+    ) -> Result<R, ACBrError>
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (resultado, receber) = oneshot::channel();
+
+        // Quem aguardava pode ter desistido; a chamada já terminou de qualquer forma
+        self.executor.enviar(prazo, operacao, move |retorno| {
+            let _ = resultado.send(retorno);
+        });
+
+        receber.await.map_err(|_| self.executor.erro_encerrada())?
+    }
+
+    /// Cria a sessão na sua thread dedicada, onde ela ficará até ser finalizada.
+    async fn iniciar<F>(criar: F) -> Result<Self, ACBrError>
+    where
+        F: FnOnce() -> Result<ACBrLibEsocial, ACBrError> + Send + 'static,
+    {
+        let (criada, receber) = oneshot::channel();
+
+        let executor = Executor::iniciar(criar, move |resultado| {
+            let _ = criada.send(resultado);
+        })?;
+
+        receber.await.map_err(|_| ACBrError::SessaoEncerrada)??;
+
+        Ok(SessaoAssincrona {
+            executor,
+            prazo: None,
+        })
+    }
+}
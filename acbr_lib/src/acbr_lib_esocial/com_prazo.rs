@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::error::ACBrError;
+
+use super::executor::Executor;
+use super::ACBrLibEsocial;
+
+/// Sessão em que toda chamada tem um prazo.
+///
+/// A sessão fica em uma thread dedicada e quem chama aguarda no máximo o prazo. Se ele
+/// passar, a chamada retorna `ACBrError::Timeout`, a sessão entra em quarentena e as
+/// próximas chamadas retornam `ACBrError::SessaoEmQuarentena`. A chamada nativa não pode
+/// ser interrompida: ela termina na thread dedicada, que então finaliza a sessão. Cada
+/// prazo vencido é contado em `metricas::metricas().chamadas_expiradas`.
+///
+/// O prazo conta desde a chamada, incluindo a espera pelas chamadas anteriores dos clones.
+/// Uma chamada cujo prazo vence antes de começar é descartada sem colocar a sessão em
+/// quarentena.
+///
+/// Os clones usam a mesma sessão, que é finalizada quando o último deles é liberado.
+#[derive(Clone)]
+pub struct SessaoComPrazo {
+    executor: Executor,
+    prazo: Duration,
+}
+
+impl SessaoComPrazo {
+    /// Equivalente de `ACBrLibEsocial::new` com o prazo padrão das chamadas.
+    pub fn new(
+        arquivo_config: impl Into<String>,
+        chave_criptografia: impl Into<String>,
+        prazo: Duration,
+    ) -> Result<Self, ACBrError> {
+        let (arquivo_config, chave) = (arquivo_config.into(), chave_criptografia.into());
+
+        Self::iniciar(move || ACBrLibEsocial::new(arquivo_config, chave), prazo)
+    }
+
+    /// Equivalente de `ACBrLibEsocial::com_config_temporaria` com o prazo padrão das chamadas.
+    pub fn com_config_temporaria(
+        chave_criptografia: impl Into<String>,
+        prazo: Duration,
+    ) -> Result<Self, ACBrError> {
+        let chave = chave_criptografia.into();
+
+        Self::iniciar(move || ACBrLibEsocial::com_config_temporaria(chave), prazo)
+    }
+
+    /// Equivalente de `ACBrLibEsocial::com_modelo_config` com o prazo padrão das chamadas.
+    pub fn com_modelo_config(
+        modelo: impl Into<PathBuf>,
+        chave_criptografia: impl Into<String>,
+        prazo: Duration,
+    ) -> Result<Self, ACBrError> {
+        let (modelo, chave) = (modelo.into(), chave_criptografia.into());
+
+        Self::iniciar(
+            move || ACBrLibEsocial::com_modelo_config(modelo, chave),
+            prazo,
+        )
+    }
+
+    /// Leva uma sessão já criada para uma thread dedicada.
+    pub fn from_sessao(sessao: ACBrLibEsocial, prazo: Duration) -> Result<Self, ACBrError> {
+        let executor = Executor::iniciar(move || Ok(sessao), |_| ())?;
+
+        Ok(SessaoComPrazo { executor, prazo })
+    }
+
+    pub fn prazo(&self) -> Duration {
+        self.prazo
+    }
+
+    /// Indica se uma chamada passou do prazo e a sessão não aceita mais chamadas.
+    pub fn em_quarentena(&self) -> bool {
+        self.executor.em_quarentena()
+    }
+
+    /// Executa `operacao` na thread da sessão, aguardando no máximo o prazo padrão.
+    pub fn executar<R, F>(&self, operacao: F) -> Result<R, ACBrError>
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.executar_com_prazo(self.prazo, operacao)
+    }
+
+    /// Executa `operacao` com um prazo próprio, no lugar do prazo padrão da sessão.
+    pub fn executar_com_prazo<R, F>(&self, prazo: Duration, operacao: F) -> Result<R, ACBrError>
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (resultado, receber) = mpsc::sync_channel(1);
+
+        self.executor.enviar(Some(prazo), operacao, move |retorno| {
+            let _ = resultado.send(retorno);
+        });
+
+        receber.recv().map_err(|_| self.executor.erro_encerrada())?
+    }
+
+    fn iniciar<F>(criar: F, prazo: Duration) -> Result<Self, ACBrError>
+    where
+        F: FnOnce() -> Result<ACBrLibEsocial, ACBrError> + Send + 'static,
+    {
+        let (criada, receber) = mpsc::sync_channel(1);
+
+        let executor = Executor::iniciar(criar, move |resultado| {
+            let _ = criada.send(resultado);
+        })?;
+
+        receber.recv().map_err(|_| ACBrError::SessaoEncerrada)??;
+
+        Ok(SessaoComPrazo { executor, prazo })
+    }
+}
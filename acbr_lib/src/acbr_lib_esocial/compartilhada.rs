@@ -0,0 +1,39 @@
+use std::sync::{Arc, Mutex};
+
+use super::ACBrLibEsocial;
+
+/// Sessão que pode ser clonada e usada por várias threads.
+///
+/// As chamadas são feitas uma de cada vez, já que a instância da ACBrLib não aceita
+/// chamadas simultâneas. A sessão é finalizada quando o último clone é liberado.
+#[derive(Clone)]
+pub struct SessaoCompartilhada {
+    sessao: Arc<Mutex<ACBrLibEsocial>>,
+}
+
+impl SessaoCompartilhada {
+    pub fn new(sessao: ACBrLibEsocial) -> Self {
+        SessaoCompartilhada {
+            sessao: Arc::new(Mutex::new(sessao)),
+        }
+    }
+
+    /// Executa `operacao` com acesso exclusivo à sessão, aguardando as demais terminarem.
+    ///
+    /// Se outra operação entrar em pânico, a sessão continua disponível: o estado da
+    /// instância nativa não depende do que o Rust fazia no momento.
+    pub fn executar<R, F>(&self, operacao: F) -> R
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> R,
+    {
+        let mut sessao = self.sessao.lock().unwrap_or_else(|erro| erro.into_inner());
+
+        operacao(&mut sessao)
+    }
+}
+
+impl From<ACBrLibEsocial> for SessaoCompartilhada {
+    fn from(sessao: ACBrLibEsocial) -> Self {
+        SessaoCompartilhada::new(sessao)
+    }
+}
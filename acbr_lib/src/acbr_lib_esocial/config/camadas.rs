@@ -0,0 +1,429 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use super::ConfigESocial;
+
+/// Prefixo das variáveis de ambiente que sobrescrevem a configuração, seguido da sessão e
+/// da chave em maiúsculas: `ACBRLIB_ESOCIAL_CONFIG__ESOCIAL__AMBIENTE`. Pontos no nome da
+/// chave viram `_` (`ACBRLIB_ESOCIAL_CONFIG__DFE__TIMEZONE_MODO`).
+pub const PREFIXO_VARIAVEIS: &str = "ACBRLIB_ESOCIAL_CONFIG__";
+
+fn nome_variavel(secao: &str, chave: &str) -> String {
+    format!(
+        "{}{}__{}",
+        PREFIXO_VARIAVEIS,
+        secao.to_uppercase(),
+        chave.to_uppercase().replace('.', "_")
+    )
+}
+
+/// Valores próprios de um empregador: identificação, certificado e onde salvar os arquivos.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfilEmpregador {
+    id_empregador: String,
+    config: ConfigESocial,
+}
+
+impl PerfilEmpregador {
+    pub fn new(id_empregador: impl Into<String>) -> Self {
+        let id_empregador = id_empregador.into();
+
+        let mut config = ConfigESocial::default();
+        config.esocial.id_empregador = Some(id_empregador.clone());
+
+        PerfilEmpregador {
+            id_empregador,
+            config,
+        }
+    }
+
+    pub fn com_tipo_empregador(mut self, tipo_empregador: i32) -> Self {
+        self.config.esocial.tipo_empregador = Some(tipo_empregador);
+        self
+    }
+
+    /// Certificado em arquivo PFX e a sua senha.
+    pub fn com_certificado_pfx(
+        mut self,
+        arquivo: impl Into<PathBuf>,
+        senha: impl Into<String>,
+    ) -> Self {
+        self.config.dfe.arquivo_pfx = Some(arquivo.into());
+        self.config.dfe.senha = Some(senha.into());
+        self
+    }
+
+    /// Certificado instalado no sistema, identificado pelo número de série.
+    pub fn com_numero_serie(mut self, numero_serie: impl Into<String>) -> Self {
+        self.config.dfe.numero_serie = Some(numero_serie.into());
+        self
+    }
+
+    pub fn com_path_salvar(mut self, path_salvar: impl Into<PathBuf>) -> Self {
+        self.config.esocial.path_salvar = Some(path_salvar.into());
+        self
+    }
+
+    /// Outros valores específicos do empregador.
+    pub fn com_valores(mut self, config: &ConfigESocial) -> Self {
+        self.config.mesclar(config);
+        self
+    }
+
+    pub fn id_empregador(&self) -> &str {
+        &self.id_empregador
+    }
+
+    pub fn config(&self) -> &ConfigESocial {
+        &self.config
+    }
+}
+
+/// Camada de onde veio um valor da `ConfigCarregada`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrigemConfig {
+    /// Arquivo de valores padrão.
+    Padrao(PathBuf),
+    /// Perfil do empregador, pelo `IdEmpregador`.
+    Perfil(String),
+    /// Variável de ambiente, pelo nome.
+    Ambiente(String),
+    /// Valores definidos pelo código com `CarregadorConfig::com_valores`.
+    Codigo,
+}
+
+impl fmt::Display for OrigemConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrigemConfig::Padrao(arquivo) => write!(f, "arquivo padrão {}", arquivo.display()),
+            OrigemConfig::Perfil(id_empregador) => {
+                write!(f, "perfil do empregador {}", id_empregador)
+            }
+            OrigemConfig::Ambiente(variavel) => write!(f, "variável de ambiente {}", variavel),
+            OrigemConfig::Codigo => write!(f, "código"),
+        }
+    }
+}
+
+/// Monta a configuração em camadas, cada uma sobrescrevendo as anteriores: o arquivo de
+/// valores padrão, o perfil do empregador, as variáveis de ambiente e os valores definidos
+/// pelo código. A ordem é sempre essa, independente da ordem das chamadas.
+///
+/// O carregador pode ser montado uma vez, com o arquivo padrão e as variáveis de ambiente,
+/// e clonado para cada empregador:
+///
+/// ```no_run
+/// # use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+/// # use acbr_lib::acbr_lib_esocial::config::{CarregadorConfig, PerfilEmpregador};
+/// # fn main() -> Result<(), acbr_lib::error::ACBrError> {
+/// let base = CarregadorConfig::new()
+///     .com_arquivo_padrao("resources/config.ini")?
+///     .com_variaveis_ambiente();
+///
+/// let perfil = PerfilEmpregador::new("12345678").com_certificado_pfx("empresa.pfx", "senha");
+/// let config = base.clone().com_perfil(perfil).carregar()?;
+///
+/// let lib = ACBrLibEsocial::com_config(&config, "")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CarregadorConfig {
+    padrao: Option<(PathBuf, ConfigESocial)>,
+    perfil: Option<PerfilEmpregador>,
+    variaveis_ambiente: bool,
+    codigo: ConfigESocial,
+}
+
+impl CarregadorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arquivo com os valores padrão, normalmente o `resources/config.ini` distribuído.
+    /// É lido na hora, então o carregador pode ser reaproveitado sem ler o arquivo de novo.
+    pub fn com_arquivo_padrao(mut self, arquivo: impl AsRef<Path>) -> Result<Self, ACBrError> {
+        let arquivo = arquivo.as_ref();
+        let texto = fs::read_to_string(arquivo).map_err(|erro| {
+            ACBrError::ArquivoNaoEncontrado(format!("{}: {}", arquivo.display(), erro))
+        })?;
+
+        self.padrao = Some((arquivo.to_path_buf(), ConfigESocial::de_ini(&texto)?));
+        Ok(self)
+    }
+
+    pub fn com_perfil(mut self, perfil: PerfilEmpregador) -> Self {
+        self.perfil = Some(perfil);
+        self
+    }
+
+    /// Aplica as variáveis de ambiente com `PREFIXO_VARIAVEIS`, lidas em `carregar`.
+    pub fn com_variaveis_ambiente(mut self) -> Self {
+        self.variaveis_ambiente = true;
+        self
+    }
+
+    /// Valores definidos pelo código, que prevalecem sobre todas as outras camadas.
+    pub fn com_valores(mut self, config: &ConfigESocial) -> Self {
+        self.codigo.mesclar(config);
+        self
+    }
+
+    pub fn carregar(&self) -> Result<ConfigCarregada, ACBrError> {
+        let variaveis = if self.variaveis_ambiente {
+            variaveis_com_prefixo(env::vars_os())?
+        } else {
+            Vec::new()
+        };
+
+        self.carregar_com(variaveis)
+    }
+
+    fn carregar_com(&self, variaveis: Vec<(String, String)>) -> Result<ConfigCarregada, ACBrError> {
+        let mut carregada = ConfigCarregada::default();
+
+        if let Some((arquivo, padrao)) = &self.padrao {
+            carregada.aplicar(padrao, |_, _| OrigemConfig::Padrao(arquivo.clone()));
+        }
+
+        if let Some(perfil) = &self.perfil {
+            carregada.aplicar(&perfil.config, |_, _| {
+                OrigemConfig::Perfil(perfil.id_empregador.clone())
+            });
+        }
+
+        let ambiente = config_das_variaveis(variaveis)?;
+        carregada.aplicar(&ambiente, |secao, chave| {
+            OrigemConfig::Ambiente(nome_variavel(secao, chave))
+        });
+
+        carregada.aplicar(&self.codigo, |_, _| OrigemConfig::Codigo);
+
+        Ok(carregada)
+    }
+}
+
+/// Variáveis com `PREFIXO_VARIAVEIS`. As demais são ignoradas sem ser convertidas, já que
+/// o ambiente do processo pode ter nomes e valores que não são UTF-8; um valor assim em uma
+/// variável do modelo é recusado.
+fn variaveis_com_prefixo(
+    variaveis: impl IntoIterator<Item = (OsString, OsString)>,
+) -> Result<Vec<(String, String)>, ACBrError> {
+    let mut lidas = Vec::new();
+
+    for (nome, valor) in variaveis {
+        let Some(nome) = nome
+            .to_str()
+            .filter(|nome| nome.starts_with(PREFIXO_VARIAVEIS))
+        else {
+            continue;
+        };
+
+        match valor.into_string() {
+            Ok(valor) => lidas.push((nome.to_string(), valor)),
+            Err(valor) => {
+                let chave = ConfigESocial::CHAVES
+                    .iter()
+                    .find(|(secao, chave)| nome_variavel(secao, chave) == nome);
+
+                if let Some((secao, chave)) = chave {
+                    return Err(ACBrError::ConfigInvalida {
+                        secao: secao.to_string(),
+                        chave: chave.to_string(),
+                        valor: valor.to_string_lossy().into_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(lidas)
+}
+
+/// Valores das variáveis de ambiente que correspondem a chaves do modelo.
+fn config_das_variaveis(variaveis: Vec<(String, String)>) -> Result<ConfigESocial, ACBrError> {
+    let mut ini = Ini::default();
+
+    for (secao, chave) in ConfigESocial::CHAVES {
+        let nome = nome_variavel(secao, chave);
+
+        if let Some((_, valor)) = variaveis.iter().find(|(variavel, _)| *variavel == nome) {
+            ini.gravar(secao, chave, valor.as_str());
+        }
+    }
+
+    ConfigESocial::de_ini_lido(&ini)
+}
+
+/// Configuração resultante do `CarregadorConfig`, com a origem de cada valor.
+///
+/// Pode ser usada diretamente onde se espera uma `ConfigESocial`, como em
+/// `ACBrLibEsocial::com_config(&config, chave)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigCarregada {
+    config: ConfigESocial,
+    origens: BTreeMap<(String, String), OrigemConfig>,
+}
+
+impl ConfigCarregada {
+    /// Camada de onde veio o valor da chave, se alguma a definiu.
+    pub fn origem(&self, secao: &str, chave: &str) -> Option<&OrigemConfig> {
+        self.origens
+            .iter()
+            .find(|((s, c), _)| s.eq_ignore_ascii_case(secao) && c.eq_ignore_ascii_case(chave))
+            .map(|(_, origem)| origem)
+    }
+
+    /// Sessão, chave e origem de cada valor definido.
+    pub fn origens(&self) -> impl Iterator<Item = (&str, &str, &OrigemConfig)> {
+        self.origens
+            .iter()
+            .map(|((secao, chave), origem)| (secao.as_str(), chave.as_str(), origem))
+    }
+
+    pub fn into_config(self) -> ConfigESocial {
+        self.config
+    }
+
+    fn aplicar(&mut self, camada: &ConfigESocial, origem: impl Fn(&str, &str) -> OrigemConfig) {
+        self.config.mesclar(camada);
+
+        for (secao, chave, _) in camada.valores() {
+            let origem = origem(&secao, &chave);
+            self.origens.insert((secao, chave), origem);
+        }
+    }
+}
+
+impl Deref for ConfigCarregada {
+    type Target = ConfigESocial;
+
+    fn deref(&self) -> &ConfigESocial {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Ambiente;
+    use super::*;
+
+    const CONFIG_PADRAO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/config.ini");
+
+    fn variavel(secao: &str, chave: &str, valor: &str) -> (String, String) {
+        (nome_variavel(secao, chave), valor.to_string())
+    }
+
+    #[test]
+    fn cada_camada_sobrescreve_as_anteriores() {
+        let mut codigo = ConfigESocial::default();
+        codigo.esocial.timeout = Some(9000);
+
+        let carregador = CarregadorConfig::new()
+            .com_valores(&codigo)
+            .com_perfil(
+                PerfilEmpregador::new("12345678")
+                    .com_tipo_empregador(1)
+                    .com_path_salvar("/srv/esocial/12345678"),
+            )
+            .com_arquivo_padrao(CONFIG_PADRAO)
+            .unwrap();
+
+        let config = carregador
+            .carregar_com(vec![
+                variavel("eSocial", "Ambiente", "0"),
+                variavel("eSocial", "Timeout", "7000"),
+                ("OUTRA_VARIAVEL".into(), "1".into()),
+            ])
+            .unwrap();
+
+        assert_eq!(config.esocial.id_empregador.as_deref(), Some("12345678"));
+        assert_eq!(config.esocial.tipo_empregador, Some(1));
+        assert_eq!(config.esocial.ambiente, Some(Ambiente::Producao));
+        assert_eq!(config.esocial.timeout, Some(9000));
+        assert_eq!(config.esocial.tentativas, Some(5));
+
+        assert_eq!(
+            config.origem("eSocial", "Tentativas"),
+            Some(&OrigemConfig::Padrao(CONFIG_PADRAO.into()))
+        );
+        assert_eq!(
+            config.origem("eSocial", "PathSalvar"),
+            Some(&OrigemConfig::Perfil("12345678".into()))
+        );
+        assert_eq!(
+            config.origem("esocial", "ambiente"),
+            Some(&OrigemConfig::Ambiente(
+                "ACBRLIB_ESOCIAL_CONFIG__ESOCIAL__AMBIENTE".into()
+            ))
+        );
+        assert_eq!(
+            config.origem("eSocial", "Timeout"),
+            Some(&OrigemConfig::Codigo)
+        );
+        assert_eq!(config.origem("eSocial", "ChaveInexistente"), None);
+    }
+
+    #[test]
+    fn chaves_com_ponto_usam_sublinhado_na_variavel() {
+        let config = CarregadorConfig::new()
+            .carregar_com(vec![variavel("DFe", "TimeZone.Modo", "1")])
+            .unwrap();
+
+        assert_eq!(config.dfe.time_zone_modo, Some(1));
+        assert_eq!(
+            config.origem("DFe", "TimeZone.Modo").unwrap().to_string(),
+            "variável de ambiente ACBRLIB_ESOCIAL_CONFIG__DFE__TIMEZONE_MODO"
+        );
+    }
+
+    #[test]
+    fn variavel_com_valor_invalido_retorna_erro() {
+        let resultado =
+            CarregadorConfig::new().carregar_com(vec![variavel("eSocial", "Ambiente", "2")]);
+
+        assert!(matches!(resultado, Err(ACBrError::ConfigInvalida { .. })));
+    }
+
+    #[test]
+    fn arquivo_padrao_inexistente_retorna_erro() {
+        assert!(matches!(
+            CarregadorConfig::new().com_arquivo_padrao("nao_existe/config.ini"),
+            Err(ACBrError::ArquivoNaoEncontrado(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn variaveis_que_nao_sao_utf8() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let invalido = || OsString::from_vec(vec![b'a', 0xff]);
+        let nome = |secao, chave| OsString::from(nome_variavel(secao, chave));
+
+        let lidas = variaveis_com_prefixo(vec![
+            (OsString::from("OUTRA"), invalido()),
+            (OsString::from_vec(vec![0xff]), OsString::from("1")),
+            (nome("eSocial", "Ambiente"), OsString::from("1")),
+            (
+                OsString::from(format!("{}DESCONHECIDA", PREFIXO_VARIAVEIS)),
+                invalido(),
+            ),
+        ])
+        .unwrap();
+        assert_eq!(lidas, [variavel("eSocial", "Ambiente", "1")]);
+
+        assert!(matches!(
+            variaveis_com_prefixo(vec![(nome("eSocial", "Ambiente"), invalido())]),
+            Err(ACBrError::ConfigInvalida { secao, chave, .. })
+                if secao == "eSocial" && chave == "Ambiente"
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Valor da configuração pretendida que não vale na sessão: a biblioteca recusou,
+/// normalizou ou sobrescreveu o valor, ou a chave não existe na configuração efetiva.
+///
+/// Os valores de chaves sigilosas, como `[DFe] Senha`, aparecem como `***`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiferencaConfig {
+    pub secao: String,
+    pub chave: String,
+    /// Valor pretendido, como gravado no INI.
+    pub esperado: String,
+    /// Valor na configuração efetiva, ou `None` se a chave não existe nela.
+    pub efetivo: Option<String>,
+}
+
+impl DiferencaConfig {
+    pub(super) fn new(secao: &str, chave: &str, esperado: String, efetivo: Option<&str>) -> Self {
+        let ocultar = |valor: String| {
+            if super::sigilosa(chave) {
+                "***".to_string()
+            } else {
+                valor
+            }
+        };
+
+        DiferencaConfig {
+            secao: secao.to_string(),
+            chave: chave.to_string(),
+            esperado: ocultar(esperado),
+            efetivo: efetivo.map(|efetivo| ocultar(efetivo.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for DiferencaConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.efetivo {
+            Some(efetivo) => write!(
+                f,
+                "[{}] {}: esperado {:?}, efetivo {:?}",
+                self.secao, self.chave, self.esperado, efetivo
+            ),
+            None => write!(
+                f,
+                "[{}] {}: esperado {:?}, ausente na configuração efetiva",
+                self.secao, self.chave, self.esperado
+            ),
+        }
+    }
+}
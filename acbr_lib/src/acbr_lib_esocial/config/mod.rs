@@ -0,0 +1,496 @@
+//! Modelo tipado da configuração da ACBrLibeSocial.
+//!
+//! Cada sessão do INI é uma struct com um campo por chave. Os campos são opcionais: só os
+//! valores definidos são gravados, e uma chave ausente no INI fica como `None`. As chaves
+//! com códigos inteiros usam enums, como `Ambiente` e `VersaoDF`.
+
+mod camadas;
+mod diferencas;
+mod valores;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use self::valores::ValorConfig;
+use super::ACBrLibEsocial;
+
+pub use self::camadas::{
+    CarregadorConfig, ConfigCarregada, OrigemConfig, PerfilEmpregador, PREFIXO_VARIAVEIS,
+};
+pub use self::diferencas::DiferencaConfig;
+pub use self::valores::{
+    Ambiente, CodificacaoResposta, FormaEmissao, LogNivel, SSLType, TipoResposta, VersaoDF,
+};
+
+/// Declara as sessões da configuração e monta a `ConfigESocial` com todas elas.
+///
+/// Cada entrada gera a struct da sessão, com um campo `Option` por chave, e a leitura e
+/// gravação dela no INI. O nome da chave só aparece aqui, então um erro de digitação no
+/// nome do campo não compila.
+macro_rules! secoes_config {
+    (
+        $(
+            $(#[$meta_secao:meta])*
+            $campo_secao:ident: $secao:ident = $nome_secao:literal {
+                $($(#[$meta:meta])* $campo:ident: $tipo:ty = $chave:literal,)*
+            }
+        )*
+    ) => {
+        $(
+            $(#[$meta_secao])*
+            #[derive(Clone, Default, PartialEq)]
+            pub struct $secao {
+                $($(#[$meta])* pub $campo: Option<$tipo>,)*
+            }
+
+            impl fmt::Debug for $secao {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    let mut secao = f.debug_struct(stringify!($secao));
+                    $(
+                        match &self.$campo {
+                            Some(_) if sigilosa($chave) => secao.field(stringify!($campo), &"***"),
+                            valor => secao.field(stringify!($campo), valor),
+                        };
+                    )*
+                    secao.finish()
+                }
+            }
+
+            impl $secao {
+                /// Nome da sessão no INI.
+                pub const NOME: &'static str = $nome_secao;
+
+                fn gravar(&self, ini: &mut Ini) {
+                    $(
+                        if let Some(valor) = &self.$campo {
+                            ini.gravar($nome_secao, $chave, valor.para_ini());
+                        }
+                    )*
+                }
+
+                fn ler(ini: &Ini) -> Result<Self, ACBrError> {
+                    Ok($secao {
+                        $($campo: ler_valor(ini, $nome_secao, $chave)?,)*
+                    })
+                }
+
+                fn mesclar(&mut self, outra: &Self) {
+                    $(
+                        if let Some(valor) = &outra.$campo {
+                            self.$campo = Some(valor.clone());
+                        }
+                    )*
+                }
+
+                /// Compara os valores definidos com os da configuração efetiva. Um valor
+                /// efetivo que não é válido para o tipo também conta como diferença.
+                fn diferencas(&self, efetiva: &Ini, diferencas: &mut Vec<DiferencaConfig>) {
+                    $(
+                        if let Some(esperado) = &self.$campo {
+                            let efetivo = ler_valor::<$tipo>(efetiva, $nome_secao, $chave);
+
+                            if !matches!(efetivo, Ok(Some(ref efetivo)) if efetivo == esperado) {
+                                diferencas.push(DiferencaConfig::new(
+                                    $nome_secao,
+                                    $chave,
+                                    esperado.para_ini(),
+                                    efetiva.valor($nome_secao, $chave),
+                                ));
+                            }
+                        }
+                    )*
+                }
+            }
+        )*
+
+        /// Configuração da ACBrLibeSocial, com as sessões usadas pelo eSocial.
+        ///
+        /// As sessões que não são usadas pelo eSocial (como `[PosPrinter]`) e as chaves
+        /// desconhecidas são ignoradas na leitura.
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct ConfigESocial {
+            $(pub $campo_secao: $secao,)*
+        }
+
+        impl ConfigESocial {
+            /// Sessão e chave de todos os valores do modelo.
+            pub(crate) const CHAVES: &'static [(&'static str, &'static str)] = &[
+                $($(($nome_secao, $chave),)*)*
+            ];
+
+            /// Lê a configuração de um texto no formato INI da ACBrLib, como o
+            /// `config.ini` ou a resposta de `esocial_config_exportar`.
+            pub fn de_ini(texto: &str) -> Result<Self, ACBrError> {
+                Self::de_ini_lido(&Ini::ler(texto))
+            }
+
+            /// Texto INI com os valores definidos, na ordem das sessões do `config.ini`.
+            pub fn para_ini(&self) -> String {
+                self.ini().to_string()
+            }
+
+            /// Sessão, chave e valor de cada valor definido.
+            pub fn valores(&self) -> Vec<(String, String, String)> {
+                let ini = self.ini();
+                let mut valores = Vec::new();
+
+                for secao in ini.secoes() {
+                    for (chave, valor) in ini.chaves(secao).unwrap_or_default() {
+                        valores.push((secao.to_string(), chave.clone(), valor.clone()));
+                    }
+                }
+
+                valores
+            }
+
+            /// Sobrescreve esta configuração com os valores definidos em `outra`.
+            pub fn mesclar(&mut self, outra: &ConfigESocial) {
+                $(self.$campo_secao.mesclar(&outra.$campo_secao);)*
+            }
+
+            /// Valores definidos nesta configuração que não valem em `efetiva`. Os valores
+            /// que não foram definidos aqui não são comparados.
+            pub fn diferencas(&self, efetiva: &ConfigESocial) -> Vec<DiferencaConfig> {
+                self.diferencas_ini(&efetiva.ini())
+            }
+
+            pub(crate) fn de_ini_lido(ini: &Ini) -> Result<Self, ACBrError> {
+                Ok(ConfigESocial {
+                    $($campo_secao: $secao::ler(ini)?,)*
+                })
+            }
+
+            fn diferencas_ini(&self, efetiva: &Ini) -> Vec<DiferencaConfig> {
+                let mut diferencas = Vec::new();
+                $(self.$campo_secao.diferencas(efetiva, &mut diferencas);)*
+                diferencas
+            }
+
+            fn ini(&self) -> Ini {
+                let mut ini = Ini::default();
+                $(self.$campo_secao.gravar(&mut ini);)*
+                ini
+            }
+        }
+    };
+}
+
+/// Chaves cujo valor não aparece no `Debug`, para não acabar em logs.
+fn sigilosa(chave: &str) -> bool {
+    chave == "Senha"
+}
+
+fn ler_valor<T: ValorConfig>(ini: &Ini, secao: &str, chave: &str) -> Result<Option<T>, ACBrError> {
+    let valor = match ini.valor(secao, chave) {
+        Some(valor) if !valor.is_empty() || T::TEXTO => valor,
+        _ => return Ok(None),
+    };
+
+    T::de_ini(valor)
+        .map(Some)
+        .ok_or_else(|| ACBrError::ConfigInvalida {
+            secao: secao.to_string(),
+            chave: chave.to_string(),
+            valor: valor.to_string(),
+        })
+}
+
+secoes_config! {
+    principal: SecaoPrincipal = "Principal" {
+        tipo_resposta: TipoResposta = "TipoResposta",
+        codificacao_resposta: CodificacaoResposta = "CodificacaoResposta",
+        log_nivel: LogNivel = "LogNivel",
+        log_path: PathBuf = "LogPath",
+    }
+
+    /// Identificação do sistema que usa a biblioteca.
+    sistema: SecaoSistema = "Sistema" {
+        nome: String = "Nome",
+        versao: String = "Versao",
+        data: String = "Data",
+        descricao: String = "Descricao",
+    }
+
+    email: SecaoEmail = "Email" {
+        nome: String = "Nome",
+        servidor: String = "Servidor",
+        conta: String = "Conta",
+        usuario: String = "Usuario",
+        senha: String = "Senha",
+        codificacao: i32 = "Codificacao",
+        porta: u32 = "Porta",
+        ssl: bool = "SSL",
+        tls: bool = "TLS",
+        ssl_type: SSLType = "SSLType",
+        timeout: u32 = "Timeout",
+        confirmacao: bool = "Confirmacao",
+        confirmacao_entrega: bool = "ConfirmacaoEntrega",
+        segundo_plano: bool = "SegundoPlano",
+        tentativas: u32 = "Tentativas",
+        is_html: bool = "IsHTML",
+        priority: i32 = "Priority",
+    }
+
+    proxy: SecaoProxy = "Proxy" {
+        servidor: String = "Servidor",
+        porta: String = "Porta",
+        usuario: String = "Usuario",
+        senha: String = "Senha",
+    }
+
+    socket: SecaoSocket = "Socket" {
+        nivel_log: i32 = "NivelLog",
+        arq_log: PathBuf = "ArqLog",
+        timeout: u32 = "Timeout",
+    }
+
+    software_house: SecaoSoftwareHouse = "SoftwareHouse" {
+        cnpj: String = "CNPJ",
+        razao_social: String = "RazaoSocial",
+        nome_fantasia: String = "NomeFantasia",
+        web_site: String = "WebSite",
+        email: String = "Email",
+        telefone: String = "Telefone",
+        responsavel: String = "Responsavel",
+    }
+
+    emissor: SecaoEmissor = "Emissor" {
+        cnpj: String = "CNPJ",
+        razao_social: String = "RazaoSocial",
+        nome_fantasia: String = "NomeFantasia",
+        web_site: String = "WebSite",
+        email: String = "Email",
+        telefone: String = "Telefone",
+        responsavel: String = "Responsavel",
+    }
+
+    /// Certificado e bibliotecas de criptografia.
+    dfe: SecaoDFe = "DFe" {
+        ssl_crypt_lib: i32 = "SSLCryptLib",
+        ssl_http_lib: i32 = "SSLHttpLib",
+        ssl_xml_sign_lib: i32 = "SSLXmlSignLib",
+        uf: String = "UF",
+        time_zone_modo: i32 = "TimeZone.Modo",
+        time_zone_str: String = "TimeZone.Str",
+        url_pfx: String = "URLPFX",
+        arquivo_pfx: PathBuf = "ArquivoPFX",
+        dados_pfx: String = "DadosPFX",
+        /// Senha do certificado.
+        senha: String = "Senha",
+        numero_serie: String = "NumeroSerie",
+        verificar_validade: bool = "VerificarValidade",
+    }
+
+    esocial: SecaoESocial = "eSocial" {
+        forma_emissao: FormaEmissao = "FormaEmissao",
+        salvar_ger: bool = "SalvarGer",
+        exibir_erro_schema: bool = "ExibirErroSchema",
+        formato_alerta: String = "FormatoAlerta",
+        retirar_acentos: bool = "RetirarAcentos",
+        retirar_espacos: bool = "RetirarEspacos",
+        identar_xml: bool = "IdentarXML",
+        validar_digest: bool = "ValidarDigest",
+        versao_df: VersaoDF = "VersaoDF",
+        id_transmissor: String = "IdTransmissor",
+        id_empregador: String = "IdEmpregador",
+        tipo_empregador: i32 = "TipoEmpregador",
+        ambiente: Ambiente = "Ambiente",
+        salvar_ws: bool = "SalvarWS",
+        /// Em milissegundos.
+        timeout: u32 = "Timeout",
+        timeout_por_thread: bool = "TimeoutPorThread",
+        visualizar: bool = "Visualizar",
+        ajusta_aguarda_consulta_ret: bool = "AjustaAguardaConsultaRet",
+        aguardar_consulta_ret: u32 = "AguardarConsultaRet",
+        intervalo_tentativas: u32 = "IntervaloTentativas",
+        tentativas: u32 = "Tentativas",
+        ssl_type: SSLType = "SSLType",
+        quebra_de_linha: String = "QuebradeLinha",
+        path_salvar: PathBuf = "PathSalvar",
+        path_schemas: PathBuf = "PathSchemas",
+        ini_servicos: PathBuf = "IniServicos",
+        salvar_arq: bool = "SalvarArq",
+        adicionar_literal: bool = "AdicionarLiteral",
+        separar_por_cnpj: bool = "SepararPorCNPJ",
+        separar_por_ie: bool = "SepararPorIE",
+        separar_por_modelo: bool = "SepararPorModelo",
+        separar_por_ano: bool = "SepararPorAno",
+        separar_por_mes: bool = "SepararPorMes",
+        separar_por_dia: bool = "SepararPorDia",
+        download_path_download: PathBuf = "Download.PathDownload",
+        download_separar_por_nome: bool = "Download.SepararPorNome",
+        emissao_path_esocial: bool = "EmissaoPatheSocial",
+        path_esocial: PathBuf = "PatheSocial",
+        id_csrt: i32 = "IdCSRT",
+        csrt: String = "CSRT",
+    }
+}
+
+impl ACBrLibEsocial {
+    /// Grava na sessão todos os valores definidos em `config`, com
+    /// `esocial_config_gravar_valor`. Para na primeira chave recusada pela biblioteca.
+    pub fn aplicar_config(&self, config: &ConfigESocial) -> Result<(), ACBrError> {
+        for (secao, chave, valor) in config.valores() {
+            self.esocial_config_gravar_valor(secao, chave, valor)?;
+        }
+
+        Ok(())
+    }
+
+    /// Configuração efetiva da sessão, lida de `esocial_config_exportar`.
+    ///
+    /// Falha com `ACBrError::ConfigInvalida` se a biblioteca tiver um valor que não cabe
+    /// no modelo; `verificar_config` não tem essa limitação.
+    pub fn config_efetiva(&self) -> Result<ConfigESocial, ACBrError> {
+        ConfigESocial::de_ini(&self.esocial_config_exportar()?)
+    }
+
+    /// Compara `esperada` com a configuração efetiva da sessão, retornando os valores que
+    /// a biblioteca recusou, normalizou ou não tem. Útil depois de `aplicar_config`,
+    /// `esocial_config_ler` ou `esocial_config_importar`, antes de transmitir.
+    pub fn verificar_config(
+        &self,
+        esperada: &ConfigESocial,
+    ) -> Result<Vec<DiferencaConfig>, ACBrError> {
+        let efetiva = Ini::ler(&self.esocial_config_exportar()?);
+
+        Ok(esperada.diferencas_ini(&efetiva))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG_PADRAO: &str = include_str!("../../../../resources/config.ini");
+
+    #[test]
+    fn le_o_config_ini_distribuido() {
+        let config = ConfigESocial::de_ini(CONFIG_PADRAO).unwrap();
+
+        assert_eq!(config.principal.tipo_resposta, Some(TipoResposta::Ini));
+        assert_eq!(config.principal.log_nivel, Some(LogNivel::Nenhum));
+        assert_eq!(config.email.ssl_type, Some(SSLType::TLSv1_2));
+        assert_eq!(config.dfe.uf.as_deref(), Some("SP"));
+        assert_eq!(config.dfe.verificar_validade, Some(true));
+        assert_eq!(config.esocial.ambiente, Some(Ambiente::Homologacao));
+        assert_eq!(config.esocial.versao_df, Some(VersaoDF::S01_02_00));
+        assert_eq!(config.esocial.forma_emissao, Some(FormaEmissao::Normal));
+        assert_eq!(config.esocial.timeout, Some(5000));
+        assert_eq!(config.esocial.path_schemas, Some(PathBuf::new()));
+        assert_eq!(
+            config.esocial.formato_alerta.as_deref(),
+            Some("TAG:%TAGNIVEL% ID:%ID%/%TAG%(%DESCRICAO%) - %MSG%.")
+        );
+        // Campos de texto mantêm o valor vazio
+        assert_eq!(config.proxy.porta.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn texto_gerado_e_lido_de_volta() {
+        let config = ConfigESocial::de_ini(CONFIG_PADRAO).unwrap();
+
+        assert_eq!(ConfigESocial::de_ini(&config.para_ini()).unwrap(), config);
+    }
+
+    #[test]
+    fn grava_apenas_os_valores_definidos() {
+        let mut config = ConfigESocial::default();
+        config.esocial.ambiente = Some(Ambiente::Producao);
+        config.esocial.path_schemas = Some(PathBuf::from("schemas"));
+        config.principal.log_nivel = Some(LogNivel::Paranoico);
+
+        assert_eq!(
+            config.para_ini(),
+            "[Principal]\nLogNivel=4\n\n[eSocial]\nAmbiente=0\nPathSchemas=schemas\n"
+        );
+    }
+
+    #[test]
+    fn senhas_nao_aparecem_no_debug() {
+        let mut config = ConfigESocial::default();
+        config.dfe.senha = Some("senha-do-certificado".into());
+        config.dfe.uf = Some("SP".into());
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("senha-do-certificado"));
+        assert!(debug.contains("senha: \"***\""));
+        assert!(debug.contains("uf: Some(\"SP\")"));
+    }
+
+    #[test]
+    fn diferencas_consideram_apenas_os_valores_definidos() {
+        let efetiva = ConfigESocial::de_ini(CONFIG_PADRAO).unwrap();
+
+        let mut esperada = ConfigESocial::default();
+        esperada.esocial.ambiente = Some(Ambiente::Producao);
+        esperada.esocial.timeout = Some(5000);
+        esperada.esocial.path_schemas = Some(PathBuf::from("schemas"));
+        esperada.dfe.senha = Some("senha-do-certificado".into());
+
+        assert_eq!(
+            esperada.diferencas(&efetiva),
+            [
+                DiferencaConfig {
+                    secao: "DFe".into(),
+                    chave: "Senha".into(),
+                    esperado: "***".into(),
+                    efetivo: Some("***".into()),
+                },
+                DiferencaConfig {
+                    secao: "eSocial".into(),
+                    chave: "Ambiente".into(),
+                    esperado: "0".into(),
+                    efetivo: Some("1".into()),
+                },
+                DiferencaConfig {
+                    secao: "eSocial".into(),
+                    chave: "PathSchemas".into(),
+                    esperado: "schemas".into(),
+                    efetivo: Some("".into()),
+                },
+            ]
+        );
+        assert!(ConfigESocial::default().diferencas(&efetiva).is_empty());
+    }
+
+    #[test]
+    fn valor_efetivo_invalido_conta_como_diferenca() {
+        let mut esperada = ConfigESocial::default();
+        esperada.esocial.versao_df = Some(VersaoDF::S01_02_00);
+        esperada.esocial.tentativas = Some(5);
+
+        let efetiva = Ini::ler("[eSocial]\nVersaoDF=S01_02_00\n");
+        let diferencas = esperada.diferencas_ini(&efetiva);
+
+        assert_eq!(diferencas.len(), 2);
+        assert_eq!(diferencas[0].efetivo.as_deref(), Some("S01_02_00"));
+        assert_eq!(
+            diferencas[1].to_string(),
+            "[eSocial] Tentativas: esperado \"5\", ausente na configuração efetiva"
+        );
+    }
+
+    #[test]
+    fn valor_invalido_informa_sessao_e_chave() {
+        assert_eq!(
+            ConfigESocial::de_ini("[eSocial]\nAmbiente=7\n"),
+            Err(ACBrError::ConfigInvalida {
+                secao: "eSocial".into(),
+                chave: "Ambiente".into(),
+                valor: "7".into(),
+            })
+        );
+        // Chave vazia em um campo numérico fica como não definida
+        assert_eq!(
+            ConfigESocial::de_ini("[Email]\nPorta=\n")
+                .unwrap()
+                .email
+                .porta,
+            None
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::path::PathBuf;
+
+/// Conversão entre o valor tipado e o texto gravado no INI da ACBrLib.
+pub(crate) trait ValorConfig: Sized {
+    /// Tipos textuais aceitam valor vazio; nos demais, uma chave vazia é tratada como
+    /// não definida.
+    const TEXTO: bool = false;
+
+    fn para_ini(&self) -> String;
+
+    /// `None` quando o texto não é um valor válido para o tipo.
+    fn de_ini(valor: &str) -> Option<Self>;
+}
+
+impl ValorConfig for String {
+    const TEXTO: bool = true;
+
+    fn para_ini(&self) -> String {
+        self.clone()
+    }
+
+    fn de_ini(valor: &str) -> Option<Self> {
+        Some(valor.to_string())
+    }
+}
+
+impl ValorConfig for PathBuf {
+    const TEXTO: bool = true;
+
+    fn para_ini(&self) -> String {
+        self.display().to_string()
+    }
+
+    fn de_ini(valor: &str) -> Option<Self> {
+        Some(PathBuf::from(valor))
+    }
+}
+
+/// A ACBrLib grava `0` e `1`, mas o `TIniFile` também aceita `True` e `False`.
+impl ValorConfig for bool {
+    fn para_ini(&self) -> String {
+        if *self { "1" } else { "0" }.to_string()
+    }
+
+    fn de_ini(valor: &str) -> Option<Self> {
+        match valor {
+            "1" => Some(true),
+            "0" => Some(false),
+            valor if valor.eq_ignore_ascii_case("true") => Some(true),
+            valor if valor.eq_ignore_ascii_case("false") => Some(false),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! valor_numerico {
+    ($($tipo:ty),*) => {
+        $(
+            impl ValorConfig for $tipo {
+                fn para_ini(&self) -> String {
+                    self.to_string()
+                }
+
+                fn de_ini(valor: &str) -> Option<Self> {
+                    valor.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+valor_numerico!(i32, u32);
+
+/// Declara um enum gravado no INI pelo seu código inteiro.
+macro_rules! enum_config {
+    (
+        $(
+            $(#[$meta:meta])*
+            pub enum $nome:ident {
+                $($(#[$meta_variante:meta])* $variante:ident = $codigo:literal,)*
+            }
+        )*
+    ) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum $nome {
+                $($(#[$meta_variante])* $variante,)*
+            }
+
+            impl $nome {
+                /// Código gravado no INI.
+                pub fn codigo(self) -> i32 {
+                    match self {
+                        $($nome::$variante => $codigo,)*
+                    }
+                }
+
+                pub fn from_codigo(codigo: i32) -> Option<Self> {
+                    match codigo {
+                        $($codigo => Some($nome::$variante),)*
+                        _ => None,
+                    }
+                }
+            }
+
+            impl ValorConfig for $nome {
+                fn para_ini(&self) -> String {
+                    self.codigo().to_string()
+                }
+
+                fn de_ini(valor: &str) -> Option<Self> {
+                    Self::from_codigo(valor.parse().ok()?)
+                }
+            }
+        )*
+    };
+}
+
+enum_config! {
+    /// Formato das respostas da biblioteca (`[Principal] TipoResposta`).
+    pub enum TipoResposta {
+        Ini = 0,
+        Xml = 1,
+        Json = 2,
+    }
+
+    /// Codificação do texto das respostas (`[Principal] CodificacaoResposta`).
+    pub enum CodificacaoResposta {
+        Utf8 = 0,
+        /// Windows-1252.
+        Ansi = 1,
+    }
+
+    /// Nível de detalhe do log da biblioteca (`[Principal] LogNivel`).
+    pub enum LogNivel {
+        Nenhum = 0,
+        Simples = 1,
+        Normal = 2,
+        Completo = 3,
+        Paranoico = 4,
+    }
+
+    /// Protocolo das conexões seguras (`SSLType` em `[eSocial]` e `[Email]`).
+    pub enum SSLType {
+        Todos = 0,
+        SSLv2 = 1,
+        SSLv3 = 2,
+        TLSv1 = 3,
+        TLSv1_1 = 4,
+        TLSv1_2 = 5,
+        SSHv2 = 6,
+    }
+
+    /// Ambiente de envio (`[eSocial] Ambiente`).
+    pub enum Ambiente {
+        Producao = 0,
+        Homologacao = 1,
+    }
+
+    /// Versão dos leiautes do eSocial (`[eSocial] VersaoDF`).
+    pub enum VersaoDF {
+        V02_04_01 = 0,
+        V02_04_02 = 1,
+        V02_05_00 = 2,
+        S01_00_00 = 3,
+        S01_01_00 = 4,
+        S01_02_00 = 5,
+        S01_03_00 = 6,
+    }
+
+    /// Forma de emissão dos documentos (`[eSocial] FormaEmissao`).
+    pub enum FormaEmissao {
+        Normal = 0,
+        Contingencia = 1,
+        SCAN = 2,
+        DPEC = 3,
+        FSDA = 4,
+        SVCAN = 5,
+        SVCRS = 6,
+        SVCSP = 7,
+        OffLine = 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enums_usam_o_codigo_da_biblioteca() {
+        assert_eq!(Ambiente::Producao.para_ini(), "0");
+        assert_eq!(VersaoDF::de_ini("5"), Some(VersaoDF::S01_02_00));
+        assert_eq!(SSLType::from_codigo(5), Some(SSLType::TLSv1_2));
+        assert_eq!(TipoResposta::de_ini("3"), None);
+        assert_eq!(LogNivel::de_ini("x"), None);
+    }
+
+    #[test]
+    fn booleanos_aceitam_numero_ou_texto() {
+        assert_eq!(bool::de_ini("1"), Some(true));
+        assert_eq!(bool::de_ini("False"), Some(false));
+        assert_eq!(bool::de_ini("2"), None);
+        assert_eq!(true.para_ini(), "1");
+    }
+}
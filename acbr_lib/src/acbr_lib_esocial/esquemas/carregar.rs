@@ -0,0 +1,620 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use roxmltree::{Document, Node};
+
+use crate::error::ACBrError;
+
+use super::tipos::{padrao_xsd, Espacos, Facetas, Limite, Primitivo};
+
+/// Namespace dos elementos e tipos do XML Schema.
+pub(super) const XS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Nome qualificado; `ns` vazio para nomes sem namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct NomeQ {
+    pub ns: String,
+    pub local: String,
+}
+
+impl NomeQ {
+    pub fn new(ns: &str, local: &str) -> Self {
+        NomeQ {
+            ns: ns.to_string(),
+            local: local.to_string(),
+        }
+    }
+}
+
+/// Elementos globais e tipos nomeados de um esquema e de tudo o que ele inclui ou importa.
+#[derive(Debug, Default)]
+pub(super) struct Definicoes {
+    pub elementos: HashMap<NomeQ, DeclElemento>,
+    pub tipos: HashMap<NomeQ, Tipo>,
+}
+
+#[derive(Debug)]
+pub(super) struct DeclElemento {
+    pub nome: NomeQ,
+    pub tipo: RefTipo,
+}
+
+#[derive(Debug)]
+pub(super) enum RefTipo {
+    /// Tipo global, do esquema ou do XML Schema (`xs:string`, `xs:date`...).
+    Nomeado(NomeQ),
+    Anonimo(Box<Tipo>),
+    /// `xs:anyType`: o conteúdo não é validado.
+    Qualquer,
+}
+
+#[derive(Debug)]
+pub(super) enum Tipo {
+    Simples(TipoSimples),
+    Complexo(TipoComplexo),
+}
+
+/// Restrição de outro tipo simples pelas facetas.
+#[derive(Debug)]
+pub(super) struct TipoSimples {
+    pub base: RefTipo,
+    pub facetas: Facetas,
+}
+
+#[derive(Debug)]
+pub(super) struct TipoComplexo {
+    /// Tipo estendido por `xs:complexContent/xs:extension`, cujo conteúdo vem antes.
+    pub base: Option<NomeQ>,
+    pub conteudo: Conteudo,
+    pub atributos: Vec<DeclAtributo>,
+    pub misto: bool,
+}
+
+#[derive(Debug)]
+pub(super) enum Conteudo {
+    Vazio,
+    Elementos(Particula),
+    /// Texto do tipo simples informado (`xs:simpleContent`).
+    Simples(RefTipo),
+}
+
+#[derive(Debug)]
+pub(super) struct DeclAtributo {
+    pub nome: String,
+    pub tipo: RefTipo,
+    pub obrigatorio: bool,
+}
+
+#[derive(Debug)]
+pub(super) struct Particula {
+    pub min: u32,
+    /// `None` para `maxOccurs="unbounded"`.
+    pub max: Option<u32>,
+    pub termo: Termo,
+}
+
+#[derive(Debug)]
+pub(super) enum Termo {
+    Elemento(Box<DeclElemento>),
+    /// Elemento global (`ref`).
+    Referencia(NomeQ),
+    Sequencia(Vec<Particula>),
+    Escolha(Vec<Particula>),
+    Curinga(Curinga),
+}
+
+/// `xs:any`, com os namespaces aceitos.
+#[derive(Debug)]
+pub(super) enum Curinga {
+    Qualquer,
+    Outro(String),
+    Lista(Vec<String>),
+}
+
+impl Curinga {
+    pub fn aceita(&self, ns: &str) -> bool {
+        match self {
+            Curinga::Qualquer => true,
+            Curinga::Outro(destino) => !ns.is_empty() && ns != destino,
+            Curinga::Lista(lista) => lista.iter().any(|item| item == ns),
+        }
+    }
+}
+
+fn invalido(arquivo: &Path, mensagem: impl std::fmt::Display) -> ACBrError {
+    ACBrError::EsquemaInvalido(format!("{}: {}", arquivo.display(), mensagem))
+}
+
+/// Lê o esquema e os arquivos incluídos (`xs:include`) e importados (`xs:import`).
+pub(super) fn carregar(arquivo: &Path) -> Result<Definicoes, ACBrError> {
+    let mut carregador = Carregador {
+        definicoes: Definicoes::default(),
+        lidos: HashSet::new(),
+    };
+
+    carregador.ler_arquivo(arquivo, None)?;
+    carregador.definicoes.verificar(arquivo)?;
+
+    Ok(carregador.definicoes)
+}
+
+struct Carregador {
+    definicoes: Definicoes,
+    /// Arquivos já lidos, com o namespace em que foram lidos.
+    lidos: HashSet<(PathBuf, String)>,
+}
+
+/// Estado da leitura de um arquivo de esquema.
+struct Arquivo<'a> {
+    caminho: &'a Path,
+    destino: String,
+    /// Arquivo sem `targetNamespace` incluído em outro: os nomes sem prefixo passam a ser do
+    /// namespace de quem incluiu.
+    camaleao: bool,
+    qualificado: bool,
+}
+
+impl Carregador {
+    fn ler_arquivo(&mut self, caminho: &Path, incluido_em: Option<&str>) -> Result<(), ACBrError> {
+        let texto = fs::read_to_string(caminho).map_err(|erro| {
+            ACBrError::ArquivoNaoEncontrado(format!("{}: {}", caminho.display(), erro))
+        })?;
+        let documento = Document::parse(texto.trim_start_matches('\u{feff}'))
+            .map_err(|erro| invalido(caminho, erro))?;
+
+        let raiz = documento.root_element();
+        if !e_xs(raiz, "schema") {
+            return Err(invalido(caminho, "o elemento raiz não é xs:schema"));
+        }
+
+        let proprio = raiz.attribute("targetNamespace");
+        let destino = proprio.or(incluido_em).unwrap_or_default().to_string();
+
+        let chave = (caminho.to_path_buf(), destino.clone());
+        if !self.lidos.insert(chave) {
+            return Ok(());
+        }
+
+        let arquivo = Arquivo {
+            caminho,
+            camaleao: proprio.is_none() && incluido_em.is_some(),
+            qualificado: raiz.attribute("elementFormDefault") == Some("qualified"),
+            destino,
+        };
+
+        for filho in elementos_xs(raiz) {
+            match filho.tag_name().name() {
+                "include" | "import" => {
+                    let local = obrigatorio(&arquivo, filho, "schemaLocation")?;
+                    let outro = localizar(caminho, local);
+                    let incluido_em =
+                        (filho.tag_name().name() == "include").then_some(arquivo.destino.as_str());
+
+                    self.ler_arquivo(&outro, incluido_em)?;
+                }
+                "element" => {
+                    let decl = arquivo.elemento(filho, true)?;
+                    self.definicoes.elementos.insert(decl.nome.clone(), decl);
+                }
+                "simpleType" | "complexType" => {
+                    let nome = NomeQ::new(&arquivo.destino, obrigatorio(&arquivo, filho, "name")?);
+                    let tipo = arquivo.tipo(filho)?;
+                    self.definicoes.tipos.insert(nome, tipo);
+                }
+                "annotation" => {}
+                outro => return Err(nao_suportado(&arquivo, outro)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Arquivo referenciado por `schemaLocation`. Se o caminho relativo não existir, procura
+/// o arquivo de mesmo nome no diretório do esquema, onde os esquemas do eSocial ficam.
+fn localizar(caminho: &Path, local: &str) -> PathBuf {
+    let diretorio = caminho.parent().unwrap_or(Path::new(""));
+    let relativo = diretorio.join(local);
+
+    match Path::new(local).file_name() {
+        Some(nome) if !relativo.exists() => diretorio.join(nome),
+        _ => relativo,
+    }
+}
+
+fn e_xs(no: Node, nome: &str) -> bool {
+    no.tag_name().namespace() == Some(XS) && no.tag_name().name() == nome
+}
+
+/// Filhos do XML Schema, sem as anotações.
+fn elementos_xs<'a, 'b>(no: Node<'a, 'b>) -> impl Iterator<Item = Node<'a, 'b>> {
+    no.children()
+        .filter(|filho| filho.is_element() && filho.tag_name().namespace() == Some(XS))
+}
+
+fn conteudo_xs<'a, 'b>(no: Node<'a, 'b>) -> impl Iterator<Item = Node<'a, 'b>> {
+    elementos_xs(no).filter(|filho| filho.tag_name().name() != "annotation")
+}
+
+fn obrigatorio<'a>(
+    arquivo: &Arquivo,
+    no: Node<'a, '_>,
+    atributo: &str,
+) -> Result<&'a str, ACBrError> {
+    no.attribute(atributo).ok_or_else(|| {
+        invalido(
+            arquivo.caminho,
+            format!(
+                "xs:{} sem o atributo {} (linha {})",
+                no.tag_name().name(),
+                atributo,
+                posicao(no)
+            ),
+        )
+    })
+}
+
+fn nao_suportado(arquivo: &Arquivo, nome: &str) -> ACBrError {
+    invalido(arquivo.caminho, format!("xs:{} não é suportado", nome))
+}
+
+fn posicao(no: Node) -> u32 {
+    no.document().text_pos_at(no.range().start).row
+}
+
+impl Arquivo<'_> {
+    /// Nome qualificado de um atributo `type`, `base` ou `ref`.
+    fn nome(&self, no: Node, valor: &str) -> Result<NomeQ, ACBrError> {
+        let (prefixo, local) = match valor.split_once(':') {
+            Some((prefixo, local)) => (Some(prefixo), local),
+            None => (None, valor),
+        };
+
+        let ns = match no.lookup_namespace_uri(prefixo) {
+            Some(ns) => ns,
+            None if prefixo.is_none() => "",
+            None => {
+                return Err(invalido(
+                    self.caminho,
+                    format!(
+                        "prefixo de '{}' não declarado (linha {})",
+                        valor,
+                        posicao(no)
+                    ),
+                ))
+            }
+        };
+
+        let ns = if ns.is_empty() && self.camaleao {
+            &self.destino
+        } else {
+            ns
+        };
+
+        Ok(NomeQ::new(ns, local))
+    }
+
+    fn elemento(&self, no: Node, global: bool) -> Result<DeclElemento, ACBrError> {
+        let local = obrigatorio(self, no, "name")?;
+        let ns = if global || self.qualificado {
+            self.destino.as_str()
+        } else {
+            ""
+        };
+
+        Ok(DeclElemento {
+            nome: NomeQ::new(ns, local),
+            tipo: self.ref_tipo(no, "type")?,
+        })
+    }
+
+    /// Tipo do atributo informado ou, sem ele, o tipo anônimo declarado dentro do nó.
+    fn ref_tipo(&self, no: Node, atributo: &str) -> Result<RefTipo, ACBrError> {
+        if let Some(valor) = no.attribute(atributo) {
+            return self.nome(no, valor).map(RefTipo::Nomeado);
+        }
+
+        match conteudo_xs(no)
+            .find(|filho| matches!(filho.tag_name().name(), "simpleType" | "complexType"))
+        {
+            Some(tipo) => Ok(RefTipo::Anonimo(Box::new(self.tipo(tipo)?))),
+            None => Ok(RefTipo::Qualquer),
+        }
+    }
+
+    fn tipo(&self, no: Node) -> Result<Tipo, ACBrError> {
+        if no.tag_name().name() == "simpleType" {
+            return self.tipo_simples(no).map(Tipo::Simples);
+        }
+
+        let mut tipo = TipoComplexo {
+            base: None,
+            conteudo: Conteudo::Vazio,
+            atributos: Vec::new(),
+            misto: no.attribute("mixed") == Some("true"),
+        };
+
+        for filho in conteudo_xs(no) {
+            match filho.tag_name().name() {
+                "sequence" | "choice" | "any" | "element" => {
+                    tipo.conteudo = Conteudo::Elementos(self.particula(filho)?)
+                }
+                "attribute" => tipo.atributos.push(self.atributo(filho)?),
+                "simpleContent" | "complexContent" => self.extensao(filho, &mut tipo)?,
+                outro => return Err(nao_suportado(self, outro)),
+            }
+        }
+
+        Ok(Tipo::Complexo(tipo))
+    }
+
+    fn extensao(&self, no: Node, tipo: &mut TipoComplexo) -> Result<(), ACBrError> {
+        let extensao = conteudo_xs(no)
+            .next()
+            .filter(|filho| e_xs(*filho, "extension"))
+            .ok_or_else(|| nao_suportado(self, &format!("{}/restriction", no.tag_name().name())))?;
+
+        let base = self.nome(extensao, obrigatorio(self, extensao, "base")?)?;
+
+        if no.tag_name().name() == "simpleContent" {
+            tipo.conteudo = Conteudo::Simples(RefTipo::Nomeado(base));
+        } else {
+            tipo.base = Some(base);
+            tipo.misto |= no.attribute("mixed") == Some("true");
+        }
+
+        for filho in conteudo_xs(extensao) {
+            match filho.tag_name().name() {
+                "sequence" | "choice" => {
+                    tipo.conteudo = Conteudo::Elementos(self.particula(filho)?)
+                }
+                "attribute" => tipo.atributos.push(self.atributo(filho)?),
+                outro => return Err(nao_suportado(self, outro)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn atributo(&self, no: Node) -> Result<DeclAtributo, ACBrError> {
+        let tipo = match self.ref_tipo(no, "type")? {
+            RefTipo::Qualquer => RefTipo::Nomeado(NomeQ::new(XS, "anySimpleType")),
+            tipo => tipo,
+        };
+
+        Ok(DeclAtributo {
+            nome: obrigatorio(self, no, "name")?.to_string(),
+            tipo,
+            obrigatorio: no.attribute("use") == Some("required"),
+        })
+    }
+
+    fn particula(&self, no: Node) -> Result<Particula, ACBrError> {
+        let ocorrencias = |atributo: &str, padrao: u32| -> Result<Option<u32>, ACBrError> {
+            match no.attribute(atributo) {
+                None => Ok(Some(padrao)),
+                Some("unbounded") => Ok(None),
+                Some(valor) => valor.parse().map(Some).map_err(|_| {
+                    invalido(
+                        self.caminho,
+                        format!(
+                            "{}=\"{}\" inválido (linha {})",
+                            atributo,
+                            valor,
+                            posicao(no)
+                        ),
+                    )
+                }),
+            }
+        };
+
+        let termo = match no.tag_name().name() {
+            "element" => match no.attribute("ref") {
+                Some(referencia) => Termo::Referencia(self.nome(no, referencia)?),
+                None => Termo::Elemento(Box::new(self.elemento(no, false)?)),
+            },
+            "sequence" => Termo::Sequencia(self.particulas(no)?),
+            "choice" => Termo::Escolha(self.particulas(no)?),
+            "any" => Termo::Curinga(match no.attribute("namespace").unwrap_or("##any") {
+                "##any" => Curinga::Qualquer,
+                "##other" => Curinga::Outro(self.destino.clone()),
+                lista => Curinga::Lista(
+                    lista
+                        .split_whitespace()
+                        .map(|ns| match ns {
+                            "##targetNamespace" => self.destino.clone(),
+                            "##local" => String::new(),
+                            ns => ns.to_string(),
+                        })
+                        .collect(),
+                ),
+            }),
+            outro => return Err(nao_suportado(self, outro)),
+        };
+
+        Ok(Particula {
+            min: ocorrencias("minOccurs", 1)?.unwrap_or_default(),
+            max: ocorrencias("maxOccurs", 1)?,
+            termo,
+        })
+    }
+
+    fn particulas(&self, no: Node) -> Result<Vec<Particula>, ACBrError> {
+        conteudo_xs(no).map(|filho| self.particula(filho)).collect()
+    }
+
+    fn tipo_simples(&self, no: Node) -> Result<TipoSimples, ACBrError> {
+        let restricao = conteudo_xs(no)
+            .next()
+            .filter(|filho| e_xs(*filho, "restriction"))
+            .ok_or_else(|| nao_suportado(self, "simpleType sem restriction"))?;
+
+        let mut facetas = Facetas::default();
+        let mut padroes = Vec::new();
+
+        for faceta in conteudo_xs(restricao) {
+            let nome = faceta.tag_name().name();
+            if nome == "simpleType" {
+                continue;
+            }
+
+            let valor = obrigatorio(self, faceta, "value")?;
+            let numero = || {
+                valor.parse::<usize>().map_err(|_| {
+                    invalido(
+                        self.caminho,
+                        format!("xs:{} com valor inválido (linha {})", nome, posicao(faceta)),
+                    )
+                })
+            };
+
+            match nome {
+                "enumeration" => facetas
+                    .enumeracao
+                    .get_or_insert_with(Vec::new)
+                    .push(valor.to_string()),
+                "pattern" => padroes.push(valor.to_string()),
+                "length" => facetas.comprimento = Some(numero()?),
+                "minLength" => facetas.comprimento_min = Some(numero()?),
+                "maxLength" => facetas.comprimento_max = Some(numero()?),
+                "totalDigits" => facetas.digitos = Some(numero()?),
+                "fractionDigits" => facetas.casas_decimais = Some(numero()?),
+                "minInclusive" => facetas.minimo = Some(Limite::inclusivo(valor)),
+                "minExclusive" => facetas.minimo = Some(Limite::exclusivo(valor)),
+                "maxInclusive" => facetas.maximo = Some(Limite::inclusivo(valor)),
+                "maxExclusive" => facetas.maximo = Some(Limite::exclusivo(valor)),
+                "whiteSpace" => {
+                    facetas.espacos = Some(Espacos::de_valor(valor).ok_or_else(|| {
+                        invalido(
+                            self.caminho,
+                            format!("xs:whiteSpace=\"{}\" inválido", valor),
+                        )
+                    })?)
+                }
+                outro => return Err(nao_suportado(self, outro)),
+            }
+        }
+
+        // Os padrões da mesma restrição são alternativas
+        if !padroes.is_empty() {
+            let expressao = padroes
+                .iter()
+                .map(|padrao| padrao_xsd(padrao))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|erro| invalido(self.caminho, erro))?
+                .join("|");
+
+            facetas.padrao = Some((
+                padroes.join(" | "),
+                compilar(&format!("^(?:{})$", expressao))
+                    .map_err(|erro| invalido(self.caminho, erro))?,
+            ));
+        }
+
+        Ok(TipoSimples {
+            base: self.ref_tipo(restricao, "base")?,
+            facetas,
+        })
+    }
+}
+
+lazy_static! {
+    /// Expressões já compiladas. Os esquemas dos eventos incluem o mesmo `tipos.xsd`, e
+    /// compilar os padrões dele é a maior parte do tempo de leitura de cada esquema.
+    static ref PADROES: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
+}
+
+/// Compila fora do lock: duas threads podem compilar a mesma expressão, mas nenhuma
+/// espera pela compilação de outra.
+fn compilar(expressao: &str) -> Result<Regex, regex::Error> {
+    let padroes = || PADROES.lock().unwrap_or_else(|erro| erro.into_inner());
+
+    if let Some(regex) = padroes().get(expressao) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Regex::new(expressao)?;
+    padroes().insert(expressao.to_string(), regex.clone());
+    Ok(regex)
+}
+
+impl Definicoes {
+    /// Confere se todos os tipos e elementos referenciados existem, para que o erro apareça
+    /// ao carregar o esquema e não na validação.
+    fn verificar(&self, arquivo: &Path) -> Result<(), ACBrError> {
+        let tipos = self.tipos.values();
+        let elementos = self.elementos.values().map(|decl| &decl.tipo);
+
+        for tipo in tipos {
+            self.verificar_tipo(tipo, arquivo)?;
+        }
+        for tipo in elementos {
+            self.verificar_ref(tipo, arquivo)?;
+        }
+
+        Ok(())
+    }
+
+    fn verificar_ref(&self, tipo: &RefTipo, arquivo: &Path) -> Result<(), ACBrError> {
+        match tipo {
+            RefTipo::Nomeado(nome) if nome.ns == XS => match Primitivo::de_nome(&nome.local) {
+                Some(_) => Ok(()),
+                None if nome.local == "anyType" => Ok(()),
+                None => Err(invalido(
+                    arquivo,
+                    format!("tipo xs:{} não suportado", nome.local),
+                )),
+            },
+            RefTipo::Nomeado(nome) if !self.tipos.contains_key(nome) => Err(invalido(
+                arquivo,
+                format!("tipo {} não encontrado", nome.local),
+            )),
+            RefTipo::Nomeado(_) | RefTipo::Qualquer => Ok(()),
+            RefTipo::Anonimo(tipo) => self.verificar_tipo(tipo, arquivo),
+        }
+    }
+
+    fn verificar_tipo(&self, tipo: &Tipo, arquivo: &Path) -> Result<(), ACBrError> {
+        match tipo {
+            Tipo::Simples(simples) => self.verificar_ref(&simples.base, arquivo),
+            Tipo::Complexo(complexo) => {
+                if let Some(base) = &complexo.base {
+                    if !matches!(self.tipos.get(base), Some(Tipo::Complexo(_))) {
+                        return Err(invalido(
+                            arquivo,
+                            format!("tipo complexo {} não encontrado", base.local),
+                        ));
+                    }
+                }
+
+                for atributo in &complexo.atributos {
+                    self.verificar_ref(&atributo.tipo, arquivo)?;
+                }
+
+                match &complexo.conteudo {
+                    Conteudo::Vazio => Ok(()),
+                    Conteudo::Elementos(particula) => self.verificar_particula(particula, arquivo),
+                    Conteudo::Simples(tipo) => self.verificar_ref(tipo, arquivo),
+                }
+            }
+        }
+    }
+
+    fn verificar_particula(&self, particula: &Particula, arquivo: &Path) -> Result<(), ACBrError> {
+        match &particula.termo {
+            Termo::Elemento(decl) => self.verificar_ref(&decl.tipo, arquivo),
+            Termo::Referencia(nome) if !self.elementos.contains_key(nome) => Err(invalido(
+                arquivo,
+                format!("elemento {} não encontrado", nome.local),
+            )),
+            Termo::Sequencia(particulas) | Termo::Escolha(particulas) => particulas
+                .iter()
+                .try_for_each(|particula| self.verificar_particula(particula, arquivo)),
+            Termo::Referencia(_) | Termo::Curinga(_) => Ok(()),
+        }
+    }
+}
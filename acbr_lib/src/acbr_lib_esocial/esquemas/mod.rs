@@ -0,0 +1,211 @@
+//! Validação dos eventos com os esquemas XSD do eSocial, sem passar pela biblioteca.
+//!
+//! Os esquemas de `resources/temp/schemas` são lidos com os arquivos incluídos
+//! (`tipos.xsd`) e importados (`xmldsig-core-schema.xsd`). É suportado o subconjunto do XML
+//! Schema usado por eles: elementos e tipos globais ou anônimos, `sequence`, `choice`,
+//! `any`, extensão de tipos complexos, restrição de tipos simples, atributos e as facetas
+//! de valores (`enumeration`, `pattern`, tamanhos, dígitos e limites). Construções fora
+//! desse subconjunto, como a restrição de tipos complexos, são recusadas na leitura com
+//! `ACBrError::EsquemaInvalido`.
+//!
+//! O `tipos.xsd` distribuído é o do leiaute atual e não tem alguns tipos usados por
+//! leiautes antigos: os esquemas de `evtBasesTrab` e `evtBasesFGTS` das versões
+//! `v_S_01_00_00` a `v_S_01_02_00` (`T_ideEvento_retorno_trab`) e o `evtFGTSProcTrab.xsd`
+//! (`TS_matricula_retorno`) não podem ser lidos, e `ValidadorEsquemas::validar` devolve
+//! `Err(ACBrError::EsquemaInvalido)` para os namespaces deles.
+
+mod carregar;
+mod tipos;
+mod validar;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use roxmltree::Document;
+
+use crate::error::ACBrError;
+
+use carregar::Definicoes;
+
+/// Problema encontrado ao validar um XML com o esquema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErroEsquema {
+    /// Linha do XML, a partir de 1.
+    pub linha: u32,
+    /// Coluna do XML, a partir de 1.
+    pub coluna: u32,
+    /// Caminho do elemento ou atributo, como `/eSocial/evtInfoEmpregador/@Id`.
+    pub caminho: String,
+    pub mensagem: String,
+}
+
+impl fmt::Display for ErroEsquema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (linha {}, coluna {}): {}",
+            self.caminho, self.linha, self.coluna, self.mensagem
+        )
+    }
+}
+
+/// Esquema XSD já lido, com os arquivos incluídos e importados.
+#[derive(Debug)]
+pub struct Esquema {
+    definicoes: Definicoes,
+}
+
+impl Esquema {
+    /// Lê o esquema do arquivo. Os arquivos incluídos e importados são procurados pelo
+    /// `schemaLocation`, relativo ao arquivo, e depois pelo nome na mesma pasta.
+    pub fn carregar(arquivo: impl AsRef<Path>) -> Result<Self, ACBrError> {
+        Ok(Esquema {
+            definicoes: carregar::carregar(arquivo.as_ref())?,
+        })
+    }
+
+    /// Valida o XML e devolve todos os problemas encontrados; um XML mal formado gera um
+    /// único erro, na posição em que a leitura parou.
+    pub fn validar(&self, xml: &str) -> Vec<ErroEsquema> {
+        match ler_xml(xml) {
+            Ok(documento) => self.validar_documento(&documento),
+            Err(erro) => vec![erro],
+        }
+    }
+
+    fn validar_documento(&self, documento: &Document) -> Vec<ErroEsquema> {
+        validar::validar(&self.definicoes, documento.root_element())
+    }
+}
+
+fn ler_xml(xml: &str) -> Result<Document<'_>, ErroEsquema> {
+    Document::parse(xml).map_err(|erro| ErroEsquema {
+        linha: erro.pos().row,
+        coluna: erro.pos().col,
+        caminho: String::new(),
+        mensagem: format!("XML mal formado: {}", erro),
+    })
+}
+
+/// Valida os eventos com o esquema da pasta escolhido pelo namespace do elemento raiz,
+/// como `http://www.esocial.gov.br/schema/evt/evtAdmissao/v_S_01_03_00`.
+///
+/// Cada esquema é lido na primeira vez em que é usado e fica guardado para as próximas
+/// validações. O validador pode ser compartilhado entre threads; enquanto um esquema é
+/// lido, só as validações do mesmo namespace aguardam.
+#[derive(Debug)]
+pub struct ValidadorEsquemas {
+    esquemas: HashMap<String, EsquemaIndexado>,
+}
+
+/// Arquivo do esquema de um namespace e o esquema, depois de lido. Cada namespace tem o
+/// seu lock, para que a leitura de um esquema não atrase as validações dos outros.
+#[derive(Debug)]
+struct EsquemaIndexado {
+    arquivo: PathBuf,
+    lido: Mutex<Option<Arc<Esquema>>>,
+}
+
+impl ValidadorEsquemas {
+    /// Indexa os arquivos `.xsd` da pasta pelo `targetNamespace`. Quando mais de um arquivo
+    /// declara o mesmo namespace, vale o primeiro em ordem alfabética.
+    pub fn new(diretorio: impl AsRef<Path>) -> Result<Self, ACBrError> {
+        let diretorio = diretorio.as_ref();
+        let erro = |erro: std::io::Error| {
+            ACBrError::DiretorioInvalido(format!("{}: {}", diretorio.display(), erro))
+        };
+
+        let mut caminhos = Vec::new();
+        for entrada in fs::read_dir(diretorio).map_err(erro)? {
+            let caminho = entrada.map_err(erro)?.path();
+            if caminho
+                .extension()
+                .is_some_and(|extensao| extensao == "xsd")
+            {
+                caminhos.push(caminho);
+            }
+        }
+        caminhos.sort();
+
+        let mut esquemas = HashMap::new();
+        for caminho in caminhos {
+            let texto = fs::read_to_string(&caminho).map_err(|erro| {
+                ACBrError::ArquivoNaoEncontrado(format!("{}: {}", caminho.display(), erro))
+            })?;
+
+            if let Some(namespace) = namespace_destino(&texto) {
+                esquemas
+                    .entry(namespace.to_string())
+                    .or_insert_with(|| EsquemaIndexado {
+                        arquivo: caminho,
+                        lido: Mutex::new(None),
+                    });
+            }
+        }
+
+        Ok(ValidadorEsquemas { esquemas })
+    }
+
+    /// Valida o evento com o esquema do namespace dele. Um namespace sem esquema na pasta
+    /// é devolvido como erro de validação; `Err` só acontece quando o esquema não pode ser
+    /// lido.
+    pub fn validar(&self, xml: &str) -> Result<Vec<ErroEsquema>, ACBrError> {
+        let documento = match ler_xml(xml) {
+            Ok(documento) => documento,
+            Err(erro) => return Ok(vec![erro]),
+        };
+
+        let raiz = documento.root_element();
+        let namespace = raiz.tag_name().namespace().unwrap_or_default();
+
+        match self.esquema(namespace)? {
+            Some(esquema) => Ok(esquema.validar_documento(&documento)),
+            None => {
+                let posicao = documento.text_pos_at(raiz.range().start);
+
+                Ok(vec![ErroEsquema {
+                    linha: posicao.row,
+                    coluna: posicao.col,
+                    caminho: format!("/{}", raiz.tag_name().name()),
+                    mensagem: format!("nenhum esquema para o namespace '{}'", namespace),
+                }])
+            }
+        }
+    }
+
+    /// Esquema do namespace, lido na primeira vez.
+    pub fn esquema(&self, namespace: &str) -> Result<Option<Arc<Esquema>>, ACBrError> {
+        let Some(indexado) = self.esquemas.get(namespace) else {
+            return Ok(None);
+        };
+
+        let mut lido = indexado
+            .lido
+            .lock()
+            .unwrap_or_else(|erro| erro.into_inner());
+        if let Some(esquema) = lido.as_ref() {
+            return Ok(Some(Arc::clone(esquema)));
+        }
+
+        let esquema = Arc::new(Esquema::carregar(&indexado.arquivo)?);
+        *lido = Some(Arc::clone(&esquema));
+
+        Ok(Some(esquema))
+    }
+}
+
+/// `targetNamespace` do elemento `xs:schema`, procurado no texto para não ler o arquivo
+/// inteiro como XML só para indexar.
+fn namespace_destino(texto: &str) -> Option<&str> {
+    let inicio = texto.find("targetNamespace=")? + "targetNamespace=".len();
+    let aspas = texto[inicio..]
+        .chars()
+        .next()
+        .filter(|c| *c == '"' || *c == '\'')?;
+    let resto = &texto[inicio + 1..];
+
+    resto.find(aspas).map(|fim| &resto[..fim])
+}
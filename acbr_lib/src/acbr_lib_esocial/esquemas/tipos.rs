@@ -0,0 +1,700 @@
+use std::cmp::Ordering;
+
+use regex::Regex;
+
+/// Tipos simples do XML Schema usados pelos esquemas do eSocial e da assinatura.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Primitivo {
+    Texto,
+    /// `normalizedString` e `token`, que não preservam os espaços.
+    TextoNormalizado,
+    Decimal,
+    /// `integer` e os tipos derivados dele, com os limites de cada um.
+    Inteiro {
+        minimo: Option<i128>,
+        maximo: Option<i128>,
+    },
+    Booleano,
+    Data,
+    DataHora,
+    Hora,
+    AnoMes,
+    Ano,
+    Base64,
+    Uri,
+    /// `ID`, `IDREF` e `NCName`.
+    NomeSimples,
+}
+
+impl Primitivo {
+    pub fn de_nome(nome: &str) -> Option<Self> {
+        let inteiro = |minimo, maximo| Primitivo::Inteiro { minimo, maximo };
+
+        Some(match nome {
+            "string" | "anySimpleType" => Primitivo::Texto,
+            "normalizedString" | "token" => Primitivo::TextoNormalizado,
+            "decimal" => Primitivo::Decimal,
+            "integer" => inteiro(None, None),
+            "long" => inteiro(Some(i64::MIN.into()), Some(i64::MAX.into())),
+            "int" => inteiro(Some(i32::MIN.into()), Some(i32::MAX.into())),
+            "short" => inteiro(Some(i16::MIN.into()), Some(i16::MAX.into())),
+            "byte" => inteiro(Some(i8::MIN.into()), Some(i8::MAX.into())),
+            "nonNegativeInteger" => inteiro(Some(0), None),
+            "positiveInteger" => inteiro(Some(1), None),
+            "nonPositiveInteger" => inteiro(None, Some(0)),
+            "negativeInteger" => inteiro(None, Some(-1)),
+            "unsignedLong" => inteiro(Some(0), Some(u64::MAX.into())),
+            "unsignedInt" => inteiro(Some(0), Some(u32::MAX.into())),
+            "unsignedShort" => inteiro(Some(0), Some(u16::MAX.into())),
+            "unsignedByte" => inteiro(Some(0), Some(u8::MAX.into())),
+            "boolean" => Primitivo::Booleano,
+            "date" => Primitivo::Data,
+            "dateTime" => Primitivo::DataHora,
+            "time" => Primitivo::Hora,
+            "gYearMonth" => Primitivo::AnoMes,
+            "gYear" => Primitivo::Ano,
+            "base64Binary" => Primitivo::Base64,
+            "anyURI" => Primitivo::Uri,
+            "ID" | "IDREF" | "NCName" => Primitivo::NomeSimples,
+            _ => return None,
+        })
+    }
+
+    pub fn espacos(self) -> Espacos {
+        match self {
+            Primitivo::Texto => Espacos::Preservar,
+            _ => Espacos::Colapsar,
+        }
+    }
+
+    /// Confere a forma do valor, já com os espaços normalizados.
+    pub fn verificar(self, valor: &str) -> Result<(), String> {
+        let valido = match self {
+            Primitivo::Texto | Primitivo::TextoNormalizado | Primitivo::Uri => true,
+            Primitivo::Decimal => Decimal::ler(valor).is_some(),
+            Primitivo::Inteiro { minimo, maximo } => {
+                return match inteiro(valor) {
+                    Some(numero) if minimo.is_some_and(|minimo| numero < minimo) => Err(format!(
+                        "o valor '{}' é menor que o mínimo do tipo ({})",
+                        valor,
+                        minimo.unwrap_or_default()
+                    )),
+                    Some(numero) if maximo.is_some_and(|maximo| numero > maximo) => Err(format!(
+                        "o valor '{}' é maior que o máximo do tipo ({})",
+                        valor,
+                        maximo.unwrap_or_default()
+                    )),
+                    Some(_) => Ok(()),
+                    None => Err(format!("o valor '{}' não é um número inteiro", valor)),
+                };
+            }
+            Primitivo::Booleano => matches!(valor, "true" | "false" | "1" | "0"),
+            Primitivo::Data
+            | Primitivo::DataHora
+            | Primitivo::Hora
+            | Primitivo::AnoMes
+            | Primitivo::Ano => Momento::ler(self, valor).is_some(),
+            Primitivo::Base64 => base64(valor),
+            Primitivo::NomeSimples => nome_simples(valor),
+        };
+
+        if valido {
+            Ok(())
+        } else {
+            Err(format!("o valor '{}' não é {}", valor, self.descricao()))
+        }
+    }
+
+    fn descricao(self) -> &'static str {
+        match self {
+            Primitivo::Decimal => "um número decimal",
+            Primitivo::Booleano => "um booleano",
+            Primitivo::Data => "uma data (AAAA-MM-DD)",
+            Primitivo::DataHora => "uma data e hora (AAAA-MM-DDThh:mm:ss)",
+            Primitivo::Hora => "uma hora (hh:mm:ss)",
+            Primitivo::AnoMes => "um mês (AAAA-MM)",
+            Primitivo::Ano => "um ano (AAAA)",
+            Primitivo::Base64 => "um texto em base64",
+            Primitivo::NomeSimples => "um identificador XML",
+            Primitivo::Texto
+            | Primitivo::TextoNormalizado
+            | Primitivo::Uri
+            | Primitivo::Inteiro { .. } => "válido",
+        }
+    }
+
+    /// Compara dois valores do tipo, para os limites `minInclusive` e afins.
+    fn comparar(self, a: &str, b: &str) -> Option<Ordering> {
+        match self {
+            Primitivo::Decimal | Primitivo::Inteiro { .. } => {
+                Some(Decimal::ler(a)?.cmp(&Decimal::ler(b)?))
+            }
+            Primitivo::Data
+            | Primitivo::DataHora
+            | Primitivo::Hora
+            | Primitivo::AnoMes
+            | Primitivo::Ano => Some(Momento::ler(self, a)?.cmp(&Momento::ler(self, b)?)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Espacos {
+    Preservar,
+    Substituir,
+    Colapsar,
+}
+
+impl Espacos {
+    pub fn de_valor(valor: &str) -> Option<Self> {
+        match valor {
+            "preserve" => Some(Espacos::Preservar),
+            "replace" => Some(Espacos::Substituir),
+            "collapse" => Some(Espacos::Colapsar),
+            _ => None,
+        }
+    }
+
+    pub fn normalizar(self, valor: &str) -> String {
+        let substituido = || valor.replace(['\t', '\n', '\r'], " ");
+
+        match self {
+            Espacos::Preservar => valor.to_string(),
+            Espacos::Substituir => substituido(),
+            Espacos::Colapsar => substituido()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// Limite de `minInclusive`, `minExclusive`, `maxInclusive` ou `maxExclusive`.
+#[derive(Debug)]
+pub(super) struct Limite {
+    valor: String,
+    inclusivo: bool,
+}
+
+impl Limite {
+    pub fn inclusivo(valor: &str) -> Self {
+        Limite {
+            valor: valor.to_string(),
+            inclusivo: true,
+        }
+    }
+
+    pub fn exclusivo(valor: &str) -> Self {
+        Limite {
+            valor: valor.to_string(),
+            inclusivo: false,
+        }
+    }
+
+    fn aceita_igual(&self, primitivo: Primitivo, valor: &str) -> bool {
+        self.inclusivo || primitivo.comparar(valor, &self.valor) != Some(Ordering::Equal)
+    }
+}
+
+/// Facetas de uma restrição (`xs:restriction`).
+#[derive(Debug, Default)]
+pub(super) struct Facetas {
+    pub enumeracao: Option<Vec<String>>,
+    /// Padrões como no esquema e a expressão equivalente.
+    pub padrao: Option<(String, Regex)>,
+    pub comprimento: Option<usize>,
+    pub comprimento_min: Option<usize>,
+    pub comprimento_max: Option<usize>,
+    pub digitos: Option<usize>,
+    pub casas_decimais: Option<usize>,
+    pub minimo: Option<Limite>,
+    pub maximo: Option<Limite>,
+    pub espacos: Option<Espacos>,
+}
+
+/// Quantidade de valores da enumeração mostrados na mensagem de erro.
+const ENUMERACAO_NA_MENSAGEM: usize = 10;
+
+impl Facetas {
+    pub fn verificar(&self, primitivo: Primitivo, valor: &str) -> Result<(), String> {
+        if let Some(enumeracao) = &self.enumeracao {
+            if !enumeracao.iter().any(|item| item == valor) {
+                let mut permitidos = enumeracao
+                    .iter()
+                    .take(ENUMERACAO_NA_MENSAGEM)
+                    .map(|item| format!("'{}'", item))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if enumeracao.len() > ENUMERACAO_NA_MENSAGEM {
+                    permitidos.push_str(", ...");
+                }
+
+                return Err(format!(
+                    "o valor '{}' não está entre os permitidos: {}",
+                    valor, permitidos
+                ));
+            }
+        }
+
+        if let Some((padrao, expressao)) = &self.padrao {
+            if !expressao.is_match(valor) {
+                return Err(format!(
+                    "o valor '{}' não segue o padrão '{}'",
+                    valor, padrao
+                ));
+            }
+        }
+
+        let tamanho = valor.chars().count();
+        if let Some(comprimento) = self.comprimento.filter(|&c| tamanho != c) {
+            return Err(format!(
+                "o valor '{}' deve ter {} caracteres",
+                valor, comprimento
+            ));
+        }
+        if let Some(minimo) = self.comprimento_min.filter(|&c| tamanho < c) {
+            return Err(format!(
+                "o valor '{}' deve ter no mínimo {} caracteres",
+                valor, minimo
+            ));
+        }
+        if let Some(maximo) = self.comprimento_max.filter(|&c| tamanho > c) {
+            return Err(format!(
+                "o valor '{}' deve ter no máximo {} caracteres",
+                valor, maximo
+            ));
+        }
+
+        if self.digitos.is_some() || self.casas_decimais.is_some() {
+            if let Some(decimal) = Decimal::ler(valor) {
+                if let Some(digitos) = self.digitos.filter(|&d| decimal.digitos() > d) {
+                    return Err(format!(
+                        "o valor '{}' tem mais de {} dígitos",
+                        valor, digitos
+                    ));
+                }
+                if let Some(casas) = self.casas_decimais.filter(|&c| decimal.fracao.len() > c) {
+                    return Err(format!(
+                        "o valor '{}' tem mais de {} casas decimais",
+                        valor, casas
+                    ));
+                }
+            }
+        }
+
+        if let Some(limite) = &self.minimo {
+            if primitivo.comparar(valor, &limite.valor) == Some(Ordering::Less)
+                || !limite.aceita_igual(primitivo, valor)
+            {
+                let relacao = if limite.inclusivo {
+                    "maior ou igual a"
+                } else {
+                    "maior que"
+                };
+                return Err(format!(
+                    "o valor '{}' deve ser {} {}",
+                    valor, relacao, limite.valor
+                ));
+            }
+        }
+
+        if let Some(limite) = &self.maximo {
+            if primitivo.comparar(valor, &limite.valor) == Some(Ordering::Greater)
+                || !limite.aceita_igual(primitivo, valor)
+            {
+                let relacao = if limite.inclusivo {
+                    "menor ou igual a"
+                } else {
+                    "menor que"
+                };
+                return Err(format!(
+                    "o valor '{}' deve ser {} {}",
+                    valor, relacao, limite.valor
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Número decimal exato, para comparar os limites sem perder precisão.
+#[derive(Debug, PartialEq, Eq)]
+struct Decimal {
+    negativo: bool,
+    /// Parte inteira sem zeros à esquerda.
+    inteiro: String,
+    /// Parte fracionária sem zeros à direita.
+    fracao: String,
+}
+
+impl Decimal {
+    fn ler(valor: &str) -> Option<Self> {
+        let (negativo, numero) = match valor.as_bytes().first()? {
+            b'-' => (true, &valor[1..]),
+            b'+' => (false, &valor[1..]),
+            _ => (false, valor),
+        };
+
+        let (inteiro, fracao) = numero.split_once('.').unwrap_or((numero, ""));
+        let digitos = |parte: &str| parte.bytes().all(|b| b.is_ascii_digit());
+
+        if (inteiro.is_empty() && fracao.is_empty()) || !digitos(inteiro) || !digitos(fracao) {
+            return None;
+        }
+
+        let inteiro = inteiro.trim_start_matches('0').to_string();
+        let fracao = fracao.trim_end_matches('0').to_string();
+        let zero = inteiro.is_empty() && fracao.is_empty();
+
+        Some(Decimal {
+            negativo: negativo && !zero,
+            inteiro,
+            fracao,
+        })
+    }
+
+    /// Dígitos significativos, como em `totalDigits`.
+    fn digitos(&self) -> usize {
+        if self.inteiro.is_empty() {
+            self.fracao.trim_start_matches('0').len().max(1)
+        } else {
+            self.inteiro.len() + self.fracao.len()
+        }
+    }
+
+    fn cmp_absoluto(&self, outro: &Self) -> Ordering {
+        self.inteiro
+            .len()
+            .cmp(&outro.inteiro.len())
+            .then_with(|| self.inteiro.cmp(&outro.inteiro))
+            .then_with(|| self.fracao.cmp(&outro.fracao))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, outro: &Self) -> Ordering {
+        match (self.negativo, outro.negativo) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_absoluto(outro),
+            (true, true) => outro.cmp_absoluto(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
+        Some(self.cmp(outro))
+    }
+}
+
+fn inteiro(valor: &str) -> Option<i128> {
+    let digitos = valor.strip_prefix(['-', '+']).unwrap_or(valor);
+    if digitos.is_empty() || !digitos.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Inteiros maiores que i128 só aparecem em `integer`, que não tem limites
+    Some(valor.parse().unwrap_or(if valor.starts_with('-') {
+        i128::MIN
+    } else {
+        i128::MAX
+    }))
+}
+
+/// Data e hora lidas nos formatos do XML Schema, sem considerar o fuso horário, para
+/// conferir a forma e comparar com os limites.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Momento {
+    campos: [i64; 6],
+    fracao: String,
+}
+
+impl Momento {
+    fn ler(primitivo: Primitivo, valor: &str) -> Option<Self> {
+        let valor = sem_fuso(valor)?;
+        let mut campos = [0, 1, 1, 0, 0, 0];
+        let mut fracao = String::new();
+
+        let (data, hora) = match primitivo {
+            Primitivo::Data | Primitivo::AnoMes | Primitivo::Ano => (Some(valor), None),
+            Primitivo::DataHora => {
+                let (data, hora) = valor.split_once('T')?;
+                (Some(data), Some(hora))
+            }
+            Primitivo::Hora => (None, Some(valor)),
+            _ => return None,
+        };
+
+        if let Some(data) = data {
+            let (negativo, data) = match data.strip_prefix('-') {
+                Some(data) => (true, data),
+                None => (false, data),
+            };
+            let partes: Vec<&str> = data.split('-').collect();
+            let quantidade = match primitivo {
+                Primitivo::Ano => 1,
+                Primitivo::AnoMes => 2,
+                _ => 3,
+            };
+
+            if partes.len() != quantidade || partes[0].len() < 4 {
+                return None;
+            }
+            for (i, parte) in partes.iter().enumerate() {
+                if i > 0 && parte.len() != 2 {
+                    return None;
+                }
+                campos[i] = numero(parte)?;
+            }
+            if negativo {
+                campos[0] = -campos[0];
+            }
+
+            let dias = dias_do_mes(campos[0], campos[1]);
+            if !(1..=12).contains(&campos[1]) || !(1..=dias).contains(&campos[2]) {
+                return None;
+            }
+        }
+
+        if let Some(hora) = hora {
+            let (hora, resto) = match hora.split_once('.') {
+                Some((hora, resto)) if digitos(resto) => (hora, resto),
+                Some(_) => return None,
+                None => (hora, ""),
+            };
+            let partes: Vec<&str> = hora.split(':').collect();
+
+            if partes.len() != 3 || partes.iter().any(|parte| parte.len() != 2) {
+                return None;
+            }
+            for (i, parte) in partes.iter().enumerate() {
+                campos[3 + i] = numero(parte)?;
+            }
+            let meia_noite = campos[3] == 24 && campos[4] == 0 && campos[5] == 0;
+            if (campos[3] > 23 && !meia_noite) || campos[4] > 59 || campos[5] > 59 {
+                return None;
+            }
+            fracao = resto.trim_end_matches('0').to_string();
+        }
+
+        Some(Momento { campos, fracao })
+    }
+}
+
+/// Remove o fuso (`Z`, `+hh:mm` ou `-hh:mm`) do fim do valor, conferindo a forma dele.
+fn sem_fuso(valor: &str) -> Option<&str> {
+    if let Some(valor) = valor.strip_suffix('Z') {
+        return Some(valor);
+    }
+
+    let Some(fuso) = valor
+        .len()
+        .checked_sub(6)
+        .and_then(|inicio| valor.get(inicio..))
+    else {
+        return Some(valor);
+    };
+    let bytes = fuso.as_bytes();
+
+    let e_fuso = matches!(bytes[0], b'+' | b'-')
+        && bytes[3] == b':'
+        && numero(&fuso[1..3]).is_some_and(|h| h <= 14)
+        && numero(&fuso[4..6]).is_some_and(|m| m <= 59);
+
+    if e_fuso {
+        Some(&valor[..valor.len() - 6])
+    } else {
+        Some(valor)
+    }
+}
+
+fn digitos(texto: &str) -> bool {
+    !texto.is_empty() && texto.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn numero(texto: &str) -> Option<i64> {
+    digitos(texto).then(|| texto.parse().ok()).flatten()
+}
+
+fn dias_do_mes(ano: i64, mes: i64) -> i64 {
+    match mes {
+        2 if ano % 4 == 0 && (ano % 100 != 0 || ano % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn base64(valor: &str) -> bool {
+    let texto: Vec<u8> = valor.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let sem_preenchimento = texto.iter().rposition(|&b| b != b'=').map_or(0, |i| i + 1);
+
+    texto.len().is_multiple_of(4)
+        && texto.len() - sem_preenchimento <= 2
+        && texto[..sem_preenchimento]
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+fn nome_simples(valor: &str) -> bool {
+    let mut caracteres = valor.chars();
+
+    caracteres
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && caracteres.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Converte uma expressão regular do XML Schema para a sintaxe do `regex`. No XML Schema
+/// a expressão vale para o valor inteiro, `^` e `$` são caracteres comuns e `\w`, `\s`,
+/// `\i`, `\c` e `.` têm definições próprias.
+pub(super) fn padrao_xsd(padrao: &str) -> Result<String, String> {
+    let mut convertido = String::with_capacity(padrao.len() + 16);
+    let mut em_classe = false;
+    let mut caracteres = padrao.chars().peekable();
+
+    while let Some(caractere) = caracteres.next() {
+        match (caractere, em_classe) {
+            ('\\', _) => {
+                let escapado = caracteres
+                    .next()
+                    .ok_or_else(|| format!("padrão '{}' termina em '\\'", padrao))?;
+
+                convertido.push_str(match (escapado, em_classe) {
+                    ('w', false) => r"[^\p{P}\p{Z}\p{C}]",
+                    ('W', false) => r"[\p{P}\p{Z}\p{C}]",
+                    ('s', false) => r"[ \t\n\r]",
+                    ('S', false) => r"[^ \t\n\r]",
+                    ('s', true) => r" \t\n\r",
+                    ('i', false) => r"[\p{L}_:]",
+                    ('i', true) => r"\p{L}_:",
+                    ('c', false) => r"[\p{L}\p{N}._:\-]",
+                    ('c', true) => r"\p{L}\p{N}._:\-",
+                    _ => {
+                        convertido.push('\\');
+                        convertido.push(escapado);
+                        continue;
+                    }
+                });
+            }
+            ('[', false) => {
+                em_classe = true;
+                convertido.push('[');
+                if caracteres.peek() == Some(&'^') {
+                    convertido.push(caracteres.next().unwrap_or('^'));
+                }
+            }
+            ('[', true) => {
+                return Err(format!(
+                    "padrão '{}' usa subtração de classes, que não é suportada",
+                    padrao
+                ))
+            }
+            (']', true) => {
+                em_classe = false;
+                convertido.push(']');
+            }
+            ('.', false) => convertido.push_str(r"[^\n\r]"),
+            ('^' | '$', false) | ('&' | '~', true) => {
+                convertido.push('\\');
+                convertido.push(caractere);
+            }
+            _ => convertido.push(caractere),
+        }
+    }
+
+    Ok(convertido)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn casa(padrao: &str, valor: &str) -> bool {
+        Regex::new(&format!("^(?:{})$", padrao_xsd(padrao).unwrap()))
+            .unwrap()
+            .is_match(valor)
+    }
+
+    #[test]
+    fn padroes_valem_para_o_valor_inteiro() {
+        assert!(casa(r"\d{11}|\d{14}", "12345678909"));
+        assert!(!casa(r"\d{11}|\d{14}", "123456789090"));
+        assert!(casa(r"[2]{1}\d{3}-(1[0-2]|0[1-9])|[2]{1}\d{3}$", "2025-01"));
+        assert!(!casa(r"[2]{1}\d{3}-(1[0-2]|0[1-9])|[2]{1}\d{3}$", "2025"));
+        assert!(casa(r".*[^\s].*", " a "));
+        assert!(!casa(r".*[^\s].*", "   "));
+        assert!(casa(r"\w{1,6}", "Açaí"));
+        assert!(!casa(r"\w{1,6}", "a_b"));
+        assert!(casa(r"[^\s]{1}[\S\s]*", "x\ny"));
+    }
+
+    #[test]
+    fn subtracao_de_classes_nao_e_suportada() {
+        assert!(padrao_xsd("[a-z-[aeiou]]").is_err());
+    }
+
+    #[test]
+    fn decimais_sao_comparados_sem_arredondar() {
+        let decimal = |valor| Decimal::ler(valor).unwrap();
+
+        assert!(decimal("999999999999.99") < decimal("1000000000000"));
+        assert!(decimal("-0.5") < decimal("0"));
+        assert_eq!(decimal("-0.00"), decimal("0"));
+        assert_eq!(decimal("007.50"), decimal("7.5"));
+        assert_eq!(decimal("0.05").digitos(), 1);
+        assert_eq!(decimal("123.45").digitos(), 5);
+        assert!(Decimal::ler("1,5").is_none());
+        assert!(Decimal::ler(".").is_none());
+    }
+
+    #[test]
+    fn datas_e_horas() {
+        assert!(Primitivo::Data.verificar("2024-02-29").is_ok());
+        assert!(Primitivo::Data.verificar("2023-02-29").is_err());
+        assert!(Primitivo::Data.verificar("2025-01-15-03:00").is_ok());
+        assert!(Primitivo::Data.verificar("15/01/2025").is_err());
+        assert!(Primitivo::DataHora
+            .verificar("2025-01-15T10:32:07.143-03:00")
+            .is_ok());
+        assert!(Primitivo::DataHora
+            .verificar("2025-01-15T25:00:00")
+            .is_err());
+        assert!(Primitivo::AnoMes.verificar("2025-13").is_err());
+        assert_eq!(
+            Primitivo::Data.comparar("1889-12-31", "1890-01-01"),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn facetas_de_numeros() {
+        let facetas = Facetas {
+            digitos: Some(14),
+            casas_decimais: Some(2),
+            minimo: Some(Limite::exclusivo("0")),
+            maximo: Some(Limite::inclusivo("999999999999.99")),
+            ..Default::default()
+        };
+
+        assert!(facetas.verificar(Primitivo::Decimal, "1500.00").is_ok());
+        assert_eq!(
+            facetas.verificar(Primitivo::Decimal, "0"),
+            Err("o valor '0' deve ser maior que 0".into())
+        );
+        assert_eq!(
+            facetas.verificar(Primitivo::Decimal, "10.555"),
+            Err("o valor '10.555' tem mais de 2 casas decimais".into())
+        );
+        assert!(facetas
+            .verificar(Primitivo::Decimal, "1000000000000")
+            .is_err());
+        assert!(Primitivo::de_nome("byte")
+            .unwrap()
+            .verificar("128")
+            .is_err());
+    }
+}
@@ -0,0 +1,463 @@
+use roxmltree::Node;
+
+use super::carregar::{
+    Conteudo, DeclAtributo, DeclElemento, Definicoes, NomeQ, Particula, RefTipo, Termo, Tipo,
+    TipoComplexo, XS,
+};
+use super::tipos::{Facetas, Primitivo};
+use super::ErroEsquema;
+
+/// Atributos do XML Schema Instance, que não são declarados nos esquemas.
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Valida o elemento raiz e todo o conteúdo dele.
+pub(super) fn validar(definicoes: &Definicoes, raiz: Node) -> Vec<ErroEsquema> {
+    let mut validacao = Validacao {
+        definicoes,
+        erros: Vec::new(),
+    };
+    let caminho = format!("/{}", raiz.tag_name().name());
+
+    match definicoes
+        .elementos
+        .values()
+        .find(|decl| mesmo_nome(raiz, &decl.nome))
+    {
+        Some(decl) => validacao.elemento(raiz, &decl.tipo, &caminho),
+        None => validacao.erro(
+            raiz,
+            raiz.range().start,
+            &caminho,
+            format!(
+                "o elemento '{}' não está declarado no esquema",
+                raiz.tag_name().name()
+            ),
+        ),
+    }
+
+    validacao.erros
+}
+
+fn mesmo_nome(no: Node, nome: &NomeQ) -> bool {
+    no.tag_name().name() == nome.local && no.tag_name().namespace().unwrap_or_default() == nome.ns
+}
+
+struct Validacao<'e> {
+    definicoes: &'e Definicoes,
+    erros: Vec<ErroEsquema>,
+}
+
+/// Tipo de um elemento depois de seguir as referências por nome.
+enum Resolvido<'e> {
+    Simples(&'e RefTipo),
+    Complexo(&'e TipoComplexo),
+    Qualquer,
+}
+
+/// Conteúdo de um tipo complexo somado ao dos tipos que ele estende.
+#[derive(Default)]
+struct Efetivo<'e> {
+    particulas: Vec<&'e Particula>,
+    atributos: Vec<&'e DeclAtributo>,
+    simples: Option<&'e RefTipo>,
+    misto: bool,
+}
+
+/// Declaração usada para validar um elemento filho.
+enum Alvo<'e> {
+    Elemento(&'e DeclElemento),
+    Curinga,
+}
+
+/// Estado do casamento dos filhos com o modelo de conteúdo.
+#[derive(Default)]
+struct Casamento<'e> {
+    alvos: Vec<(usize, Alvo<'e>)>,
+    /// Posição mais distante em que faltou um elemento e os nomes esperados nela.
+    falha: usize,
+    esperados: Vec<String>,
+}
+
+impl Casamento<'_> {
+    fn esperar(&mut self, posicao: usize, nome: &str) {
+        if posicao > self.falha {
+            self.falha = posicao;
+            self.esperados.clear();
+        }
+        if posicao == self.falha && !self.esperados.iter().any(|esperado| esperado == nome) {
+            self.esperados.push(nome.to_string());
+        }
+    }
+}
+
+impl<'e> Validacao<'e> {
+    fn erro(&mut self, no: Node, posicao: usize, caminho: &str, mensagem: String) {
+        let posicao = no.document().text_pos_at(posicao);
+
+        self.erros.push(ErroEsquema {
+            linha: posicao.row,
+            coluna: posicao.col,
+            caminho: caminho.to_string(),
+            mensagem,
+        });
+    }
+
+    fn resolver(&self, tipo: &'e RefTipo) -> Resolvido<'e> {
+        match tipo {
+            RefTipo::Qualquer => Resolvido::Qualquer,
+            RefTipo::Nomeado(nome) if nome.ns == XS && nome.local == "anyType" => {
+                Resolvido::Qualquer
+            }
+            RefTipo::Nomeado(nome) if nome.ns == XS => Resolvido::Simples(tipo),
+            RefTipo::Nomeado(nome) => match self.definicoes.tipos.get(nome) {
+                Some(Tipo::Complexo(complexo)) => Resolvido::Complexo(complexo),
+                Some(Tipo::Simples(_)) => Resolvido::Simples(tipo),
+                None => Resolvido::Qualquer,
+            },
+            RefTipo::Anonimo(anonimo) => match anonimo.as_ref() {
+                Tipo::Complexo(complexo) => Resolvido::Complexo(complexo),
+                Tipo::Simples(_) => Resolvido::Simples(tipo),
+            },
+        }
+    }
+
+    fn efetivo(&self, tipo: &'e TipoComplexo) -> Efetivo<'e> {
+        let mut efetivo = match tipo
+            .base
+            .as_ref()
+            .and_then(|base| self.definicoes.tipos.get(base))
+        {
+            Some(Tipo::Complexo(base)) => self.efetivo(base),
+            _ => Efetivo::default(),
+        };
+
+        match &tipo.conteudo {
+            Conteudo::Vazio => {}
+            Conteudo::Elementos(particula) => efetivo.particulas.push(particula),
+            Conteudo::Simples(simples) => efetivo.simples = Some(simples),
+        }
+        efetivo.atributos.extend(&tipo.atributos);
+        efetivo.misto |= tipo.misto;
+
+        efetivo
+    }
+
+    /// Confere o valor com o tipo simples e as restrições de toda a cadeia até o tipo
+    /// do XML Schema.
+    fn valor(&self, tipo: &RefTipo, valor: &str) -> Result<(), String> {
+        let mut facetas: Vec<&Facetas> = Vec::new();
+        let mut atual = tipo;
+
+        let primitivo = loop {
+            let simples = match atual {
+                RefTipo::Nomeado(nome) if nome.ns == XS => {
+                    break Primitivo::de_nome(&nome.local).unwrap_or(Primitivo::Texto)
+                }
+                RefTipo::Nomeado(nome) => self.definicoes.tipos.get(nome),
+                RefTipo::Anonimo(anonimo) => Some(anonimo.as_ref()),
+                RefTipo::Qualquer => None,
+            };
+
+            match simples {
+                Some(Tipo::Simples(simples)) => {
+                    facetas.push(&simples.facetas);
+                    atual = &simples.base;
+                }
+                _ => break Primitivo::Texto,
+            }
+        };
+
+        let espacos = facetas
+            .iter()
+            .find_map(|facetas| facetas.espacos)
+            .unwrap_or(primitivo.espacos());
+        let valor = espacos.normalizar(valor);
+
+        primitivo.verificar(&valor)?;
+        facetas
+            .iter()
+            .rev()
+            .try_for_each(|facetas| facetas.verificar(primitivo, &valor))
+    }
+
+    fn elemento(&mut self, no: Node, tipo: &'e RefTipo, caminho: &str) {
+        let efetivo = match self.resolver(tipo) {
+            Resolvido::Qualquer => return,
+            Resolvido::Simples(simples) => Efetivo {
+                simples: Some(simples),
+                ..Default::default()
+            },
+            Resolvido::Complexo(complexo) => self.efetivo(complexo),
+        };
+
+        self.atributos(no, &efetivo.atributos, caminho);
+
+        if let Some(simples) = efetivo.simples {
+            if let Some(filho) = no.children().find(Node::is_element) {
+                let mensagem = format!(
+                    "o elemento '{}' não pode ter elementos filhos",
+                    no.tag_name().name()
+                );
+                return self.erro(filho, filho.range().start, caminho, mensagem);
+            }
+
+            let texto: String = no.children().filter_map(|filho| filho.text()).collect();
+            if let Err(mensagem) = self.valor(simples, &texto) {
+                self.erro(no, no.range().start, caminho, mensagem);
+            }
+            return;
+        }
+
+        if !efetivo.misto {
+            let texto = no.children().find(|filho| {
+                filho.is_text() && !filho.text().unwrap_or_default().trim().is_empty()
+            });
+
+            if let Some(texto) = texto {
+                let mensagem = format!(
+                    "o elemento '{}' não pode ter texto, só elementos filhos",
+                    no.tag_name().name()
+                );
+                self.erro(texto, texto.range().start, caminho, mensagem);
+            }
+        }
+
+        self.filhos(no, &efetivo.particulas, caminho);
+    }
+
+    fn atributos(&mut self, no: Node, declarados: &[&'e DeclAtributo], caminho: &str) {
+        for atributo in no.attributes() {
+            if matches!(atributo.namespace(), Some(XSI | XML)) {
+                continue;
+            }
+
+            let caminho_atributo = format!("{}/@{}", caminho, atributo.name());
+            let decl = declarados
+                .iter()
+                .find(|decl| atributo.namespace().is_none() && decl.nome == atributo.name());
+
+            let resultado = match decl {
+                Some(decl) => self.valor(&decl.tipo, atributo.value()),
+                None => Err(format!("o atributo '{}' não é permitido", atributo.name())),
+            };
+
+            if let Err(mensagem) = resultado {
+                self.erro(no, atributo.range().start, &caminho_atributo, mensagem);
+            }
+        }
+
+        for decl in declarados.iter().filter(|decl| decl.obrigatorio) {
+            if no.attribute(decl.nome.as_str()).is_none() {
+                let mensagem = format!("o atributo obrigatório '{}' não foi informado", decl.nome);
+                self.erro(no, no.range().start, caminho, mensagem);
+            }
+        }
+    }
+
+    /// Casa os filhos com o modelo de conteúdo e valida cada um com a declaração
+    /// correspondente. Com um erro no modelo, os filhos que não chegaram a ser casados
+    /// são validados pela declaração de mesmo nome, para que os erros deles também
+    /// apareçam.
+    fn filhos(&mut self, no: Node, particulas: &[&'e Particula], caminho: &str) {
+        let filhos: Vec<Node> = no.children().filter(Node::is_element).collect();
+        let mut casamento = Casamento::default();
+
+        let fim = self.casar_sequencia(particulas.iter().copied(), &filhos, 0, &mut casamento);
+
+        let caminho_de = |filho: Node| format!("{}/{}", caminho, filho.tag_name().name());
+        let mut casados = vec![false; filhos.len()];
+
+        for (i, alvo) in std::mem::take(&mut casamento.alvos) {
+            casados[i] = true;
+
+            let decl = match alvo {
+                Alvo::Elemento(decl) => Some(decl),
+                Alvo::Curinga => self
+                    .definicoes
+                    .elementos
+                    .values()
+                    .find(|decl| mesmo_nome(filhos[i], &decl.nome)),
+            };
+            if let Some(decl) = decl {
+                self.elemento(filhos[i], &decl.tipo, &caminho_de(filhos[i]));
+            }
+        }
+
+        if fim == Some(filhos.len()) {
+            return;
+        }
+
+        let parado = fim.unwrap_or_default();
+        let esperados = if casamento.falha >= parado && !casamento.esperados.is_empty() {
+            format!("; esperado: {}", casamento.esperados.join(", "))
+        } else {
+            String::new()
+        };
+        let posicao = casamento.falha.max(parado);
+
+        match filhos.get(posicao) {
+            Some(filho) => {
+                let mensagem = format!(
+                    "o elemento '{}' não era esperado{}",
+                    filho.tag_name().name(),
+                    esperados
+                );
+                self.erro(*filho, filho.range().start, &caminho_de(*filho), mensagem);
+            }
+            None => {
+                let mensagem = format!(
+                    "o elemento '{}' está incompleto{}",
+                    no.tag_name().name(),
+                    esperados
+                );
+                self.erro(no, no.range().start, caminho, mensagem);
+            }
+        }
+
+        for (filho, _) in filhos.iter().zip(casados).filter(|(_, casado)| !casado) {
+            if let Some(decl) = procurar(particulas.iter().copied(), *filho, self.definicoes) {
+                self.elemento(*filho, &decl.tipo, &caminho_de(*filho));
+            }
+        }
+    }
+
+    fn casar_sequencia(
+        &self,
+        particulas: impl IntoIterator<Item = &'e Particula>,
+        filhos: &[Node],
+        inicio: usize,
+        casamento: &mut Casamento<'e>,
+    ) -> Option<usize> {
+        let salvo = casamento.alvos.len();
+        let mut posicao = inicio;
+
+        for particula in particulas {
+            match self.casar(particula, filhos, posicao, casamento) {
+                Some(fim) => posicao = fim,
+                None => {
+                    casamento.alvos.truncate(salvo);
+                    return None;
+                }
+            }
+        }
+
+        Some(posicao)
+    }
+
+    /// Casa a partícula quantas vezes ela permitir. Como os esquemas não podem ser
+    /// ambíguos, o primeiro casamento possível é o único.
+    fn casar(
+        &self,
+        particula: &'e Particula,
+        filhos: &[Node],
+        inicio: usize,
+        casamento: &mut Casamento<'e>,
+    ) -> Option<usize> {
+        let mut ocorrencias = 0;
+        let mut posicao = inicio;
+
+        while particula.max.is_none_or(|max| ocorrencias < max) {
+            let salvo = casamento.alvos.len();
+
+            match self.casar_termo(&particula.termo, filhos, posicao, casamento) {
+                Some(fim) if fim > posicao => {
+                    posicao = fim;
+                    ocorrencias += 1;
+                }
+                Some(_) => {
+                    ocorrencias = ocorrencias.max(particula.min);
+                    break;
+                }
+                None => {
+                    casamento.alvos.truncate(salvo);
+                    break;
+                }
+            }
+        }
+
+        (ocorrencias >= particula.min).then_some(posicao)
+    }
+
+    fn casar_termo(
+        &self,
+        termo: &'e Termo,
+        filhos: &[Node],
+        posicao: usize,
+        casamento: &mut Casamento<'e>,
+    ) -> Option<usize> {
+        match termo {
+            Termo::Elemento(decl) => casar_elemento(decl, filhos, posicao, casamento),
+            Termo::Referencia(nome) => casar_elemento(
+                self.definicoes.elementos.get(nome)?,
+                filhos,
+                posicao,
+                casamento,
+            ),
+            Termo::Sequencia(particulas) => {
+                self.casar_sequencia(particulas, filhos, posicao, casamento)
+            }
+            Termo::Escolha(particulas) => {
+                let mut vazia = false;
+
+                for particula in particulas {
+                    let salvo = casamento.alvos.len();
+
+                    match self.casar(particula, filhos, posicao, casamento) {
+                        Some(fim) if fim > posicao => return Some(fim),
+                        Some(_) => vazia = true,
+                        None => casamento.alvos.truncate(salvo),
+                    }
+                }
+
+                vazia.then_some(posicao)
+            }
+            Termo::Curinga(curinga) => match filhos.get(posicao) {
+                Some(filho) if curinga.aceita(filho.tag_name().namespace().unwrap_or_default()) => {
+                    casamento.alvos.push((posicao, Alvo::Curinga));
+                    Some(posicao + 1)
+                }
+                _ => {
+                    casamento.esperar(posicao, "qualquer elemento");
+                    None
+                }
+            },
+        }
+    }
+}
+
+fn casar_elemento<'e>(
+    decl: &'e DeclElemento,
+    filhos: &[Node],
+    posicao: usize,
+    casamento: &mut Casamento<'e>,
+) -> Option<usize> {
+    match filhos.get(posicao) {
+        Some(filho) if mesmo_nome(*filho, &decl.nome) => {
+            casamento.alvos.push((posicao, Alvo::Elemento(decl)));
+            Some(posicao + 1)
+        }
+        _ => {
+            casamento.esperar(posicao, &decl.nome.local);
+            None
+        }
+    }
+}
+
+/// Declaração de um elemento com o nome do filho em qualquer ponto do modelo de conteúdo.
+fn procurar<'e>(
+    particulas: impl IntoIterator<Item = &'e Particula>,
+    filho: Node,
+    definicoes: &'e Definicoes,
+) -> Option<&'e DeclElemento> {
+    particulas
+        .into_iter()
+        .find_map(|particula| match &particula.termo {
+            Termo::Elemento(decl) if mesmo_nome(filho, &decl.nome) => Some(decl.as_ref()),
+            Termo::Referencia(nome) if mesmo_nome(filho, nome) => definicoes.elementos.get(nome),
+            Termo::Sequencia(particulas) | Termo::Escolha(particulas) => {
+                procurar(particulas, filho, definicoes)
+            }
+            _ => None,
+        })
+}
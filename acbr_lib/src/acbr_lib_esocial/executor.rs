@@ -0,0 +1,181 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::ACBrError;
+use crate::metricas;
+use crate::utils::vigia;
+
+use super::ACBrLibEsocial;
+
+type Tarefa = Box<dyn FnOnce(&mut ACBrLibEsocial) + Send>;
+
+/// Entrega o resultado de uma chamada a quem a fez, seja por um canal síncrono ou
+/// assíncrono. A primeira entrega vale: o resultado da chamada ou o erro de prazo.
+type Responder<R> = Arc<Mutex<Pendente<R>>>;
+
+type Entrega<R> = Box<dyn FnOnce(Result<R, ACBrError>) + Send>;
+
+struct Pendente<R> {
+    entrega: Option<Entrega<R>>,
+    /// A operação começou a ser executada na thread da sessão.
+    iniciada: bool,
+}
+
+fn bloquear<R>(responder: &Responder<R>) -> MutexGuard<'_, Pendente<R>> {
+    responder.lock().unwrap_or_else(|erro| erro.into_inner())
+}
+
+/// Retira a entrega do resultado, que só pode ser feita por quem a retirar primeiro.
+fn tomar<R>(responder: &Responder<R>) -> Option<Entrega<R>> {
+    bloquear(responder).entrega.take()
+}
+
+/// Marca a chamada como iniciada, a não ser que o prazo já tenha vencido na fila.
+fn iniciar<R>(responder: &Responder<R>) -> bool {
+    let mut pendente = bloquear(responder);
+    pendente.iniciada = pendente.entrega.is_some();
+    pendente.iniciada
+}
+
+/// Thread dedicada que mantém uma sessão e executa as chamadas uma de cada vez.
+///
+/// Cada chamada pode ter um prazo, contado a partir do envio e acompanhado pela thread de
+/// vigia. Se o prazo passar, quem chamou recebe `ACBrError::Timeout` na hora. Uma chamada
+/// que ainda estava na fila é descartada sem ser executada; se ela já estava em execução,
+/// a sessão entra em quarentena: a chamada nativa continua até terminar, mas a sessão não
+/// executa mais nada e é finalizada em seguida.
+#[derive(Clone)]
+pub(crate) struct Executor {
+    tarefas: mpsc::Sender<Tarefa>,
+    quarentena: Arc<AtomicBool>,
+}
+
+impl Executor {
+    /// Cria a thread e a sessão dentro dela. O resultado da criação é entregue a `criada`.
+    pub(crate) fn iniciar<C, F>(criar: C, criada: F) -> Result<Self, ACBrError>
+    where
+        C: FnOnce() -> Result<ACBrLibEsocial, ACBrError> + Send + 'static,
+        F: FnOnce(Result<(), ACBrError>) + Send + 'static,
+    {
+        let (tarefas, recebidas) = mpsc::channel::<Tarefa>();
+        let quarentena = Arc::new(AtomicBool::new(false));
+
+        let em_quarentena = Arc::clone(&quarentena);
+        thread::Builder::new()
+            .name("acbr-esocial".into())
+            .spawn(move || match criar() {
+                Ok(sessao) => {
+                    criada(Ok(()));
+                    executar_tarefas(sessao, recebidas, &em_quarentena);
+                }
+                Err(erro) => criada(Err(erro)),
+            })
+            .map_err(|_| ACBrError::SessaoEncerrada)?;
+
+        Ok(Executor {
+            tarefas,
+            quarentena,
+        })
+    }
+
+    /// Envia `operacao` para a thread da sessão. O resultado, ou o erro de prazo, é
+    /// entregue a `entregar` exatamente uma vez.
+    pub(crate) fn enviar<R, F, E>(&self, prazo: Option<Duration>, operacao: F, entregar: E)
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError> + Send + 'static,
+        E: FnOnce(Result<R, ACBrError>) + Send + 'static,
+        R: Send + 'static,
+    {
+        if self.em_quarentena() {
+            return entregar(Err(ACBrError::SessaoEmQuarentena));
+        }
+
+        let entregar: Responder<R> = Arc::new(Mutex::new(Pendente {
+            entrega: Some(Box::new(entregar)),
+            iniciada: false,
+        }));
+        let pendente = Arc::clone(&entregar);
+
+        let vigilancia = prazo.map(|prazo| {
+            let entregar = Arc::clone(&entregar);
+            let quarentena = Arc::clone(&self.quarentena);
+
+            vigia::vigiar(prazo, move || {
+                let (entrega, iniciada) = {
+                    let mut pendente = bloquear(&entregar);
+                    (pendente.entrega.take(), pendente.iniciada)
+                };
+
+                // Só entra em quarentena se o prazo venceu durante a chamada, antes do
+                // resultado, e antes de avisar quem chamou
+                if let Some(entregar) = entrega {
+                    if iniciada {
+                        quarentena.store(true, Ordering::SeqCst);
+                    }
+                    metricas::registrar_chamada_expirada();
+                    entregar(Err(ACBrError::Timeout(prazo)));
+                }
+            })
+        });
+        let vigilancia = match vigilancia.transpose() {
+            Ok(vigilancia) => vigilancia,
+            Err(erro) => {
+                if let Some(entregar) = tomar(&pendente) {
+                    entregar(Err(erro));
+                }
+                return;
+            }
+        };
+
+        let tarefa: Tarefa = Box::new(move |sessao| {
+            if !iniciar(&entregar) {
+                return;
+            }
+
+            let resultado = operacao(sessao);
+            drop(vigilancia);
+
+            if let Some(entregar) = tomar(&entregar) {
+                entregar(resultado);
+            }
+        });
+
+        if self.tarefas.send(tarefa).is_err() {
+            if let Some(entregar) = tomar(&pendente) {
+                entregar(Err(self.erro_encerrada()));
+            }
+        }
+    }
+
+    pub(crate) fn em_quarentena(&self) -> bool {
+        self.quarentena.load(Ordering::SeqCst)
+    }
+
+    /// Erro para uma chamada descartada sem resposta porque a thread da sessão terminou.
+    pub(crate) fn erro_encerrada(&self) -> ACBrError {
+        if self.em_quarentena() {
+            ACBrError::SessaoEmQuarentena
+        } else {
+            ACBrError::SessaoEncerrada
+        }
+    }
+}
+
+/// Laço da thread dedicada. Termina, finalizando a sessão, quando todos os `Executor`
+/// são liberados ou quando a sessão entra em quarentena; as chamadas que ainda estavam
+/// na fila são descartadas.
+fn executar_tarefas(
+    mut sessao: ACBrLibEsocial,
+    tarefas: mpsc::Receiver<Tarefa>,
+    quarentena: &AtomicBool,
+) {
+    for tarefa in tarefas {
+        if quarentena.load(Ordering::SeqCst) {
+            break;
+        }
+
+        tarefa(&mut sessao);
+    }
+}
@@ -0,0 +1,365 @@
+use std::mem::transmute;
+use std::os::raw::{c_char, c_int, c_void};
+
+use crate::error::ACBrError;
+use crate::utils::dynamic_library::get_function;
+
+use super::ACBrLibEsocial;
+
+/// Ponteiro para uma função exportada pela ACBrLib, na convenção de chamada do alvo.
+///
+/// Só a build Windows 32 bits usa `stdcall`; as builds 64 bits e as de Linux usam `cdecl`.
+/// Todas as assinaturas da tabela de funções passam por esta definição.
+#[cfg(all(target_os = "windows", target_arch = "x86"))]
+macro_rules! funcao_nativa {
+    ($($tipo:ty),*) => { unsafe extern "stdcall" fn($($tipo),*) -> c_int };
+}
+
+#[cfg(not(all(target_os = "windows", target_arch = "x86")))]
+macro_rules! funcao_nativa {
+    ($($tipo:ty),*) => { unsafe extern "C" fn($($tipo),*) -> c_int };
+}
+
+pub(crate) type FnInicializar = funcao_nativa!(*mut *mut c_void, *const c_char, *const c_char);
+pub(crate) type FnFinalizar = funcao_nativa!(*mut c_void);
+
+/// Tipo do parâmetro na assinatura nativa para cada tipo de argumento declarado.
+macro_rules! tipo_ffi {
+    (texto) => { *const c_char };
+    (inteiro) => { c_int };
+}
+
+/// Tipo do parâmetro no método seguro para cada tipo de argumento declarado.
+macro_rules! tipo_rust {
+    (texto) => { impl AsRef<str> };
+    (inteiro) => { i32 };
+}
+
+/// Tipo do parâmetro nos métodos executados na thread dedicada da sessão, para onde o
+/// argumento precisa ser levado.
+macro_rules! tipo_enviado {
+    (texto) => { impl Into<String> };
+    (inteiro) => { i32 };
+}
+
+/// Tipo do argumento depois de copiado para ser enviado à thread da sessão.
+macro_rules! tipo_dono {
+    (texto) => {
+        String
+    };
+    (inteiro) => {
+        i32
+    };
+}
+
+/// Converte o argumento do método seguro para o valor que é mantido vivo durante a chamada,
+/// com os textos na codificação da sessão.
+macro_rules! converter {
+    (texto, $arg:ident, $codificacao:expr) => {
+        super::texto_nativo($arg.as_ref(), $codificacao, stringify!($arg))?
+    };
+    (inteiro, $arg:ident, $codificacao:expr) => {
+        $arg as c_int
+    };
+}
+
+/// Argumento de texto antes da conversão, para `muda_codificacao`.
+macro_rules! argumento_texto {
+    (texto, $arg:ident) => {
+        $arg.as_ref()
+    };
+    (inteiro, $arg:ident) => {
+        ""
+    };
+}
+
+/// Valor efetivamente passado para a função nativa.
+macro_rules! argumento {
+    (texto, $arg:ident) => {
+        $arg.as_ptr()
+    };
+    (inteiro, $arg:ident) => {
+        $arg
+    };
+}
+
+/// Assinatura nativa completa: toda função recebe o handle da lib e, quando devolve
+/// uma resposta, termina com o buffer e o seu tamanho.
+macro_rules! assinatura {
+    (codigo; $($tipo:ident),*) => {
+        funcao_nativa!(*mut c_void $(, tipo_ffi!($tipo))*)
+    };
+    (resposta; $($tipo:ident),*) => {
+        funcao_nativa!(*mut c_void, $(tipo_ffi!($tipo),)* *mut c_char, *mut c_int)
+    };
+}
+
+/// Tipo de retorno do método seguro.
+macro_rules! retorno {
+    (codigo) => { Result<(), ACBrError> };
+    (resposta) => { Result<String, ACBrError> };
+}
+
+/// Lê do worker o argumento recebido como texto pelo protocolo.
+macro_rules! ler_argumento {
+    (texto, $valor:expr) => {
+        $valor.ok_or_else(|| ACBrError::Worker("argumento ausente".into()))?
+    };
+    (inteiro, $valor:expr) => {
+        $valor
+            .and_then(|valor| valor.parse::<i32>().ok())
+            .ok_or_else(|| ACBrError::Worker("argumento inteiro inválido".into()))?
+    };
+}
+
+/// Converte o argumento do método da `SessaoIsolada` para o texto enviado ao worker.
+macro_rules! enviar_argumento {
+    (texto, $arg:ident) => {{
+        let $arg = $arg.as_ref();
+        // Recusado aqui, como em `ACBrLibEsocial`, para o erro apontar o parâmetro
+        if $arg.contains('\0') {
+            return Err(ACBrError::TextoInvalido(stringify!($arg)));
+        }
+        $arg.to_string()
+    }};
+    (inteiro, $arg:ident) => {
+        $arg.to_string()
+    };
+}
+
+/// Converte o retorno do método para o texto da resposta do worker, e de volta.
+macro_rules! como_texto {
+    (codigo, $resultado:expr) => {
+        $resultado.map(|()| String::new())
+    };
+    (resposta, $resultado:expr) => {
+        $resultado
+    };
+}
+
+macro_rules! como_retorno {
+    (codigo, $resultado:expr) => {
+        $resultado.map(drop)
+    };
+    (resposta, $resultado:expr) => {
+        $resultado
+    };
+}
+
+/// Faz a chamada nativa e trata o retorno conforme o tipo de saída declarado.
+macro_rules! chamar {
+    (codigo, $lib:ident, $funcoes:ident, $funcao:ident, $($tipo:ident $arg:ident),*) => {
+        $lib.verificar_retorno(
+            $funcoes,
+            unsafe { $funcao($lib.pointer, $(argumento!($tipo, $arg)),*) },
+        )
+    };
+    (resposta, $lib:ident, $funcoes:ident, $funcao:ident, $($tipo:ident $arg:ident),*) => {
+        $lib.ler_resposta($funcoes, |resposta, tamanho| unsafe {
+            $funcao($lib.pointer, $(argumento!($tipo, $arg),)* resposta, tamanho)
+        })
+    };
+}
+
+/// Declara as funções `eSocial_*` exportadas pela biblioteca.
+///
+/// Cada entrada gera o campo da tabela de funções (resolvida uma única vez ao carregar
+/// a DLL) e o método seguro correspondente em `ACBrLibEsocial`. Os argumentos podem ser
+/// `texto` ou `inteiro`; a saída é `codigo` quando a função só devolve o código de
+/// retorno, ou `resposta` quando ela também preenche um buffer de texto.
+macro_rules! funcoes_esocial {
+    (
+        $(
+            $(#[$meta:meta])*
+            fn $metodo:ident($($arg:ident: $tipo:ident),*) -> $saida:ident = $simbolo:literal;
+        )*
+    ) => {
+        /// Tabela com todas as funções `eSocial_*` exportadas pela biblioteca.
+        ///
+        /// É resolvida uma única vez ao carregar a DLL e compartilhada por todas as instâncias
+        /// de `ACBrLibEsocial`, evitando o `GetProcAddress`/`dlsym` a cada chamada.
+        pub(crate) struct FuncoesESocial {
+            pub inicializar: FnInicializar,
+            pub finalizar: FnFinalizar,
+            $(pub $metodo: assinatura!($saida; $($tipo),*),)*
+        }
+
+        impl FuncoesESocial {
+            /// Resolve todas as funções da tabela. Caso alguma não exista na biblioteca,
+            /// retorna um único erro com a lista completa das funções ausentes.
+            pub(crate) unsafe fn carregar(lib_handle: *mut c_void) -> Result<Self, ACBrError> {
+                Self::resolver(|nome| get_function(lib_handle, nome).ok())
+            }
+
+            /// Monta a tabela a partir de uma função que localiza cada símbolo pelo nome.
+            ///
+            /// # Safety
+            /// Todo endereço devolvido precisa apontar para uma função com a assinatura
+            /// declarada para aquele símbolo.
+            pub(crate) unsafe fn resolver<R>(mut resolver: R) -> Result<Self, ACBrError>
+            where
+                R: FnMut(&str) -> Option<*mut c_void>,
+            {
+                let mut ausentes: Vec<String> = Vec::new();
+
+                let mut simbolo = |nome: &'static str| match resolver(nome) {
+                    Some(function) if !function.is_null() => function,
+                    _ => {
+                        ausentes.push(nome.to_string());
+                        std::ptr::null_mut()
+                    }
+                };
+
+                let inicializar = simbolo("eSocial_Inicializar");
+                let finalizar = simbolo("eSocial_Finalizar");
+                $(let $metodo = simbolo($simbolo);)*
+
+                if !ausentes.is_empty() {
+                    return Err(ACBrError::FuncaoNaoEncontrada(ausentes));
+                }
+
+                Ok(FuncoesESocial {
+                    inicializar: transmute::<*mut c_void, FnInicializar>(inicializar),
+                    finalizar: transmute::<*mut c_void, FnFinalizar>(finalizar),
+                    $($metodo: transmute::<*mut c_void, assinatura!($saida; $($tipo),*)>($metodo),)*
+                })
+            }
+        }
+
+        impl ACBrLibEsocial {
+            $(
+                $(#[$meta])*
+                pub fn $metodo(&self, $($arg: tipo_rust!($tipo)),*) -> retorno!($saida) {
+                    let biblioteca = self.biblioteca();
+                    let _chamada = biblioteca.chamada()?;
+
+                    let muda_codificacao =
+                        super::muda_codificacao($simbolo, &[$(argumento_texto!($tipo, $arg)),*]);
+                    $(let $arg = converter!($tipo, $arg, self.codificacao.get());)*
+
+                    let funcoes = super::funcoes(biblioteca);
+                    let funcao = funcoes.$metodo;
+
+                    let resultado = chamar!($saida, self, funcoes, funcao, $($tipo $arg),*);
+
+                    if resultado.is_ok() && muda_codificacao {
+                        self.ler_codificacao(funcoes)?;
+                    }
+
+                    resultado
+                }
+            )*
+        }
+
+        impl ACBrLibEsocial {
+            /// Executa o método pelo nome, com os argumentos recebidos como texto pelo
+            /// processo worker.
+            pub(crate) fn despachar(
+                &self,
+                metodo: &str,
+                argumentos: &[String],
+            ) -> Result<String, ACBrError> {
+                let mut argumentos = argumentos.iter();
+
+                match metodo {
+                    $(
+                        stringify!($metodo) => {
+                            $(let $arg = ler_argumento!($tipo, argumentos.next());)*
+                            como_texto!($saida, self.$metodo($($arg),*))
+                        }
+                    )*
+                    metodo => Err(super::protocolo::resposta_invalida(metodo)),
+                }
+            }
+        }
+
+        impl super::SessaoIsolada {
+            $(
+                $(#[$meta])*
+                pub fn $metodo(&self, $($arg: tipo_rust!($tipo)),*) -> retorno!($saida) {
+                    let requisicao = [
+                        stringify!($metodo).to_string(),
+                        $(enviar_argumento!($tipo, $arg)),*
+                    ];
+
+                    como_retorno!($saida, self.chamar(&requisicao))
+                }
+            )*
+        }
+
+        impl super::SessaoComPrazo {
+            $(
+                $(#[$meta])*
+                pub fn $metodo(&self, $($arg: tipo_enviado!($tipo)),*) -> retorno!($saida) {
+                    $(let $arg: tipo_dono!($tipo) = $arg.into();)*
+
+                    self.executar(move |sessao| sessao.$metodo($($arg),*))
+                }
+            )*
+        }
+
+        #[cfg(feature = "async")]
+        impl super::SessaoAssincrona {
+            $(
+                $(#[$meta])*
+                pub async fn $metodo(&self, $($arg: tipo_enviado!($tipo)),*) -> retorno!($saida) {
+                    $(let $arg: tipo_dono!($tipo) = $arg.into();)*
+
+                    self.executar(move |sessao| sessao.$metodo($($arg),*)).await
+                }
+            )*
+        }
+    };
+}
+
+funcoes_esocial! {
+    /// Retorna o texto da última resposta da biblioteca.
+    fn esocial_ultimo_retorno() -> resposta = "eSocial_UltimoRetorno";
+    fn esocial_nome() -> resposta = "eSocial_Nome";
+    fn esocial_versao() -> resposta = "eSocial_Versao";
+
+    fn esocial_config_ler(arquivo_config: texto) -> codigo = "eSocial_ConfigLer";
+    fn esocial_config_gravar(arquivo_config: texto) -> codigo = "eSocial_ConfigGravar";
+    fn esocial_config_ler_valor(sessao: texto, chave: texto) -> resposta = "eSocial_ConfigLerValor";
+    fn esocial_config_gravar_valor(sessao: texto, chave: texto, valor: texto) -> codigo = "eSocial_ConfigGravarValor";
+    fn esocial_config_importar(arquivo_config: texto) -> codigo = "eSocial_ConfigImportar";
+    fn esocial_config_exportar() -> resposta = "eSocial_ConfigExportar";
+
+    fn criar_evento_esocial(arquivo_ini: texto) -> codigo = "eSocial_CriarEventoeSocial";
+    fn enviar_esocial(grupo: inteiro) -> resposta = "eSocial_EnviareSocial";
+    fn consultar_protocolo(protocolo: texto) -> resposta = "eSocial_ConsultareSocial";
+    fn criar_enviar_esocial(arquivo_ini: texto, grupo: inteiro) -> codigo = "eSocial_CriarEnviareSocial";
+    fn limpar_esocial() -> codigo = "eSocial_LimpareSocial";
+    fn carregar_xml_evento(arquivo_ou_xml: texto) -> codigo = "eSocial_CarregarXMLEventoeSocial";
+    fn set_id_empregador(id_empregador: texto) -> codigo = "eSocial_SetIDEmpregador";
+    fn set_id_transmissor(id_transmissor: texto) -> codigo = "eSocial_SetIDTransmissor";
+    fn set_tipo_empregador(tipo_empregador: inteiro) -> codigo = "eSocial_SetTipoEmpregador";
+    fn set_versao_df(versao: texto) -> codigo = "eSocial_SetVersaoDF";
+
+    fn consulta_identificadores_eventos_empregador(
+        id_empregador: texto,
+        tipo_evento: inteiro,
+        periodo_apuracao: texto
+    ) -> resposta = "eSocial_ConsultaIdentificadoresEventosEmpregador";
+    fn consulta_identificadores_eventos_tabela(
+        id_empregador: texto,
+        tipo_evento: inteiro,
+        chave: texto,
+        data_inicial: texto,
+        data_final: texto
+    ) -> resposta = "eSocial_ConsultaIdentificadoresEventosTabela";
+    fn consulta_identificadores_eventos_trabalhador(
+        id_empregador: texto,
+        cpf_trabalhador: texto,
+        data_inicial: texto,
+        data_final: texto
+    ) -> resposta = "eSocial_ConsultaIdentificadoresEventosTrabalhador";
+    fn download_eventos(
+        id_empregador: texto,
+        cpf_trabalhador: texto,
+        data_inicial: texto,
+        data_final: texto
+    ) -> resposta = "eSocial_DownloadEventos";
+    fn obter_certificados() -> resposta = "eSocial_ObterCertificados";
+    fn esocial_validar() -> codigo = "eSocial_Validar";
+}
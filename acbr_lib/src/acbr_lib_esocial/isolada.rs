@@ -0,0 +1,298 @@
+use std::env;
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::error::ACBrError;
+use crate::localizador::VARIAVEL_ESOCIAL;
+
+use super::chave_oculta;
+use super::protocolo::{self, CONFIG_ARQUIVO, CONFIG_MODELO, CONFIG_TEMPORARIA};
+
+/// Variável de ambiente com o caminho do executável do worker.
+pub const VARIAVEL_WORKER: &str = "ACBRLIB_ESOCIAL_WORKER";
+
+/// Nome do executável do worker, procurado ao lado do executável atual.
+const NOME_WORKER: &str = "acbr_esocial_worker";
+
+#[derive(Debug, Clone)]
+enum ConfigSessao {
+    Arquivo(PathBuf),
+    Modelo(PathBuf),
+    Temporaria,
+}
+
+/// Configuração de uma `SessaoIsolada`.
+#[derive(Clone)]
+pub struct ConfigWorker {
+    executavel: Option<PathBuf>,
+    biblioteca: Option<PathBuf>,
+    config: ConfigSessao,
+    chave_criptografia: String,
+}
+
+impl ConfigWorker {
+    /// Sessão com configuração temporária, criada pelo worker.
+    pub fn new() -> Self {
+        ConfigWorker {
+            executavel: None,
+            biblioteca: None,
+            config: ConfigSessao::Temporaria,
+            chave_criptografia: String::new(),
+        }
+    }
+
+    /// Executável do worker. Sem ele, é usado o de `ACBRLIB_ESOCIAL_WORKER` ou o
+    /// `acbr_esocial_worker` ao lado do executável atual.
+    pub fn com_executavel(mut self, executavel: impl Into<PathBuf>) -> Self {
+        self.executavel = Some(executavel.into());
+        self
+    }
+
+    /// Biblioteca carregada pelo worker, informada a ele por `ACBRLIB_ESOCIAL_PATH`.
+    pub fn com_biblioteca(mut self, biblioteca: impl Into<PathBuf>) -> Self {
+        self.biblioteca = Some(biblioteca.into());
+        self
+    }
+
+    /// Arquivo de configuração usado diretamente pela sessão do worker.
+    pub fn com_arquivo_config(mut self, arquivo: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSessao::Arquivo(arquivo.into());
+        self
+    }
+
+    /// Arquivo de configuração copiado para a sessão a cada início do worker.
+    pub fn com_modelo_config(mut self, modelo: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSessao::Modelo(modelo.into());
+        self
+    }
+
+    pub fn com_chave_criptografia(mut self, chave: impl Into<String>) -> Self {
+        self.chave_criptografia = chave.into();
+        self
+    }
+
+    fn executavel(&self) -> PathBuf {
+        if let Some(executavel) = &self.executavel {
+            return executavel.clone();
+        }
+
+        if let Some(executavel) = env::var_os(VARIAVEL_WORKER) {
+            return executavel.into();
+        }
+
+        let nome = format!("{}{}", NOME_WORKER, env::consts::EXE_SUFFIX);
+        env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.join(&nome)))
+            .unwrap_or_else(|| nome.into())
+    }
+
+    fn inicializacao(&self) -> [String; 4] {
+        let (modo, caminho) = match &self.config {
+            ConfigSessao::Arquivo(arquivo) => (CONFIG_ARQUIVO, arquivo.display().to_string()),
+            ConfigSessao::Modelo(modelo) => (CONFIG_MODELO, modelo.display().to_string()),
+            ConfigSessao::Temporaria => (CONFIG_TEMPORARIA, String::new()),
+        };
+
+        [
+            protocolo::INICIALIZAR.to_string(),
+            modo.to_string(),
+            caminho,
+            self.chave_criptografia.clone(),
+        ]
+    }
+}
+
+impl fmt::Debug for ConfigWorker {
+    /// A chave de criptografia aparece como `***`, como a senha na `ConfigESocial`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigWorker")
+            .field("executavel", &self.executavel)
+            .field("biblioteca", &self.biblioteca)
+            .field("config", &self.config)
+            .field(
+                "chave_criptografia",
+                &chave_oculta(&self.chave_criptografia),
+            )
+            .finish()
+    }
+}
+
+impl Default for ConfigWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Processo worker em execução, com a sua entrada e saída padrão.
+struct Worker {
+    processo: Child,
+    /// Fica vazia apenas no `drop`, para fechar a entrada antes de aguardar o processo.
+    entrada: Option<ChildStdin>,
+    saida: BufReader<ChildStdout>,
+}
+
+impl Worker {
+    fn iniciar(config: &ConfigWorker) -> Result<Self, ACBrError> {
+        let executavel = config.executavel();
+        let mut comando = Command::new(&executavel);
+        comando.stdin(Stdio::piped()).stdout(Stdio::piped());
+
+        if let Some(biblioteca) = &config.biblioteca {
+            comando.env(VARIAVEL_ESOCIAL, biblioteca);
+        }
+
+        let mut processo = comando.spawn().map_err(|erro| {
+            ACBrError::Worker(format!(
+                "não foi possível iniciar {}: {}",
+                executavel.display(),
+                erro
+            ))
+        })?;
+
+        let entrada = processo.stdin.take();
+        let Some(saida) = processo.stdout.take() else {
+            let _ = processo.kill();
+            let _ = processo.wait();
+            return Err(ACBrError::Worker(
+                "a saída padrão do processo não está disponível".into(),
+            ));
+        };
+        let mut worker = Worker {
+            processo,
+            entrada,
+            saida: BufReader::new(saida),
+        };
+
+        worker.requisitar(&config.inicializacao())?;
+        Ok(worker)
+    }
+
+    /// Envia a requisição e aguarda a resposta. Um erro de comunicação indica que o
+    /// processo terminou, e o erro informa como.
+    fn requisitar<S: AsRef<str>>(&mut self, campos: &[S]) -> Result<String, ACBrError> {
+        let linha = protocolo::codificar(campos);
+        let mut resposta = String::new();
+
+        let Some(entrada) = self.entrada.as_mut() else {
+            return Err(ACBrError::Worker(
+                "a entrada padrão do processo já foi fechada".into(),
+            ));
+        };
+        let lida = entrada
+            .write_all(linha.as_bytes())
+            .and_then(|()| entrada.flush())
+            .and_then(|()| self.saida.read_line(&mut resposta));
+
+        match lida {
+            Ok(tamanho) if tamanho > 0 => {
+                protocolo::decodificar_resultado(protocolo::decodificar(&resposta))
+            }
+            _ => Err(ACBrError::Worker(self.encerrar())),
+        }
+    }
+
+    fn ativo(&mut self) -> bool {
+        matches!(self.processo.try_wait(), Ok(None))
+    }
+
+    /// Aguarda o fim do processo e descreve como ele terminou.
+    fn encerrar(&mut self) -> String {
+        let _ = self.processo.kill();
+
+        match self.processo.wait() {
+            Ok(status) => format!("o processo terminou inesperadamente ({})", status),
+            Err(erro) => format!("o processo terminou inesperadamente: {}", erro),
+        }
+    }
+}
+
+/// Sessão do eSocial executada em um processo separado.
+///
+/// Uma falha de acesso à memória dentro da ACBrLib derruba apenas o worker: a chamada em
+/// andamento retorna `ACBrError::Worker` e a próxima inicia um novo processo, com uma
+/// sessão criada do zero a partir da mesma configuração. As alterações feitas na sessão
+/// anterior (configuração gravada, eventos carregados) são perdidas.
+///
+/// Os métodos são os mesmos de `ACBrLibEsocial`. A sessão pode ser compartilhada entre
+/// threads, e as chamadas são atendidas uma de cada vez. A biblioteca não deve escrever
+/// na saída padrão do worker, que é usada pelo protocolo.
+pub struct SessaoIsolada {
+    config: ConfigWorker,
+    worker: Mutex<Option<Worker>>,
+    reinicios: AtomicU32,
+}
+
+impl SessaoIsolada {
+    /// Inicia o worker e cria a sessão dentro dele.
+    pub fn new(config: ConfigWorker) -> Result<Self, ACBrError> {
+        let worker = Worker::iniciar(&config)?;
+
+        Ok(SessaoIsolada {
+            config,
+            worker: Mutex::new(Some(worker)),
+            reinicios: AtomicU32::new(0),
+        })
+    }
+
+    /// Quantas vezes o worker foi iniciado novamente depois de terminar.
+    pub fn reinicios(&self) -> u32 {
+        self.reinicios.load(Ordering::Relaxed)
+    }
+
+    pub fn obter_ultimo_retorno(&self) -> Result<String, ACBrError> {
+        self.esocial_ultimo_retorno()
+    }
+
+    /// Finaliza a sessão e aguarda o fim do worker, retornando o erro da finalização.
+    pub fn finalizar(self) -> Result<(), ACBrError> {
+        let worker = self
+            .worker
+            .into_inner()
+            .unwrap_or_else(|erro| erro.into_inner());
+
+        match worker {
+            Some(mut worker) => worker.requisitar(&[protocolo::FINALIZAR]).map(drop),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn chamar(&self, requisicao: &[String]) -> Result<String, ACBrError> {
+        let mut worker = self.bloquear();
+
+        let mut atual = match worker.take() {
+            Some(atual) => atual,
+            None => {
+                let novo = Worker::iniciar(&self.config)?;
+                self.reinicios.fetch_add(1, Ordering::Relaxed);
+                novo
+            }
+        };
+
+        let resultado = atual.requisitar(requisicao);
+
+        // Um worker que terminou é descartado, e a próxima chamada inicia outro
+        if atual.ativo() {
+            *worker = Some(atual);
+        }
+
+        resultado
+    }
+
+    fn bloquear(&self) -> MutexGuard<'_, Option<Worker>> {
+        // O worker é descartado quando uma chamada falha, então o lock pode ser recuperado
+        self.worker.lock().unwrap_or_else(|erro| erro.into_inner())
+    }
+}
+
+impl Drop for Worker {
+    /// Fechar a entrada faz o worker finalizar a sessão e terminar.
+    fn drop(&mut self) {
+        drop(self.entrada.take());
+        let _ = self.processo.wait();
+    }
+}
@@ -1,939 +1,639 @@
-use std::ffi::{CStr, CString};
-// use std::fmt::Debug;
-use std::mem::transmute;
+#[cfg(feature = "async")]
+mod assincrona;
+mod com_prazo;
+mod compartilhada;
+pub mod config;
+pub mod esquemas;
+mod executor;
+pub(crate) mod funcoes;
+mod isolada;
+mod pool;
+mod protocolo;
+pub mod respostas;
+pub mod validacao;
+mod worker;
+
+use std::cell::Cell;
+use std::ffi::CString;
 use std::os::raw::{c_char, c_int, c_void};
-use std::ptr;
-// use std::sync::OnceLock;
-
-use crate::utils::dynamic_library::{get_function, read_lib_file, unread_lib_file, ACBrLibType};
-
-// #[derive(Debug)]
-// struct SafeHandle(*mut c_void);
-
-// unsafe impl Send for SafeHandle {}
-// unsafe impl Sync for SafeHandle {}
-
-// static DLL_HANDLE: OnceLock<SafeHandle> = OnceLock::new();
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs, process, ptr};
+
+use crate::error::ACBrError;
+use crate::localizador::LocalizadorBiblioteca;
+use crate::utils::dynamic_library::{
+    configurar_localizador, read_lib_file, reload_lib_file, ACBrLibType, Biblioteca, FuncoesLib,
+};
+use crate::utils::windows1252;
+
+use self::config::{CodificacaoResposta, ConfigESocial};
+use self::funcoes::FuncoesESocial;
+
+#[cfg(feature = "async")]
+pub use self::assincrona::SessaoAssincrona;
+pub use self::com_prazo::SessaoComPrazo;
+pub use self::compartilhada::SessaoCompartilhada;
+pub use self::isolada::{ConfigWorker, SessaoIsolada, VARIAVEL_WORKER};
+pub use self::pool::{ConfigPool, ESocialPool, EstatisticasPool, SessaoPool};
+pub use self::worker::executar_worker;
+
+/// Tamanho inicial do buffer entregue às funções que devolvem texto. Respostas maiores
+/// são lidas por completo em uma segunda chamada a `eSocial_UltimoRetorno`.
+const TAMANHO_BUFFER_RESPOSTA: usize = 16 * 1024;
+
+/// Sessão da ACBrLibeSocial.
+///
+/// Só é criada já inicializada (`eSocial_Inicializar`), então todos os métodos têm uma
+/// instância válida para usar. Ao ser liberada, chama `eSocial_Finalizar` e apaga o arquivo
+/// de configuração temporário, quando foi a sessão que o criou.
+///
+/// Uma instância da ACBrLib não aceita chamadas simultâneas, por isso a sessão pode ser
+/// movida entre threads, mas não compartilhada nem clonada. Para usar a mesma sessão em
+/// várias threads, use [`SessaoCompartilhada`].
+///
+/// ```compile_fail
+/// fn exige_sync<T: Sync>() {}
+/// exige_sync::<acbr_lib::acbr_lib_esocial::ACBrLibEsocial>();
+/// ```
+///
+/// ```compile_fail
+/// fn exige_clone<T: Clone>() {}
+/// exige_clone::<acbr_lib::acbr_lib_esocial::ACBrLibEsocial>();
+/// ```
+pub struct ACBrLibEsocial {
+    biblioteca: Arc<Biblioteca>,
+    pointer: *mut c_void,
+    config: ArquivoConfig,
+    /// Código de retorno da última chamada à biblioteca.
+    ultimo_codigo: Cell<c_int>,
+    /// Codificação dos textos trocados com a biblioteca, lida de
+    /// `[Principal] CodificacaoResposta` ao criar a sessão e quando a configuração muda.
+    codificacao: Cell<CodificacaoResposta>,
+}
 
+/// Arquivo de configuração usado pela sessão.
 #[derive(Clone)]
-pub struct ACBrLibEsocial {
-    lib_handle: *mut c_void,
-    pub pointer: *mut c_void,
-    config_path: Option<String>,
+enum ArquivoConfig {
+    /// Arquivo informado por quem criou a sessão, que é mantido.
+    Externo(PathBuf),
+    /// Arquivo criado para a sessão em um diretório privado, apagado junto com ele quando a
+    /// sessão é liberada.
+    Temporario {
+        diretorio: PathBuf,
+        arquivo: PathBuf,
+    },
 }
 
-impl ACBrLibEsocial {
-    pub fn new() -> Result<Self, String> {
-        let lib_handle = read_lib_file(ACBrLibType::Esocial)?;
+impl ArquivoConfig {
+    fn temporario() -> Result<Self, ACBrError> {
+        let diretorio = diretorio_privado()?;
 
-        Ok(ACBrLibEsocial {
-            lib_handle,
-            pointer: ptr::null_mut(),
-            config_path: None,
+        Ok(ArquivoConfig::Temporario {
+            arquivo: diretorio.join("config.ini"),
+            diretorio,
         })
     }
 
-    pub fn esocial_inicializar<T: AsRef<str>, U: AsRef<str>>(
-        &mut self,
+    fn caminho(&self) -> &Path {
+        match self {
+            ArquivoConfig::Externo(caminho)
+            | ArquivoConfig::Temporario {
+                arquivo: caminho, ..
+            } => caminho,
+        }
+    }
+}
+
+impl ACBrLibEsocial {
+    /// Cria uma sessão usando o arquivo de configuração informado, que não é apagado.
+    pub fn new<T: AsRef<str>, U: AsRef<str>>(
         arquivo_config: T,
         chave_criptografia: U,
-    ) -> Result<i32, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
-        }
+    ) -> Result<Self, ACBrError> {
+        let config = ArquivoConfig::Externo(PathBuf::from(arquivo_config.as_ref()));
 
-        match get_function(self.lib_handle, "eSocial_Inicializar") {
-            Ok(function) => unsafe {
-                let esocial_inicializar: extern "stdcall" fn(
-                    *mut *mut c_void,
-                    *const c_char,
-                    *const c_char,
-                ) -> i32 = transmute(function);
-
-                let config_path = match CString::new(arquivo_config.as_ref()) {
-                    Ok(path) => path,
-                    Err(_) => {
-                        return Err("Falha ao converter arquivo_config".to_string());
-                    }
-                };
-
-                let chave_crypt = match CString::new(chave_criptografia.as_ref()) {
-                    Ok(chave) => chave,
-                    Err(_) => {
-                        return Err("Falha ao converter chave_criptografia".to_string());
-                    }
-                };
-
-                let mut temp_pointer: *mut c_void = ptr::null_mut();
-
-                let resultado = esocial_inicializar(
-                    &mut temp_pointer,
-                    config_path.as_ptr(),
-                    chave_crypt.as_ptr(),
-                );
+        Self::inicializar(config, chave_criptografia.as_ref())
+    }
 
-                match resultado {
-                    0 => {
-                        if !temp_pointer.is_null() {
-                            self.pointer = temp_pointer;
-                            self.config_path = Some(arquivo_config.as_ref().to_string());
-                            Ok(resultado)
-                        } else {
-                            unread_lib_file(ACBrLibType::Esocial)?;
-                            self.lib_handle = ptr::null_mut();
-                            Err(
-                                "Ponteiro retornado é nulo mesmo com inicialização bem sucedida"
-                                    .to_string(),
-                            )
-                        }
-                    }
-                    _ => {
-                        unread_lib_file(ACBrLibType::Esocial)?;
-                        self.lib_handle = ptr::null_mut();
-                        Err(format!("Erro na inicialização: {}", resultado))
-                    }
-                }
-            },
-            Err(error) => Err(error),
-        }
+    /// Cria uma sessão com um arquivo de configuração temporário, apagado ao liberar a sessão.
+    ///
+    /// O arquivo fica em um diretório criado só para a sessão, acessível apenas pelo
+    /// usuário atual, e removido junto com ele.
+    pub fn com_config_temporaria<U: AsRef<str>>(chave_criptografia: U) -> Result<Self, ACBrError> {
+        Self::inicializar(ArquivoConfig::temporario()?, chave_criptografia.as_ref())
     }
 
-    pub fn esocial_finalizar(&mut self) -> Result<i32, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
-        }
+    /// Cria uma sessão com uma cópia temporária do arquivo de configuração `modelo`.
+    pub fn com_modelo_config<T: AsRef<Path>, U: AsRef<str>>(
+        modelo: T,
+        chave_criptografia: U,
+    ) -> Result<Self, ACBrError> {
+        let config = ArquivoConfig::temporario()?;
 
-        if self.pointer.is_null() {
-            return Err("O ponteiro da Lib esta nulo".into());
+        if let Err(erro) = fs::copy(modelo.as_ref(), config.caminho()) {
+            remover_temporario(&config);
+            return Err(ACBrError::ArquivoNaoEncontrado(format!(
+                "{}: {}",
+                modelo.as_ref().display(),
+                erro
+            )));
         }
 
-        match get_function(self.lib_handle, "eSocial_Finalizar") {
-            Ok(function) => unsafe {
-                let esocial_finalizar: extern "stdcall" fn(*mut c_void) -> i32 =
-                    transmute(function);
-
-                let resultado = esocial_finalizar(self.pointer);
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+        Self::inicializar(config, chave_criptografia.as_ref())
     }
 
-    pub fn esocial_ultimo_retorno(&self) -> Result<String, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
-        }
-
-        match get_function(self.lib_handle, "eSocial_UltimoRetorno") {
-            Ok(function) => unsafe {
-                let esocial_ultimo_retorno: extern "stdcall" fn(
-                    *mut c_void,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> c_int = transmute(function);
-
-                let cap: usize = 1024 * 1024;
-                let mut buf = vec![0u8; cap];
-                let mut tamanho: c_int = cap as c_int;
-
-                let _rc = esocial_ultimo_retorno(
-                    self.pointer,
-                    buf.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
-
-                let message = CStr::from_ptr(buf.as_ptr() as *const c_char)
-                    .to_string_lossy()
-                    .into_owned();
+    /// Cria uma sessão com a configuração em memória, sem gravá-la em disco.
+    ///
+    /// A biblioteca é inicializada com um arquivo temporário próprio, como em
+    /// `com_config_temporaria`, e os valores de `config` são aplicados em seguida com
+    /// `aplicar_config`. Assim, a senha do certificado (`[DFe] Senha`) só existe na memória
+    /// da biblioteca, desde que a sessão não chame `esocial_config_gravar`.
+    pub fn com_config<U: AsRef<str>>(
+        config: &ConfigESocial,
+        chave_criptografia: U,
+    ) -> Result<Self, ACBrError> {
+        let sessao = Self::com_config_temporaria(chave_criptografia)?;
+        sessao.aplicar_config(config)?;
 
-                Ok(message)
-            },
-            Err(error) => Err(error),
-        }
+        Ok(sessao)
     }
 
-    pub fn esocial_nome(&self) -> Result<String, String> {
-        match get_function(self.lib_handle, "eSocial_Nome") {
-            Ok(function) => unsafe {
-                let esocial_nome: extern "stdcall" fn(
-                    *mut c_void,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> c_int = transmute(function);
-
-                let mut tamanho = 0;
-                let resultado = esocial_nome(self.pointer, ptr::null_mut(), &mut tamanho);
-
-                if resultado != 0 {
-                    return Err(format!(
-                        "Erro ao obter o tamanho necessário, código: {}",
-                        resultado
-                    ));
-                }
-
-                let mut buffer: Vec<u8> = vec![0; tamanho as usize + 1];
-
-                let resultado = esocial_nome(
-                    self.pointer,
-                    buffer.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
-
-                if resultado == 0 {
-                    buffer.resize(tamanho as usize, 0);
-                    if !buffer.ends_with(&[0]) {
-                        buffer.push(0);
-                    }
-
-                    let message = CStr::from_bytes_with_nul(&buffer)
-                        .expect("Erro ao converter a mensagem para CStr")
-                        .to_string_lossy()
-                        .into_owned();
-
-                    Ok(message)
-                } else {
-                    Err(format!("Erro ao obter o nome, código: {}", resultado))
-                }
-            },
-            Err(error) => Err(error),
-        }
+    /// Define onde a biblioteca será procurada. Vale para o próximo carregamento, então deve
+    /// ser chamado antes da primeira sessão ou seguido de `recarregar_biblioteca`.
+    pub fn configurar_biblioteca(localizador: LocalizadorBiblioteca) -> Result<(), ACBrError> {
+        configurar_localizador(ACBrLibType::Esocial, localizador)
     }
-    pub fn esocial_versao(&self) -> Result<String, String> {
-        match get_function(self.lib_handle, "eSocial_Versao") {
-            Ok(function) => unsafe {
-                let esocial_versao: extern "stdcall" fn(
-                    *mut c_void,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> c_int = transmute(function);
-
-                let mut tamanho = 0;
-                let resultado = esocial_versao(self.pointer, ptr::null_mut(), &mut tamanho);
-
-                if resultado != 0 {
-                    return Err(format!(
-                        "Erro ao obter o tamanho necessário, código: {}",
-                        resultado
-                    ));
-                }
 
-                let mut buffer: Vec<u8> = vec![0; tamanho as usize + 1];
-
-                let resultado = esocial_versao(
-                    self.pointer,
-                    buffer.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
-
-                if resultado == 0 {
-                    buffer.resize(tamanho as usize, 0);
-                    if !buffer.ends_with(&[0]) {
-                        buffer.push(0);
-                    }
-
-                    let message = CStr::from_bytes_with_nul(&buffer)
-                        .expect("Erro ao converter a mensagem para CStr")
-                        .to_string_lossy()
-                        .into_owned();
-
-                    Ok(message)
-                } else {
-                    Err(format!("Erro ao obter a versão, código: {}", resultado))
-                }
-            },
-            Err(error) => Err(error),
-        }
+    /// Carrega a biblioteca novamente para as próximas sessões, aguardando as chamadas
+    /// em andamento. As sessões existentes seguem usando a versão que já tinham.
+    pub fn recarregar_biblioteca() -> Result<(), ACBrError> {
+        reload_lib_file(ACBrLibType::Esocial)
     }
 
-    pub fn criar_evento_esocial<T: AsRef<str>>(&self, arquivo_ini: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_CriarEventoeSocial") {
-            Ok(function) => unsafe {
-                let criar_evento: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
-
-                let arquivo_ini_path = CString::new(arquivo_ini.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_ini' falhou");
-
-                let resultado = criar_evento(self.pointer, arquivo_ini_path.as_ptr());
+    /// Arquivo de configuração da sessão.
+    pub fn arquivo_config(&self) -> &Path {
+        self.config.caminho()
+    }
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+    /// Indica se a última chamada retornou um código fatal (veja `ACBrError::codigo_fatal`),
+    /// caso em que a sessão não deve ser reutilizada.
+    pub fn ultima_chamada_fatal(&self) -> bool {
+        ACBrError::codigo_fatal(self.ultimo_codigo.get())
     }
 
-    pub fn enviar_esocial(&self, grupo: i32) -> Result<i32, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
-        }
+    /// Finaliza a sessão, retornando o erro de `eSocial_Finalizar` se houver.
+    pub fn finalizar(mut self) -> Result<(), ACBrError> {
+        self.esocial_finalizar()
+    }
 
-        match get_function(self.lib_handle, "eSocial_EnviareSocial") {
-            Ok(function) => unsafe {
-                type ESocialEnviar =
-                    extern "stdcall" fn(*mut c_void, c_int, *mut c_char, *mut c_int) -> c_int;
+    fn inicializar(config: ArquivoConfig, chave_criptografia: &str) -> Result<Self, ACBrError> {
+        let resultado = read_lib_file(ACBrLibType::Esocial)
+            .and_then(|biblioteca| Self::criar(biblioteca, config.clone(), chave_criptografia))
+            .and_then(|sessao| {
+                sessao.atualizar_codificacao()?;
+                sessao.definir_tipo_resposta()?;
+                Ok(sessao)
+            });
 
-                let enviar_esocial: ESocialEnviar = transmute(function);
+        if resultado.is_err() {
+            remover_temporario(&config);
+        }
 
-                let cap: usize = 64 * 1024;
-                let mut buf = vec![0u8; cap];
-                let mut tamanho: c_int = cap as c_int;
+        resultado
+    }
 
-                let rc = enviar_esocial(
-                    self.pointer,
-                    grupo as c_int,
-                    buf.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
+    fn criar(
+        biblioteca: Arc<Biblioteca>,
+        config: ArquivoConfig,
+        chave_criptografia: &str,
+    ) -> Result<Self, ACBrError> {
+        let chamada = biblioteca.chamada()?;
+
+        let config_path = config
+            .caminho()
+            .to_str()
+            .and_then(|caminho| CString::new(caminho).ok())
+            .ok_or(ACBrError::TextoInvalido("arquivo_config"))?;
+
+        let chave_crypt = CString::new(chave_criptografia)
+            .map_err(|_| ACBrError::TextoInvalido("chave_criptografia"))?;
+
+        let esocial_inicializar = funcoes(&biblioteca).inicializar;
+
+        let mut temp_pointer: *mut c_void = ptr::null_mut();
+
+        let resultado = unsafe {
+            esocial_inicializar(
+                &mut temp_pointer,
+                config_path.as_ptr(),
+                chave_crypt.as_ptr(),
+            )
+        };
+
+        match resultado {
+            0 if !temp_pointer.is_null() => {
+                drop(chamada);
+
+                Ok(ACBrLibEsocial {
+                    biblioteca,
+                    pointer: temp_pointer,
+                    config,
+                    ultimo_codigo: Cell::new(0),
+                    codificacao: Cell::new(CodificacaoResposta::Utf8),
+                })
+            }
+            0 => Err(ACBrError::PonteiroNulo),
+            _ => {
+                // Sem o ponteiro da instância não há último retorno para consultar
+                let mensagem = format!(
+                    "eSocial_Inicializar falhou com o arquivo de configuração {}",
+                    config.caminho().display()
                 );
-
-                Ok(rc as i32)
-            },
-            Err(error) => Err(error),
+                Err(ACBrError::from_codigo(resultado, mensagem))
+            }
         }
     }
 
-    pub fn obter_ultimo_retorno(&self) -> Result<String, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
+    fn esocial_finalizar(&mut self) -> Result<(), ACBrError> {
+        if self.pointer.is_null() {
+            return Ok(());
         }
 
-        match get_function(self.lib_handle, "eSocial_UltimoRetorno") {
-            Ok(function) => unsafe {
-                let obter_ultimo_retorno: extern "stdcall" fn(
-                    *mut c_void,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> c_int = transmute(function);
+        let biblioteca = Arc::clone(&self.biblioteca);
+        let _chamada = biblioteca.chamada()?;
 
-                let cap: usize = 1024 * 1024;
-                let mut buf = vec![0u8; cap];
-                let mut tamanho: c_int = cap as c_int;
+        let funcoes = funcoes(&biblioteca);
 
-                let rc = obter_ultimo_retorno(
-                    self.pointer,
-                    buf.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
+        let resultado = unsafe { (funcoes.finalizar)(self.pointer) };
+        let retorno = self.verificar_retorno(funcoes, resultado);
 
-                let message = CStr::from_ptr(buf.as_ptr() as *const c_char)
-                    .to_string_lossy()
-                    .into_owned();
+        // A instância não é mais usada, mesmo que a biblioteca tenha retornado erro
+        self.pointer = ptr::null_mut();
 
-                if rc == 0 {
-                    Ok(message)
-                } else {
-                    let message = if message.len() <= 0 {
-                        "Ultimo retorno vazio".to_owned()
-                    } else {
-                        message
-                    };
-                    Err(format!("Último retorno, mensagem: {}", message))
-                }
-            },
-            Err(error) => Err(error),
-        }
+        retorno
     }
 
-    pub fn consultar_protocolo<T: AsRef<str>>(&self, protocolo: T) -> Result<i32, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
-        }
+    pub fn obter_ultimo_retorno(&self) -> Result<String, ACBrError> {
+        self.esocial_ultimo_retorno()
+    }
 
-        match get_function(self.lib_handle, "eSocial_ConsultareSocial") {
-            Ok(function) => unsafe {
-                type ESocialConsultar = extern "stdcall" fn(
-                    *mut c_void,
-                    *const c_char,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> c_int;
+    /// Codificação dos textos enviados e recebidos pela sessão
+    /// (`[Principal] CodificacaoResposta`).
+    pub fn codificacao(&self) -> CodificacaoResposta {
+        self.codificacao.get()
+    }
 
-                let consultar_protocolo: ESocialConsultar = transmute(function);
+    fn atualizar_codificacao(&self) -> Result<(), ACBrError> {
+        let biblioteca = self.biblioteca();
+        let _chamada = biblioteca.chamada()?;
 
-                let protocolo = CString::new(protocolo.as_ref())
-                    .map_err(|_| "CString protocolo".to_string())?;
+        self.ler_codificacao(funcoes(biblioteca))
+    }
 
-                let cap: usize = 64 * 1024;
-                let mut buf = vec![0u8; cap];
-                let mut tamanho: c_int = cap as c_int;
+    /// Lê `[Principal] CodificacaoResposta` da biblioteca. Sem o valor, a sessão usa UTF-8;
+    /// qualquer outra falha da leitura é devolvida, para a sessão não ficar com a
+    /// codificação errada.
+    fn ler_codificacao(&self, funcoes: &FuncoesESocial) -> Result<(), ACBrError> {
+        let (secao, chave) = (c"Principal", c"CodificacaoResposta");
+        let ler_valor = funcoes.esocial_config_ler_valor;
 
-                let rc = consultar_protocolo(
+        let valor = self
+            .ler_buffer(funcoes, |resposta, tamanho| unsafe {
+                ler_valor(
                     self.pointer,
-                    protocolo.as_ptr(),
-                    buf.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
+                    secao.as_ptr(),
+                    chave.as_ptr(),
+                    resposta,
+                    tamanho,
+                )
+            })
+            .map_err(|resultado| self.falha(funcoes, resultado));
+
+        let codificacao = match valor {
+            Ok(valor) if !valor.is_empty() => valor
+                .parse()
+                .ok()
+                .and_then(CodificacaoResposta::from_codigo)
+                .ok_or(ACBrError::ConfigInvalida {
+                    secao: "Principal".into(),
+                    chave: "CodificacaoResposta".into(),
+                    valor,
+                })?,
+            Ok(_) | Err(ACBrError::SessaoNaoEncontrada(_) | ACBrError::ChaveNaoEncontrada(_)) => {
+                CodificacaoResposta::Utf8
+            }
+            Err(erro) => return Err(erro),
+        };
 
-                Ok(rc as i32)
-            },
-            Err(error) => Err(error),
-        }
+        self.codificacao.set(codificacao);
+        Ok(())
     }
 
-    pub fn criar_enviar_esocial<T: AsRef<str>>(
-        &self,
-        arquivo_ini: T,
-        grupo: i32,
-    ) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_CriarEnviareSocial") {
-            Ok(function) => unsafe {
-                let criar_enviar_esocial: extern "stdcall" fn(
-                    *mut c_void,
-                    *const c_char,
-                    c_int,
-                ) -> i32 = transmute(function);
-
-                let arquivo_ini_path = CString::new(arquivo_ini.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_ini' falhou");
-                let resultado =
-                    criar_enviar_esocial(self.pointer, arquivo_ini_path.as_ptr(), grupo);
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+    fn biblioteca(&self) -> &Biblioteca {
+        &self.biblioteca
     }
 
-    pub fn limpar_esocial(&self) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_LimpareSocial") {
-            Ok(function) => unsafe {
-                let limpar_esocial: extern "stdcall" fn(*mut c_void) -> i32 = transmute(function);
-
-                let resultado = limpar_esocial(self.pointer);
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
-    }
+    /// Converte o código de retorno da biblioteca, anexando o último retorno em caso de erro.
+    fn verificar_retorno(
+        &self,
+        funcoes: &FuncoesESocial,
+        resultado: c_int,
+    ) -> Result<(), ACBrError> {
+        self.ultimo_codigo.set(resultado);
 
-    pub fn carregar_xml_evento<T: AsRef<str>>(&self, arquivo_ou_xml: T) -> Result<i32, String> {
-        if self.lib_handle.is_null() {
-            return Err("Handle da biblioteca é nulo".to_string());
+        if resultado < 0 {
+            return Err(self.falha(funcoes, resultado));
         }
 
-        match get_function(self.lib_handle, "eSocial_CarregarXMLEventoeSocial") {
-            Ok(function) => unsafe {
-                let carregar_xml_evento: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
-
-                let xml = CString::new(arquivo_ou_xml.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_ini' falhou");
-
-                let resultado = carregar_xml_evento(self.pointer, xml.as_ptr());
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+        Ok(())
     }
 
-    pub fn set_id_empregador<T: AsRef<str>>(&self, id_empregador: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_SetIDEmpregador") {
-            Ok(function) => unsafe {
-                let set_id_empregador: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
+    /// Monta o erro do código de retorno com o texto de `eSocial_UltimoRetorno`.
+    fn falha(&self, funcoes: &FuncoesESocial, resultado: c_int) -> ACBrError {
+        let ultimo_retorno = funcoes.esocial_ultimo_retorno;
 
-                let id_empregador = CString::new(id_empregador.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_ini' falhou");
+        let mensagem = if self.pointer.is_null() {
+            String::new()
+        } else {
+            self.ler_buffer(funcoes, |resposta, tamanho| unsafe {
+                ultimo_retorno(self.pointer, resposta, tamanho)
+            })
+            .unwrap_or_default()
+        };
 
-                let resultado = set_id_empregador(self.pointer, id_empregador.as_ptr());
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+        ACBrError::from_codigo(resultado, mensagem)
     }
 
-    pub fn set_id_transmissor<T: AsRef<str>>(&self, id_transmissor: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_SetIDTransmissor") {
-            Ok(function) => unsafe {
-                let set_id_transmissor: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
-
-                let id_transmissor = CString::new(id_transmissor.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_ini' falhou");
-
-                let resultado = set_id_transmissor(self.pointer, id_transmissor.as_ptr());
+    /// Executa uma função que devolve texto e converte o código de retorno em `ACBrError`.
+    fn ler_resposta<F>(&self, funcoes: &FuncoesESocial, chamada: F) -> Result<String, ACBrError>
+    where
+        F: FnOnce(*mut c_char, *mut c_int) -> c_int,
+    {
+        let resposta = self.ler_buffer(funcoes, chamada);
+        self.ultimo_codigo
+            .set(*resposta.as_ref().err().unwrap_or(&0));
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+        resposta.map_err(|resultado| self.falha(funcoes, resultado))
     }
 
-    pub fn set_tipo_empregador(&self, tipo_empregador: i32) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_SetTipoEmpregador") {
-            Ok(function) => unsafe {
-                let set_tipo_empregador: extern "stdcall" fn(*mut c_void, c_int) -> c_int =
-                    transmute(function);
+    /// Executa uma função que devolve texto no buffer `sResposta`/`esTamanho`.
+    ///
+    /// A biblioteca recebe em `esTamanho` quantos caracteres cabem no buffer, sem contar o
+    /// NUL que ela escreve depois deles, e devolve nele o tamanho total da resposta. Quando
+    /// a resposta não coube no buffer, ela continua disponível em `eSocial_UltimoRetorno`,
+    /// que é chamado de novo com o tamanho informado.
+    fn ler_buffer<F>(&self, funcoes: &FuncoesESocial, chamada: F) -> Result<String, c_int>
+    where
+        F: FnOnce(*mut c_char, *mut c_int) -> c_int,
+    {
+        let mut capacidade = TAMANHO_BUFFER_RESPOSTA;
+        let mut buffer: Vec<u8> = vec![0; capacidade + 1];
+        let mut tamanho = capacidade as c_int;
+
+        let resultado = chamada(buffer.as_mut_ptr() as *mut c_char, &mut tamanho);
+
+        if resultado < 0 {
+            return Err(resultado);
+        }
+
+        let mut necessario = tamanho.max(0) as usize;
+
+        // A resposta foi truncada: busca o texto completo no último retorno
+        while necessario > capacidade {
+            capacidade = necessario;
+            buffer = vec![0; capacidade + 1];
+            tamanho = capacidade as c_int;
+
+            let ultimo_retorno = funcoes.esocial_ultimo_retorno;
+            let resultado = unsafe {
+                ultimo_retorno(
+                    self.pointer,
+                    buffer.as_mut_ptr() as *mut c_char,
+                    &mut tamanho,
+                )
+            };
 
-                let resultado = set_tipo_empregador(self.pointer, tipo_empregador);
+            if resultado < 0 {
+                return Err(resultado);
+            }
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
+            necessario = tamanho.max(0) as usize;
         }
-    }
-
-    pub fn set_versao_df<T: AsRef<str>>(&self, versao: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_SetVersaoDF") {
-            Ok(function) => unsafe {
-                let set_versao_df: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
-
-                let versao = CString::new(versao.as_ref())
-                    .expect("A conversão do parâmetro 'versao' falhou");
-
-                let resultado = set_versao_df(self.pointer, versao.as_ptr());
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+        Ok(texto_do_buffer(buffer, necessario, self.codificacao.get()))
     }
+}
 
-    pub fn consulta_identificadores_eventos_empregador<T: AsRef<str>>(
-        &self,
-        id_empregador: T,
-        tipo_evento: i32,
-        periodo_apuracao: T,
-    ) -> Result<i32, String> {
-        match get_function(
-            self.lib_handle,
-            "eSocial_ConsultaIdentificadoresEventosEmpregador",
-        ) {
-            Ok(function) => {
-                unsafe {
-                    let consulta_identificadores_eventos_empregador: extern "stdcall" fn(
-                        *mut c_void,
-                        *const c_char, // idEmpregador
-                        c_int,         // aTipoEvento
-                        *const c_char, // aPeriodoApuracao
-                        *mut c_char,   // sResposta
-                        *mut c_int,    // esTamanho
-                    )
-                        -> c_int = transmute(function);
-
-                    let id_empregador_cstr = CString::new(id_empregador.as_ref())
-                        .expect("Erro ao converter id_empregador para CString");
-                    let periodo_apuracao_cstr = CString::new(periodo_apuracao.as_ref())
-                        .expect("Erro ao converter periodo_apuracao para CString");
-
-                    let mut tamanho: c_int = 0;
-                    let resultado = consulta_identificadores_eventos_empregador(
-                        self.pointer,
-                        id_empregador_cstr.as_ptr(),
-                        tipo_evento,
-                        periodo_apuracao_cstr.as_ptr(),
-                        ptr::null_mut(),
-                        &mut tamanho,
-                    );
-
-                    return Ok(resultado);
-                }
-            }
-            Err(error) => Err(error),
-        }
-    }
+/// Converte o argumento para a codificação da sessão.
+fn texto_nativo(
+    texto: &str,
+    codificacao: CodificacaoResposta,
+    parametro: &'static str,
+) -> Result<CString, ACBrError> {
+    let bytes = match codificacao {
+        CodificacaoResposta::Utf8 => texto.as_bytes().to_vec(),
+        CodificacaoResposta::Ansi => {
+            windows1252::codificar(texto).map_err(|caractere| ACBrError::CaractereInvalido {
+                parametro,
+                caractere,
+            })?
+        }
+    };
+
+    CString::new(bytes).map_err(|_| ACBrError::TextoInvalido(parametro))
+}
 
-    pub fn consulta_identificadores_eventos_tabela<T: AsRef<str>>(
-        &self,
-        id_empregador: T,
-        tipo_evento: i32,
-        chave: T,
-        data_inicial: T,
-        data_final: T,
-    ) -> Result<i32, String> {
-        match get_function(
-            self.lib_handle,
-            "eSocial_ConsultaIdentificadoresEventosTabela",
-        ) {
-            Ok(function) => {
-                unsafe {
-                    let consulta_identificadores_eventos_tabela: extern "stdcall" fn(
-                        *mut c_void,
-                        *const c_char, // idEmpregador
-                        c_int,         // aTipoEvento
-                        *const c_char, // aChave
-                        *const c_char, // aDataInicial
-                        *const c_char, // aDataFinal
-                        *mut c_char,   // sResposta
-                        *mut c_int,    // esTamanho
-                    )
-                        -> c_int = transmute(function);
-
-                    let id_empregador_cstr = CString::new(id_empregador.as_ref())
-                        .expect("Erro ao converter id_empregador para CString");
-                    let chave_cstr =
-                        CString::new(chave.as_ref()).expect("Erro ao converter chave para CString");
-                    let data_inicial_cstr = CString::new(data_inicial.as_ref())
-                        .expect("Erro ao converter data_inicial para CString");
-                    let data_final_cstr = CString::new(data_final.as_ref())
-                        .expect("Erro ao converter data_final para CString");
-
-                    let mut tamanho: c_int = 0;
-                    let resultado = consulta_identificadores_eventos_tabela(
-                        self.pointer,
-                        id_empregador_cstr.as_ptr(),
-                        tipo_evento,
-                        chave_cstr.as_ptr(),
-                        data_inicial_cstr.as_ptr(),
-                        data_final_cstr.as_ptr(),
-                        ptr::null_mut(),
-                        &mut tamanho,
-                    );
-
-                    return Ok(resultado);
-                }
-            }
-            Err(error) => Err(error),
+/// Indica se a chamada pode mudar `[Principal] CodificacaoResposta`, recebendo os
+/// argumentos de texto (os inteiros chegam vazios).
+fn muda_codificacao(simbolo: &str, argumentos: &[&str]) -> bool {
+    match (simbolo, argumentos) {
+        ("eSocial_ConfigLer" | "eSocial_ConfigImportar", _) => true,
+        ("eSocial_ConfigGravarValor", [secao, chave, ..]) => {
+            secao.eq_ignore_ascii_case("Principal")
+                && chave.eq_ignore_ascii_case("CodificacaoResposta")
         }
+        _ => false,
     }
+}
 
-    pub fn consulta_identificadores_eventos_trabalhador<T: AsRef<str>>(
-        &self,
-        id_empregador: T,
-        cpf_trabalhador: T,
-        data_inicial: T,
-        data_final: T,
-    ) -> Result<i32, String> {
-        match get_function(
-            self.lib_handle,
-            "eSocial_ConsultaIdentificadoresEventosTrabalhador",
-        ) {
-            Ok(function) => {
-                unsafe {
-                    let consulta_identificadores_eventos_trabalhador: extern "stdcall" fn(
-                        *mut c_void,
-                        *const c_char, // idEmpregador
-                        *const c_char, // aCPFTrabalhador
-                        *const c_char, // aDataInicial
-                        *const c_char, // aDataFinal
-                        *mut c_char,   // sResposta
-                        *mut c_int,    // esTamanho
-                    )
-                        -> c_int = transmute(function);
-
-                    let id_empregador_cstr = CString::new(id_empregador.as_ref())
-                        .expect("Erro ao converter id_empregador para CString");
-                    let cpf_trabalhador_cstr = CString::new(cpf_trabalhador.as_ref())
-                        .expect("Erro ao converter cpf_trabalhador para CString");
-                    let data_inicial_cstr = CString::new(data_inicial.as_ref())
-                        .expect("Erro ao converter data_inicial para CString");
-                    let data_final_cstr = CString::new(data_final.as_ref())
-                        .expect("Erro ao converter data_final para CString");
-
-                    let mut tamanho: c_int = 0;
-                    let resultado = consulta_identificadores_eventos_trabalhador(
-                        self.pointer,
-                        id_empregador_cstr.as_ptr(),
-                        cpf_trabalhador_cstr.as_ptr(),
-                        data_inicial_cstr.as_ptr(),
-                        data_final_cstr.as_ptr(),
-                        ptr::null_mut(),
-                        &mut tamanho,
-                    );
-
-                    return Ok(resultado);
-                }
-            }
-            Err(error) => Err(error),
-        }
+/// Chave de criptografia para o `Debug` das configurações: `***` quando informada.
+fn chave_oculta(chave: &str) -> &str {
+    if chave.is_empty() {
+        ""
+    } else {
+        "***"
     }
+}
 
-    pub fn download_eventos<T: AsRef<str>>(
-        &self,
-        id_empregador: T,
-        cpf_trabalhador: T,
-        data_inicial: T,
-        data_final: T,
-    ) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_DownloadEventos") {
-            Ok(function) => {
-                unsafe {
-                    let download_eventos: extern "stdcall" fn(
-                        *mut c_void,
-                        *const c_char, // idEmpregador
-                        *const c_char, // aCPFTrabalhador
-                        *const c_char, // aDataInicial
-                        *const c_char, // aDataFinal
-                        *mut c_char,   // sResposta
-                        *mut c_int,    // esTamanho
-                    ) -> c_int = transmute(function);
-
-                    let id_empregador_cstr = CString::new(id_empregador.as_ref())
-                        .expect("Erro ao converter id_empregador para CString");
-                    let cpf_trabalhador_cstr = CString::new(cpf_trabalhador.as_ref())
-                        .expect("Erro ao converter cpf_trabalhador para CString");
-                    let data_inicial_cstr = CString::new(data_inicial.as_ref())
-                        .expect("Erro ao converter data_inicial para CString");
-                    let data_final_cstr = CString::new(data_final.as_ref())
-                        .expect("Erro ao converter data_final para CString");
-
-                    let mut tamanho: c_int = 0;
-                    let resultado = download_eventos(
-                        self.pointer,
-                        id_empregador_cstr.as_ptr(),
-                        cpf_trabalhador_cstr.as_ptr(),
-                        data_inicial_cstr.as_ptr(),
-                        data_final_cstr.as_ptr(),
-                        ptr::null_mut(),
-                        &mut tamanho,
-                    );
-
-                    return Ok(resultado);
-                }
-            }
-            Err(error) => Err(error),
-        }
-    }
+/// Cria um diretório exclusivo da sessão no diretório temporário. No Unix, só o usuário
+/// atual tem acesso a ele.
+fn diretorio_privado() -> Result<PathBuf, ACBrError> {
+    static CONTADOR: AtomicU64 = AtomicU64::new(0);
 
-    pub fn obter_certificados(&self) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_ObterCertificados") {
-            Ok(function) => {
-                unsafe {
-                    let obter_certificados: extern "stdcall" fn(
-                        *mut c_void,
-                        *mut c_char, // sResposta
-                        *mut c_int,  // esTamanho
-                    ) -> c_int = transmute(function);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duracao| duracao.as_nanos())
+        .unwrap_or_default();
 
-                    let mut tamanho: c_int = 0;
-                    let resultado = obter_certificados(self.pointer, ptr::null_mut(), &mut tamanho);
+    let diretorio = env::temp_dir().join(format!(
+        "acbr_esocial_{}_{}_{}",
+        process::id(),
+        CONTADOR.fetch_add(1, Ordering::Relaxed),
+        nanos
+    ));
 
-                    return Ok(resultado);
-                }
-            }
-            Err(error) => Err(error),
-        }
-    }
+    let mut criar = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut criar, 0o700);
 
-    pub fn validar_esocial(&self) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_Validar") {
-            Ok(function) => unsafe {
-                let validar_esocial: extern "stdcall" fn(*mut c_void) -> i32 = transmute(function);
+    criar.create(&diretorio).map_err(|erro| {
+        ACBrError::DiretorioInvalido(format!("{}: {}", diretorio.display(), erro))
+    })?;
 
-                let resultado = validar_esocial(self.pointer);
+    Ok(diretorio)
+}
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+fn remover_temporario(config: &ArquivoConfig) {
+    if let ArquivoConfig::Temporario { diretorio, .. } = config {
+        let _ = fs::remove_dir_all(diretorio);
     }
-    pub fn esocial_config_ler<T: AsRef<str>>(&self, arquivo_config: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigLer") {
-            Ok(function) => unsafe {
-                let esocial_config_ler: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
+}
 
-                let config_path = CString::new(arquivo_config.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
+/// Tabela de funções do eSocial da biblioteca carregada.
+fn funcoes(biblioteca: &Biblioteca) -> &FuncoesESocial {
+    let FuncoesLib::Esocial(funcoes) = &biblioteca.funcoes;
+    funcoes
+}
 
-                let resultado = esocial_config_ler(self.pointer, config_path.as_ptr());
+/// Converte os `tamanho` primeiros bytes do buffer em texto, parando no primeiro NUL.
+fn texto_do_buffer(
+    mut buffer: Vec<u8>,
+    tamanho: usize,
+    codificacao: CodificacaoResposta,
+) -> String {
+    buffer.truncate(tamanho);
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+    if let Some(fim) = buffer.iter().position(|&byte| byte == 0) {
+        buffer.truncate(fim);
     }
 
-    pub fn esocial_config_gravar<T: AsRef<str>>(&self, arquivo_config: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigGravar") {
-            Ok(function) => unsafe {
-                let esocial_config_gravar: extern "stdcall" fn(*mut c_void, *const c_char) -> i32 =
-                    transmute(function);
-
-                let config_path = CString::new(arquivo_config.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
-
-                let resultado = esocial_config_gravar(self.pointer, config_path.as_ptr());
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
-        }
+    match codificacao {
+        CodificacaoResposta::Utf8 => String::from_utf8_lossy(&buffer).into_owned(),
+        CodificacaoResposta::Ansi => windows1252::decodificar(&buffer),
     }
+}
 
-    pub fn esocial_config_ler_valor<T: AsRef<str>>(
-        &self,
-        sessao: T,
-        chave: T,
-    ) -> Result<String, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigLerValor") {
-            Ok(function) => unsafe {
-                let esocial_config_ler_valor: extern "stdcall" fn(
-                    *mut c_void,
-                    *const c_char,
-                    *const c_char,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> i32 = transmute(function);
-
-                let sessao = CString::new(sessao.as_ref())
-                    .expect("A conversão do parâmetro 'sessao' falhou");
-
-                let chave =
-                    CString::new(chave.as_ref()).expect("A conversão do parâmetro 'chave' falhou");
-
-                let mut tamanho = 0;
-
-                let _resultado = esocial_config_ler_valor(
-                    self.pointer,
-                    sessao.as_ptr(),
-                    chave.as_ptr(),
-                    ptr::null_mut(),
-                    &mut tamanho,
-                );
-
-                let mut buffer: Vec<u8> = vec![0; tamanho as usize + 1];
-
-                let _resultado = esocial_config_ler_valor(
-                    self.pointer,
-                    sessao.as_ptr(),
-                    chave.as_ptr(),
-                    buffer.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
+// O ponteiro da instância pode ser usado em outra thread, desde que uma de cada vez
+unsafe impl Send for ACBrLibEsocial {}
 
-                buffer.resize(tamanho as usize, 0);
+impl Drop for ACBrLibEsocial {
+    fn drop(&mut self) {
+        // Finaliza antes de apagar, a biblioteca pode gravar a configuração ao finalizar
+        let _ = self.esocial_finalizar();
 
-                if !buffer.ends_with(&[0]) {
-                    buffer.push(0);
-                }
+        remover_temporario(&self.config);
+    }
+}
 
-                let message = CStr::from_bytes_with_nul(&buffer)
-                    .expect("Erro ao converter a mensagem para CStr")
-                    .to_string_lossy()
-                    .into_owned();
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
 
-                Ok(message)
-            },
-            Err(error) => Err(error),
-        }
+    // Nunca é chamada: os testes abaixo falham antes de chegar na biblioteca
+    extern "C" fn nao_chamar() -> c_int {
+        -10
     }
 
-    pub fn esocial_config_gravar_valor<T: AsRef<str>>(
-        &self,
-        sessao: T,
-        chave: T,
-        valor: T,
-    ) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigGravarValor") {
-            Ok(function) => unsafe {
-                let esocial_config_gravar_valor: extern "stdcall" fn(
-                    *mut c_void,
-                    *const c_char,
-                    *const c_char,
-                    *const c_char,
-                ) -> i32 = transmute(function);
-
-                let sessao = CString::new(sessao.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
-
-                let chave = CString::new(chave.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
-
-                let valor = CString::new(valor.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
-
-                let resultado = esocial_config_gravar_valor(
-                    self.pointer,
-                    sessao.as_ptr(),
-                    chave.as_ptr(),
-                    valor.as_ptr(),
-                );
-
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
+    pub(crate) fn funcoes_de_teste() -> FuncoesESocial {
+        unsafe {
+            FuncoesESocial::resolver(|_| Some(nao_chamar as *const () as *mut c_void)).unwrap()
         }
     }
 
-    pub fn esocial_config_importar<T: AsRef<str>>(&self, arquivo_config: T) -> Result<i32, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigImportar") {
-            Ok(function) => unsafe {
-                let esocial_config_importar: extern "stdcall" fn(
-                    *mut c_void,
-                    *const c_char,
-                ) -> i32 = transmute(function);
-
-                let config_path = CString::new(arquivo_config.as_ref())
-                    .expect("A conversão do parâmetro 'arquivo_config' falhou");
-
-                let resultado = esocial_config_importar(self.pointer, config_path.as_ptr());
+    fn lib_de_teste() -> ACBrLibEsocial {
+        let funcoes = FuncoesLib::Esocial(funcoes_de_teste());
 
-                Ok(resultado)
-            },
-            Err(error) => Err(error),
+        ACBrLibEsocial {
+            biblioteca: Arc::new(Biblioteca::de_teste(funcoes)),
+            pointer: ptr::null_mut(),
+            config: ArquivoConfig::Externo(PathBuf::new()),
+            ultimo_codigo: Cell::new(0),
+            codificacao: Cell::new(CodificacaoResposta::Utf8),
         }
     }
 
-    pub fn esocial_config_exportar(&self) -> Result<String, String> {
-        match get_function(self.lib_handle, "eSocial_ConfigExportar") {
-            Ok(function) => unsafe {
-                let esocial_config_exportar: extern "stdcall" fn(
-                    *mut c_void,
-                    *mut c_char,
-                    *mut c_int,
-                ) -> i32 = transmute(function);
-
-                let mut tamanho = 0;
+    #[test]
+    fn xml_com_byte_nulo_retorna_erro() {
+        let lib = lib_de_teste();
 
-                let resultado =
-                    esocial_config_exportar(self.pointer, ptr::null_mut(), &mut tamanho);
-
-                if resultado != 0 {
-                    return Err(format!(
-                        "Erro ao obter o tamanho necessário, código: {}",
-                        resultado
-                    ));
-                }
-
-                let mut buffer: Vec<u8> = vec![0; tamanho as usize + 1];
-
-                let resultado = esocial_config_exportar(
-                    self.pointer,
-                    buffer.as_mut_ptr() as *mut c_char,
-                    &mut tamanho,
-                );
-
-                if resultado == 0 {
-                    buffer.resize(tamanho as usize, 0);
-
-                    if !buffer.ends_with(&[0]) {
-                        buffer.push(0);
-                    }
+        assert_eq!(
+            lib.carregar_xml_evento("<eSocial>\0</eSocial>"),
+            Err(ACBrError::TextoInvalido("arquivo_ou_xml"))
+        );
+    }
 
-                    let message = CStr::from_bytes_with_nul(&buffer)
-                        .expect("Erro ao converter a mensagem para CStr")
-                        .to_string_lossy()
-                        .into_owned();
+    #[test]
+    fn parametros_com_byte_nulo_retornam_erro() {
+        let lib = lib_de_teste();
+        let inicializar = |config: &str, chave: &str| {
+            ACBrLibEsocial::criar(
+                Arc::clone(&lib.biblioteca),
+                ArquivoConfig::Externo(PathBuf::from(config)),
+                chave,
+            )
+            .map(|_| ())
+        };
+
+        assert_eq!(
+            inicializar("config\0.ini", ""),
+            Err(ACBrError::TextoInvalido("arquivo_config"))
+        );
+        assert_eq!(
+            inicializar("config.ini", "\0"),
+            Err(ACBrError::TextoInvalido("chave_criptografia"))
+        );
+        assert_eq!(
+            lib.esocial_config_gravar_valor("eSocial", "Path\0Schemas", "schemas"),
+            Err(ACBrError::TextoInvalido("chave"))
+        );
+        assert_eq!(
+            lib.set_id_empregador("12345678\0"),
+            Err(ACBrError::TextoInvalido("id_empregador"))
+        );
+        assert_eq!(
+            lib.consulta_identificadores_eventos_tabela("123", 1, "chave", "01/01/2025", "\0"),
+            Err(ACBrError::TextoInvalido("data_final"))
+        );
+        assert_eq!(
+            lib.download_eventos("123", "\0", "01/01/2025", "31/01/2025"),
+            Err(ACBrError::TextoInvalido("cpf_trabalhador"))
+        );
+    }
 
-                    Ok(message)
+    #[test]
+    fn funcoes_ausentes_sao_reportadas_juntas() {
+        let resultado = unsafe {
+            FuncoesESocial::resolver(|nome| {
+                if nome == "eSocial_Validar" || nome == "eSocial_DownloadEventos" {
+                    None
                 } else {
-                    Err(format!(
-                        "Erro ao obter o último retorno, código: {}",
-                        resultado
-                    ))
+                    Some(nao_chamar as *const () as *mut c_void)
                 }
-            },
-            Err(error) => Err(error),
-        }
-    }
-}
-
-unsafe impl Send for ACBrLibEsocial {}
-unsafe impl Sync for ACBrLibEsocial {}
-
-impl Drop for ACBrLibEsocial {
-    fn drop(&mut self) {
-        if let Some(config_path) = &self.config_path {
-            if std::path::Path::new(config_path).exists() {
-                let _ = std::fs::remove_file(config_path);
-            }
+            })
+        };
+
+        match resultado {
+            Err(ACBrError::FuncaoNaoEncontrada(ausentes)) => assert_eq!(
+                ausentes,
+                vec![
+                    "eSocial_DownloadEventos".to_string(),
+                    "eSocial_Validar".into()
+                ]
+            ),
+            _ => panic!("esperava a lista de funções ausentes"),
         }
-
-        let _ = self.esocial_finalizar();
     }
 }
@@ -0,0 +1,289 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use crate::error::ACBrError;
+
+use super::config::ConfigESocial;
+use super::{chave_oculta, ACBrLibEsocial};
+
+/// Configuração de um `ESocialPool`.
+#[derive(Clone)]
+pub struct ConfigPool {
+    tamanho_maximo: usize,
+    modelo_config: Option<PathBuf>,
+    config: Option<ConfigESocial>,
+    chave_criptografia: String,
+    verificar_ao_obter: bool,
+}
+
+impl ConfigPool {
+    /// Pool com no máximo `tamanho_maximo` sessões (no mínimo uma).
+    pub fn new(tamanho_maximo: usize) -> Self {
+        ConfigPool {
+            tamanho_maximo: tamanho_maximo.max(1),
+            modelo_config: None,
+            config: None,
+            chave_criptografia: String::new(),
+            verificar_ao_obter: true,
+        }
+    }
+
+    /// Arquivo de configuração copiado para cada sessão criada pelo pool.
+    pub fn com_modelo_config(mut self, modelo: impl Into<PathBuf>) -> Self {
+        self.modelo_config = Some(modelo.into());
+        self
+    }
+
+    /// Configuração em memória aplicada a cada sessão criada pelo pool, depois do modelo
+    /// quando houver um. Veja `ACBrLibEsocial::com_config`.
+    pub fn com_config(mut self, config: ConfigESocial) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn com_chave_criptografia(mut self, chave: impl Into<String>) -> Self {
+        self.chave_criptografia = chave.into();
+        self
+    }
+
+    /// Liga ou desliga a verificação com `esocial_nome` das sessões reaproveitadas.
+    pub fn com_verificacao(mut self, verificar: bool) -> Self {
+        self.verificar_ao_obter = verificar;
+        self
+    }
+}
+
+impl fmt::Debug for ConfigPool {
+    /// A chave de criptografia aparece como `***`, como a senha na `ConfigESocial`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigPool")
+            .field("tamanho_maximo", &self.tamanho_maximo)
+            .field("modelo_config", &self.modelo_config)
+            .field("config", &self.config)
+            .field(
+                "chave_criptografia",
+                &chave_oculta(&self.chave_criptografia),
+            )
+            .field("verificar_ao_obter", &self.verificar_ao_obter)
+            .finish()
+    }
+}
+
+/// Contadores do pool desde a sua criação.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EstatisticasPool {
+    /// Sessões existentes, livres ou em uso.
+    pub abertas: usize,
+    pub livres: usize,
+    /// Sessões inicializadas pelo pool.
+    pub criadas: usize,
+    /// Sessões reaproveitadas em vez de criadas.
+    pub reaproveitadas: usize,
+    /// Sessões descartadas por código fatal ou por falharem na verificação.
+    pub descartadas: usize,
+}
+
+#[derive(Default)]
+struct EstadoPool {
+    livres: Vec<ACBrLibEsocial>,
+    estatisticas: EstatisticasPool,
+}
+
+struct PoolInterno {
+    config: ConfigPool,
+    estado: Mutex<EstadoPool>,
+    devolvida: Condvar,
+}
+
+impl PoolInterno {
+    fn bloquear(&self) -> MutexGuard<'_, EstadoPool> {
+        // O estado só é alterado em trechos que não entram em pânico
+        self.estado.lock().unwrap_or_else(|erro| erro.into_inner())
+    }
+
+    fn devolver(&self, sessao: ACBrLibEsocial) {
+        let mut estado = self.bloquear();
+
+        if sessao.ultima_chamada_fatal() {
+            estado.estatisticas.abertas -= 1;
+            estado.estatisticas.descartadas += 1;
+            drop(estado);
+            drop(sessao);
+        } else {
+            estado.livres.push(sessao);
+            drop(estado);
+        }
+
+        self.devolvida.notify_one();
+    }
+
+    /// Libera a vaga de uma sessão que não voltará para o pool.
+    fn descartar(&self, descartada: bool) {
+        let mut estado = self.bloquear();
+        estado.estatisticas.abertas -= 1;
+        if descartada {
+            estado.estatisticas.descartadas += 1;
+        }
+        drop(estado);
+
+        self.devolvida.notify_one();
+    }
+}
+
+/// Pool de sessões do eSocial com tamanho máximo.
+///
+/// As sessões são criadas sob demanda, a partir do mesmo modelo de configuração, até o
+/// limite configurado; depois disso `obter` aguarda uma ser devolvida. Sessões cuja última
+/// chamada retornou um código fatal não voltam para o pool, e as reaproveitadas são
+/// verificadas com `esocial_nome` antes de serem entregues.
+///
+/// A configuração alterada por quem usa a sessão continua valendo para o próximo uso.
+#[derive(Clone)]
+pub struct ESocialPool {
+    interno: Arc<PoolInterno>,
+}
+
+impl ESocialPool {
+    pub fn new(config: ConfigPool) -> Self {
+        ESocialPool {
+            interno: Arc::new(PoolInterno {
+                config,
+                estado: Mutex::new(EstadoPool::default()),
+                devolvida: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Retira uma sessão do pool, criando uma nova se houver vaga ou aguardando a devolução
+    /// de outra. A sessão volta para o pool quando o `SessaoPool` é liberado.
+    pub fn obter(&self) -> Result<SessaoPool, ACBrError> {
+        let interno = &self.interno;
+
+        loop {
+            let mut estado = interno.bloquear();
+
+            while estado.livres.is_empty()
+                && estado.estatisticas.abertas >= interno.config.tamanho_maximo
+            {
+                estado = interno
+                    .devolvida
+                    .wait(estado)
+                    .unwrap_or_else(|erro| erro.into_inner());
+            }
+
+            if let Some(sessao) = estado.livres.pop() {
+                drop(estado);
+
+                // Uma sessão que não responde é descartada e a busca recomeça
+                if interno.config.verificar_ao_obter && sessao.esocial_nome().is_err() {
+                    drop(sessao);
+                    interno.descartar(true);
+                    continue;
+                }
+
+                interno.bloquear().estatisticas.reaproveitadas += 1;
+                return Ok(self.emprestar(sessao));
+            }
+
+            // Reserva a vaga e cria a sessão fora do lock
+            estado.estatisticas.abertas += 1;
+            drop(estado);
+
+            return match self.criar() {
+                Ok(sessao) => {
+                    interno.bloquear().estatisticas.criadas += 1;
+                    Ok(self.emprestar(sessao))
+                }
+                Err(erro) => {
+                    interno.descartar(false);
+                    Err(erro)
+                }
+            };
+        }
+    }
+
+    /// Executa `operacao` com uma sessão do pool, devolvendo-a ao final.
+    pub fn executar<R, F>(&self, operacao: F) -> Result<R, ACBrError>
+    where
+        F: FnOnce(&mut ACBrLibEsocial) -> Result<R, ACBrError>,
+    {
+        let mut sessao = self.obter()?;
+        operacao(&mut sessao)
+    }
+
+    pub fn estatisticas(&self) -> EstatisticasPool {
+        let estado = self.interno.bloquear();
+
+        EstatisticasPool {
+            livres: estado.livres.len(),
+            ..estado.estatisticas
+        }
+    }
+
+    fn criar(&self) -> Result<ACBrLibEsocial, ACBrError> {
+        let config = &self.interno.config;
+
+        let sessao = match &config.modelo_config {
+            Some(modelo) => ACBrLibEsocial::com_modelo_config(modelo, &config.chave_criptografia),
+            None => ACBrLibEsocial::com_config_temporaria(&config.chave_criptografia),
+        }?;
+
+        if let Some(config) = &config.config {
+            sessao.aplicar_config(config)?;
+        }
+
+        Ok(sessao)
+    }
+
+    fn emprestar(&self, sessao: ACBrLibEsocial) -> SessaoPool {
+        SessaoPool {
+            sessao: Some(sessao),
+            pool: Arc::clone(&self.interno),
+        }
+    }
+}
+
+/// Sessão emprestada pelo `ESocialPool`, devolvida ao ser liberada.
+pub struct SessaoPool {
+    sessao: Option<ACBrLibEsocial>,
+    pool: Arc<PoolInterno>,
+}
+
+impl SessaoPool {
+    /// Tira a sessão do pool definitivamente, liberando a vaga para outra.
+    pub fn desvincular(mut self) -> ACBrLibEsocial {
+        self.pool.descartar(false);
+        self.sessao.take().expect("sessão já devolvida")
+    }
+
+    /// Descarta a sessão em vez de devolvê-la, por exemplo depois de um erro que deixou
+    /// a instância em estado desconhecido.
+    pub fn descartar(mut self) {
+        self.sessao = None;
+        self.pool.descartar(true);
+    }
+}
+
+impl Deref for SessaoPool {
+    type Target = ACBrLibEsocial;
+
+    fn deref(&self) -> &ACBrLibEsocial {
+        self.sessao.as_ref().expect("sessão já devolvida")
+    }
+}
+
+impl DerefMut for SessaoPool {
+    fn deref_mut(&mut self) -> &mut ACBrLibEsocial {
+        self.sessao.as_mut().expect("sessão já devolvida")
+    }
+}
+
+impl Drop for SessaoPool {
+    fn drop(&mut self) {
+        if let Some(sessao) = self.sessao.take() {
+            self.pool.devolver(sessao);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Protocolo entre a `SessaoIsolada` e o processo worker.
+//!
+//! Cada mensagem é uma linha de texto com campos separados por tabulação; barras
+//! invertidas, tabulações e quebras de linha dentro dos campos são escapadas. A primeira
+//! requisição cria a sessão (`inicializar`), as seguintes têm o nome do método seguido dos
+//! argumentos, e `finalizar` encerra o worker. Toda requisição recebe uma resposta `ok`
+//! com o texto devolvido pelo método ou o erro correspondente.
+
+use crate::error::ACBrError;
+
+pub(crate) const INICIALIZAR: &str = "inicializar";
+pub(crate) const FINALIZAR: &str = "finalizar";
+
+/// Como a sessão do worker obtém o seu arquivo de configuração.
+pub(crate) const CONFIG_ARQUIVO: &str = "arquivo";
+pub(crate) const CONFIG_MODELO: &str = "modelo";
+pub(crate) const CONFIG_TEMPORARIA: &str = "temporaria";
+
+pub(crate) fn codificar<S: AsRef<str>>(campos: &[S]) -> String {
+    let mut linha = String::new();
+
+    for (i, campo) in campos.iter().enumerate() {
+        if i > 0 {
+            linha.push('\t');
+        }
+
+        for c in campo.as_ref().chars() {
+            match c {
+                '\\' => linha.push_str("\\\\"),
+                '\t' => linha.push_str("\\t"),
+                '\n' => linha.push_str("\\n"),
+                '\r' => linha.push_str("\\r"),
+                c => linha.push(c),
+            }
+        }
+    }
+
+    linha.push('\n');
+    linha
+}
+
+pub(crate) fn decodificar(linha: &str) -> Vec<String> {
+    let mut campos = Vec::new();
+    let mut campo = String::new();
+    let mut caracteres = linha.trim_end_matches(['\r', '\n']).chars();
+
+    while let Some(c) = caracteres.next() {
+        match c {
+            '\t' => campos.push(std::mem::take(&mut campo)),
+            '\\' => match caracteres.next() {
+                Some('t') => campo.push('\t'),
+                Some('n') => campo.push('\n'),
+                Some('r') => campo.push('\r'),
+                Some(c) => campo.push(c),
+                None => campo.push('\\'),
+            },
+            c => campo.push(c),
+        }
+    }
+
+    campos.push(campo);
+    campos
+}
+
+/// Campos da resposta a uma requisição.
+pub(crate) fn codificar_resultado(resultado: Result<String, ACBrError>) -> Vec<String> {
+    let erro = match resultado {
+        Ok(resposta) => return vec!["ok".into(), resposta],
+        Err(erro) => erro,
+    };
+
+    match erro {
+        ACBrError::CarregarBiblioteca(mensagem) => vec!["carregar".into(), mensagem],
+        ACBrError::FuncaoNaoEncontrada(funcoes) => {
+            let mut campos = vec!["funcoes".to_string()];
+            campos.extend(funcoes);
+            campos
+        }
+        ACBrError::PonteiroNulo => vec!["ponteiro".into()],
+        erro => match erro.codigo() {
+            Some(codigo) => vec![
+                "erro".into(),
+                codigo.to_string(),
+                erro.mensagem().unwrap_or_default().to_string(),
+            ],
+            None => vec!["falha".into(), erro.to_string()],
+        },
+    }
+}
+
+/// Converte os campos da resposta de volta para o resultado do método.
+pub(crate) fn decodificar_resultado(campos: Vec<String>) -> Result<String, ACBrError> {
+    let mut campos = campos.into_iter();
+    let tipo = campos.next().unwrap_or_default();
+    let mut proximo = || campos.next().unwrap_or_default();
+
+    match tipo.as_str() {
+        "ok" => Ok(proximo()),
+        "erro" => {
+            let codigo = proximo();
+            let codigo = codigo.parse().map_err(|_| resposta_invalida(&tipo))?;
+            Err(ACBrError::from_codigo(codigo, proximo()))
+        }
+        "carregar" => Err(ACBrError::CarregarBiblioteca(proximo())),
+        "funcoes" => Err(ACBrError::FuncaoNaoEncontrada(campos.collect())),
+        "ponteiro" => Err(ACBrError::PonteiroNulo),
+        "falha" => Err(ACBrError::Worker(proximo())),
+        _ => Err(resposta_invalida(&tipo)),
+    }
+}
+
+/// Erro para uma mensagem que não segue o protocolo.
+pub(crate) fn resposta_invalida(tipo: &str) -> ACBrError {
+    ACBrError::Worker(format!("mensagem inválida: {:?}", tipo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn campos_com_separadores_sobrevivem_a_codificacao() {
+        let campos = [
+            "consultar",
+            "[Consulta]\r\nCodigo=201",
+            "a\tb",
+            "c:\\temp\\",
+            "",
+        ];
+        let linha = codificar(&campos);
+
+        assert_eq!(linha.matches('\n').count(), 1);
+        assert_eq!(decodificar(&linha), campos);
+    }
+
+    #[test]
+    fn erros_da_biblioteca_mantem_codigo_e_mensagem() {
+        let erros = [
+            ACBrError::Execucao("Nenhum evento carregado".into()),
+            ACBrError::Desconhecido {
+                codigo: -99,
+                mensagem: "erro\nem duas linhas".into(),
+            },
+            ACBrError::FuncaoNaoEncontrada(vec!["eSocial_Nome".into(), "eSocial_Versao".into()]),
+            ACBrError::PonteiroNulo,
+        ];
+
+        for erro in erros {
+            let linha = codificar(&codificar_resultado(Err(erro.clone())));
+            assert_eq!(decodificar_resultado(decodificar(&linha)), Err(erro));
+        }
+
+        let linha = codificar(&codificar_resultado(Ok(String::new())));
+        assert_eq!(
+            decodificar_resultado(decodificar(&linha)),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn erros_do_wrapper_viram_falha_do_worker() {
+        let campos = codificar_resultado(Err(ACBrError::SessaoEncerrada));
+
+        assert_eq!(
+            decodificar_resultado(campos),
+            Err(ACBrError::Worker("A sessão foi encerrada".into()))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::str::FromStr;
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use super::{ConsultaResposta, EnvioResposta, Ocorrencia, RespostaESocial, RetornoEvento};
+
+/// Sessão de uma resposta no formato INI.
+struct Secao<'a> {
+    ini: &'a Ini,
+    nome: &'a str,
+}
+
+impl<'a> Secao<'a> {
+    fn obrigatoria(ini: &'a Ini, nome: &'a str) -> Result<Self, ACBrError> {
+        match ini.chaves(nome) {
+            Some(_) => Ok(Secao { ini, nome }),
+            None => Err(ACBrError::RespostaInvalida(format!(
+                "sessão [{}] não encontrada",
+                nome
+            ))),
+        }
+    }
+
+    /// Valor da chave; vazio é tratado como ausente.
+    fn texto(&self, chave: &str) -> Option<String> {
+        self.ini
+            .valor(self.nome, chave)
+            .filter(|valor| !valor.is_empty())
+            .map(str::to_string)
+    }
+
+    fn numero<T: FromStr>(&self, chave: &str) -> Result<Option<T>, ACBrError> {
+        match self.texto(chave) {
+            Some(valor) => valor.parse().map(Some).map_err(|_| {
+                ACBrError::RespostaInvalida(format!(
+                    "[{}] {}={} não é um número",
+                    self.nome, chave, valor
+                ))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn codigo(&self, chave: &str) -> Result<i32, ACBrError> {
+        self.numero(chave)?.ok_or_else(|| {
+            ACBrError::RespostaInvalida(format!("[{}] sem a chave {}", self.nome, chave))
+        })
+    }
+
+    /// Ocorrências das sessões `<prefixo>NNN`.
+    fn ocorrencias(&self, prefixo: &str) -> Result<Vec<Ocorrencia>, ACBrError> {
+        secoes_numeradas(self.ini, prefixo)
+            .iter()
+            .map(|secao| {
+                Ok(Ocorrencia {
+                    codigo: secao.codigo("Codigo")?,
+                    descricao: secao.texto("Descricao").unwrap_or_default(),
+                    tipo: secao.numero("Tipo")?,
+                    localizacao: secao.texto("Localizacao"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Sessões cujo nome é o prefixo seguido só de dígitos, como `Evento001`.
+fn secoes_numeradas<'a>(ini: &'a Ini, prefixo: &str) -> Vec<Secao<'a>> {
+    ini.secoes()
+        .filter(|nome| {
+            nome.len() > prefixo.len()
+                && nome.is_char_boundary(prefixo.len())
+                && nome[..prefixo.len()].eq_ignore_ascii_case(prefixo)
+                && nome[prefixo.len()..].bytes().all(|b| b.is_ascii_digit())
+        })
+        .map(|nome| Secao { ini, nome })
+        .collect()
+}
+
+impl EnvioResposta {
+    pub(super) fn de_secoes(ini: &Ini) -> Result<Self, ACBrError> {
+        let envio = Secao::obrigatoria(ini, "Envio")?;
+
+        Ok(EnvioResposta {
+            cod_resposta: envio.codigo("Codigo")?,
+            desc_resposta: envio.texto("Mensagem").unwrap_or_default(),
+            tp_insc_empreg: envio.numero("TpInscEmpreg")?,
+            nr_insc_empreg: envio.texto("NrInscEmpreg"),
+            tp_insc_transm: envio.numero("TpInscTransm")?,
+            nr_insc_transm: envio.texto("NrInscTransm"),
+            grupo: envio.numero("Grupo")?,
+            qtde_eventos: envio.numero("QtdeEventos")?,
+            dh_recepcao: envio.texto("DhRecepcao"),
+            versao_aplic: envio.texto("VersaoAplic"),
+            protocolo: envio.texto("Protocolo"),
+            ocorrencias: envio.ocorrencias("Ocorrencia")?,
+        })
+    }
+}
+
+impl ConsultaResposta {
+    pub(super) fn de_secoes(ini: &Ini) -> Result<Self, ACBrError> {
+        let consulta = Secao::obrigatoria(ini, "Consulta")?;
+
+        let eventos = secoes_numeradas(ini, "Evento")
+            .iter()
+            .map(|evento| {
+                Ok(RetornoEvento {
+                    id: evento.texto("Id").unwrap_or_default(),
+                    cod_resposta: evento.codigo("Codigo")?,
+                    desc_resposta: evento.texto("Descricao").unwrap_or_default(),
+                    nr_recibo: evento.texto("NrRecibo"),
+                    dh_processamento: evento.texto("DhProcessamento"),
+                    ocorrencias: evento.ocorrencias(&format!("{}Ocorrencia", evento.nome))?,
+                })
+            })
+            .collect::<Result<_, ACBrError>>()?;
+
+        Ok(ConsultaResposta {
+            cod_resposta: consulta.codigo("Codigo")?,
+            desc_resposta: consulta.texto("Mensagem").unwrap_or_default(),
+            protocolo: consulta.texto("Protocolo"),
+            dh_recepcao: consulta.texto("DhRecepcao"),
+            versao_aplic: consulta.texto("VersaoAplic"),
+            ocorrencias: consulta.ocorrencias("Ocorrencia")?,
+            eventos,
+        })
+    }
+}
+
+impl RespostaESocial {
+    pub(super) fn de_secoes(ini: &Ini) -> Result<Self, ACBrError> {
+        if ini.chaves("Envio").is_some() {
+            EnvioResposta::de_secoes(ini).map(RespostaESocial::Envio)
+        } else if ini.chaves("Consulta").is_some() {
+            ConsultaResposta::de_secoes(ini).map(RespostaESocial::Consulta)
+        } else {
+            Err(ACBrError::RespostaInvalida(
+                "sessão [Envio] ou [Consulta] não encontrada".into(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envio_recebido() {
+        let envio = EnvioResposta::de_ini(include_str!("amostras/envio.ini")).unwrap();
+
+        assert_eq!(
+            envio,
+            EnvioResposta {
+                cod_resposta: 201,
+                desc_resposta: "Lote Recebido com Sucesso.".into(),
+                tp_insc_empreg: Some(1),
+                nr_insc_empreg: Some("12345678".into()),
+                tp_insc_transm: Some(1),
+                nr_insc_transm: Some("12345678000190".into()),
+                grupo: Some(1),
+                qtde_eventos: Some(2),
+                dh_recepcao: Some("2025-01-15T10:32:07.143".into()),
+                versao_aplic: Some("1.5.0".into()),
+                protocolo: Some("1.1.202501.0000000000012345678".into()),
+                ocorrencias: Vec::new(),
+            }
+        );
+        assert!(envio.recebido());
+    }
+
+    #[test]
+    fn envio_rejeitado_traz_as_ocorrencias_do_lote() {
+        let envio = EnvioResposta::de_ini(include_str!("amostras/envio_rejeitado.ini")).unwrap();
+
+        assert!(!envio.recebido());
+        assert_eq!(envio.cod_resposta, 402);
+        assert_eq!(envio.protocolo, None);
+        assert_eq!(
+            envio.ocorrencias,
+            [Ocorrencia {
+                codigo: 142,
+                descricao: "O certificado digital do transmissor não está autorizado.".into(),
+                tipo: Some(1),
+                localizacao: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn consulta_processada_com_eventos_e_ocorrencias() {
+        let consulta = ConsultaResposta::de_ini(include_str!("amostras/consulta.ini")).unwrap();
+
+        assert!(consulta.processado());
+        assert_eq!(
+            consulta.protocolo.as_deref(),
+            Some("1.1.202501.0000000000012345678")
+        );
+        assert_eq!(consulta.eventos.len(), 2);
+
+        let aceito = &consulta.eventos[0];
+        assert!(aceito.aceito());
+        assert_eq!(aceito.id, "ID1123456780000002025011510320700001");
+        assert_eq!(aceito.nr_recibo.as_deref(), Some("1.1.0000000000123456789"));
+        assert!(aceito.ocorrencias.is_empty());
+
+        let rejeitado = &consulta.eventos[1];
+        assert!(!rejeitado.aceito());
+        assert_eq!(rejeitado.cod_resposta, 401);
+        assert_eq!(rejeitado.nr_recibo, None);
+        assert_eq!(rejeitado.ocorrencias.len(), 2);
+        assert_eq!(rejeitado.ocorrencias[1].codigo, 1011);
+        assert_eq!(rejeitado.ocorrencias[1].tipo, Some(2));
+        assert_eq!(
+            rejeitado.ocorrencias[1].localizacao.as_deref(),
+            Some("/eSocial/evtAdmissao/trabalhador/nmTrab")
+        );
+    }
+
+    #[test]
+    fn consulta_em_processamento_sem_eventos() {
+        let consulta = ConsultaResposta::de_ini(
+            "[Consulta]\r\nCodigo=101\r\nMensagem=Lote aguardando processamento.\r\n",
+        )
+        .unwrap();
+
+        assert!(consulta.em_processamento());
+        assert!(consulta.eventos.is_empty());
+    }
+
+    #[test]
+    fn resposta_fora_do_formato_retorna_erro() {
+        assert_eq!(
+            EnvioResposta::de_ini("[Consulta]\nCodigo=201\n"),
+            Err(ACBrError::RespostaInvalida(
+                "sessão [Envio] não encontrada".into()
+            ))
+        );
+        assert_eq!(
+            ConsultaResposta::de_ini("[Consulta]\nCodigo=abc\n"),
+            Err(ACBrError::RespostaInvalida(
+                "[Consulta] Codigo=abc não é um número".into()
+            ))
+        );
+        assert!(ConsultaResposta::de_ini("[Consulta]\n\n[Evento001]\nId=ID1\n").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use serde_json::Value;
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use super::nome_aninhado;
+
+/// Sessões de uma resposta no formato JSON (`TipoResposta=2`).
+///
+/// O objeto principal tem uma propriedade por sessão. Objetos são sessões, listas de
+/// objetos são sessões numeradas a partir de `001` e os demais valores são chaves.
+pub(super) fn secoes(texto: &str) -> Result<Ini, ACBrError> {
+    let valor: Value = serde_json::from_str(texto.trim_start_matches('\u{feff}'))
+        .map_err(|erro| ACBrError::RespostaInvalida(format!("JSON inválido: {}", erro)))?;
+
+    let raiz = valor
+        .as_object()
+        .ok_or_else(|| ACBrError::RespostaInvalida("o JSON não é um objeto".into()))?;
+
+    let mut ini = Ini::default();
+    for (nome, valor) in raiz {
+        ler_secao(nome, valor, None, &mut ini);
+    }
+
+    Ok(ini)
+}
+
+fn ler_secao(nome: &str, valor: &Value, pai: Option<&str>, ini: &mut Ini) {
+    match valor {
+        Value::Object(campos) => {
+            let nome = nome_aninhado(pai, nome);
+
+            for (chave, valor) in campos {
+                match valor {
+                    Value::Object(_) | Value::Array(_) => ler_secao(chave, valor, Some(&nome), ini),
+                    valor => ini.gravar(&nome, chave, texto(valor)),
+                }
+            }
+        }
+        Value::Array(itens) => {
+            for (i, item) in itens.iter().enumerate() {
+                ler_secao(&format!("{}{:03}", nome, i + 1), item, pai, ini);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn texto(valor: &Value) -> String {
+    match valor {
+        Value::String(texto) => texto.clone(),
+        Value::Bool(valor) => if *valor { "1" } else { "0" }.to_string(),
+        Value::Null => String::new(),
+        valor => valor.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ConsultaResposta, EnvioResposta};
+    use super::*;
+
+    #[test]
+    fn respostas_em_json_sao_iguais_as_em_ini() {
+        assert_eq!(
+            EnvioResposta::de_json(include_str!("amostras/envio.json")).unwrap(),
+            EnvioResposta::de_ini(include_str!("amostras/envio.ini")).unwrap()
+        );
+        assert_eq!(
+            ConsultaResposta::de_json(include_str!("amostras/consulta.json")).unwrap(),
+            ConsultaResposta::de_ini(include_str!("amostras/consulta.ini")).unwrap()
+        );
+    }
+
+    #[test]
+    fn sessoes_no_nivel_principal() {
+        let consulta = ConsultaResposta::de_json(
+            r#"{
+                "Consulta": { "Codigo": 201, "Mensagem": "Lote processado com sucesso." },
+                "Evento001": { "Id": "ID1", "Codigo": 201, "NrRecibo": "1.1.1" },
+                "Evento002": {
+                    "Id": "ID2",
+                    "Codigo": 401,
+                    "NrRecibo": null,
+                    "Ocorrencia001": { "Codigo": 539, "Descricao": "Erro", "Tipo": 1 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(consulta.eventos.len(), 2);
+        assert_eq!(consulta.eventos[1].nr_recibo, None);
+        assert_eq!(consulta.eventos[1].ocorrencias[0].codigo, 539);
+        assert!(consulta.ocorrencias.is_empty());
+    }
+
+    #[test]
+    fn json_que_nao_e_objeto_retorna_erro() {
+        assert_eq!(
+            EnvioResposta::de_json("[1, 2]"),
+            Err(ACBrError::RespostaInvalida("o JSON não é um objeto".into()))
+        );
+        assert!(EnvioResposta::de_json("{\"Envio\": ").is_err());
+    }
+}
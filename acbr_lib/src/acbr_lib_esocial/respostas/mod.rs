@@ -0,0 +1,329 @@
+//! Respostas tipadas das operações de envio e consulta do eSocial.
+//!
+//! A biblioteca devolve as respostas no formato de `[Principal] TipoResposta`. No formato
+//! INI, a sessão `[Envio]` ou `[Consulta]` traz o status do lote, uma sessão `[EventoNNN]`
+//! traz cada evento processado e as ocorrências ficam em `[OcorrenciaNNN]` (do lote) ou
+//! `[EventoNNNOcorrenciaNNN]` (do evento). Nos formatos XML e JSON as sessões são elementos
+//! ou objetos, que podem estar aninhados na sessão principal; listas JSON viram sessões
+//! numeradas. Os três formatos são convertidos para as mesmas sessões antes da leitura.
+
+mod ini;
+mod json;
+mod xml;
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use super::config::TipoResposta;
+#[cfg(feature = "async")]
+use super::SessaoAssincrona;
+use super::{ACBrLibEsocial, SessaoComPrazo, SessaoIsolada};
+
+/// Código de `cod_resposta` para lote recebido ou processado com sucesso.
+pub const COD_SUCESSO: i32 = 201;
+/// Código de `cod_resposta` para lote que ainda aguarda processamento.
+pub const COD_EM_PROCESSAMENTO: i32 = 101;
+/// Código de `cod_resposta` para evento aceito com advertências.
+pub const COD_SUCESSO_COM_ADVERTENCIA: i32 = 202;
+
+/// Ocorrência (erro ou advertência) retornada para o lote ou para um evento.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ocorrencia {
+    pub codigo: i32,
+    pub descricao: String,
+    /// 1 para erro e 2 para advertência.
+    pub tipo: Option<i32>,
+    /// Caminho do elemento do XML ao qual a ocorrência se refere.
+    pub localizacao: Option<String>,
+}
+
+/// Resposta de `enviar_esocial`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvioResposta {
+    pub cod_resposta: i32,
+    pub desc_resposta: String,
+    pub tp_insc_empreg: Option<i32>,
+    pub nr_insc_empreg: Option<String>,
+    pub tp_insc_transm: Option<i32>,
+    pub nr_insc_transm: Option<String>,
+    pub grupo: Option<i32>,
+    pub qtde_eventos: Option<u32>,
+    pub dh_recepcao: Option<String>,
+    pub versao_aplic: Option<String>,
+    /// Protocolo para a consulta do lote, presente quando ele foi recebido.
+    pub protocolo: Option<String>,
+    pub ocorrencias: Vec<Ocorrencia>,
+}
+
+impl EnvioResposta {
+    /// Indica se o lote foi recebido e pode ser consultado pelo `protocolo`.
+    pub fn recebido(&self) -> bool {
+        self.cod_resposta == COD_SUCESSO
+    }
+}
+
+/// Resposta de `consultar_protocolo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsultaResposta {
+    pub cod_resposta: i32,
+    pub desc_resposta: String,
+    pub protocolo: Option<String>,
+    pub dh_recepcao: Option<String>,
+    pub versao_aplic: Option<String>,
+    pub ocorrencias: Vec<Ocorrencia>,
+    /// Retorno de cada evento do lote, na ordem da resposta.
+    pub eventos: Vec<RetornoEvento>,
+}
+
+impl ConsultaResposta {
+    /// Indica se o lote já foi processado; o resultado de cada evento fica em `eventos`.
+    pub fn processado(&self) -> bool {
+        self.cod_resposta == COD_SUCESSO
+    }
+
+    /// Indica se o lote ainda aguarda processamento e deve ser consultado de novo.
+    pub fn em_processamento(&self) -> bool {
+        self.cod_resposta == COD_EM_PROCESSAMENTO
+    }
+}
+
+/// Resultado do processamento de um evento do lote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetornoEvento {
+    /// `Id` do evento enviado.
+    pub id: String,
+    pub cod_resposta: i32,
+    pub desc_resposta: String,
+    /// Recibo do evento, presente quando ele foi aceito.
+    pub nr_recibo: Option<String>,
+    pub dh_processamento: Option<String>,
+    pub ocorrencias: Vec<Ocorrencia>,
+}
+
+impl RetornoEvento {
+    /// Indica se o evento foi aceito, com ou sem advertências em `ocorrencias`.
+    pub fn aceito(&self) -> bool {
+        matches!(self.cod_resposta, COD_SUCESSO | COD_SUCESSO_COM_ADVERTENCIA)
+    }
+}
+
+/// Resposta de `obter_ultimo_retorno`, que pode ser de um envio ou de uma consulta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RespostaESocial {
+    Envio(EnvioResposta),
+    Consulta(ConsultaResposta),
+}
+
+/// Sessões principais das respostas. As sessões aninhadas nelas não recebem o nome delas
+/// como prefixo; as aninhadas em outras sessões, sim (`Evento001` + `Ocorrencia001`).
+const SECOES_PRINCIPAIS: [&str; 2] = ["Envio", "Consulta"];
+
+fn nome_aninhado(pai: Option<&str>, nome: &str) -> String {
+    match pai {
+        Some(pai)
+            if !SECOES_PRINCIPAIS
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(pai)) =>
+        {
+            format!("{}{}", pai, nome)
+        }
+        _ => nome.to_string(),
+    }
+}
+
+/// Formato da resposta pelo primeiro caractere do texto.
+pub fn detectar_tipo(texto: &str) -> TipoResposta {
+    match texto
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .chars()
+        .next()
+    {
+        Some('<') => TipoResposta::Xml,
+        Some('{') | Some('[') => TipoResposta::Json,
+        _ => TipoResposta::Ini,
+    }
+}
+
+fn secoes(texto: &str, tipo: TipoResposta) -> Result<Ini, ACBrError> {
+    match tipo {
+        TipoResposta::Ini => Ok(Ini::ler(texto)),
+        TipoResposta::Xml => xml::secoes(texto),
+        TipoResposta::Json => json::secoes(texto),
+    }
+}
+
+macro_rules! leitura_resposta {
+    ($($tipo:ident),*) => {
+        $(
+            impl $tipo {
+                /// Lê a resposta no formato INI (`TipoResposta=0`).
+                pub fn de_ini(texto: &str) -> Result<Self, ACBrError> {
+                    Self::ler(texto, TipoResposta::Ini)
+                }
+
+                /// Lê a resposta no formato XML (`TipoResposta=1`).
+                pub fn de_xml(texto: &str) -> Result<Self, ACBrError> {
+                    Self::ler(texto, TipoResposta::Xml)
+                }
+
+                /// Lê a resposta no formato JSON (`TipoResposta=2`).
+                pub fn de_json(texto: &str) -> Result<Self, ACBrError> {
+                    Self::ler(texto, TipoResposta::Json)
+                }
+
+                /// Lê a resposta no formato informado.
+                pub fn ler(texto: &str, tipo: TipoResposta) -> Result<Self, ACBrError> {
+                    Self::de_secoes(&secoes(texto, tipo)?)
+                }
+
+                /// Lê a resposta no formato identificado por `detectar_tipo`.
+                pub fn de_texto(texto: &str) -> Result<Self, ACBrError> {
+                    Self::ler(texto, detectar_tipo(texto))
+                }
+            }
+        )*
+    };
+}
+
+leitura_resposta!(EnvioResposta, ConsultaResposta, RespostaESocial);
+
+/// Formato a partir do valor lido de `[Principal] TipoResposta`.
+fn tipo_do_valor(valor: String) -> Result<TipoResposta, ACBrError> {
+    valor
+        .parse()
+        .ok()
+        .and_then(TipoResposta::from_codigo)
+        .ok_or(ACBrError::ConfigInvalida {
+            secao: "Principal".into(),
+            chave: "TipoResposta".into(),
+            valor,
+        })
+}
+
+impl ACBrLibEsocial {
+    /// Formato das respostas da sessão (`[Principal] TipoResposta`).
+    pub fn tipo_resposta(&self) -> Result<TipoResposta, ACBrError> {
+        tipo_do_valor(self.esocial_config_ler_valor("Principal", "TipoResposta")?)
+    }
+
+    /// `enviar_esocial` com a resposta lida no formato da sessão.
+    pub fn enviar_lote(&self, grupo: i32) -> Result<EnvioResposta, ACBrError> {
+        let tipo = self.tipo_resposta()?;
+        EnvioResposta::ler(&self.enviar_esocial(grupo)?, tipo)
+    }
+
+    /// `consultar_protocolo` com a resposta lida no formato da sessão.
+    pub fn consultar_lote(
+        &self,
+        protocolo: impl AsRef<str>,
+    ) -> Result<ConsultaResposta, ACBrError> {
+        let tipo = self.tipo_resposta()?;
+        ConsultaResposta::ler(&self.consultar_protocolo(protocolo)?, tipo)
+    }
+
+    /// `obter_ultimo_retorno` lido como resposta de envio ou de consulta.
+    pub fn ultima_resposta(&self) -> Result<RespostaESocial, ACBrError> {
+        // O texto é lido antes porque consultar o formato muda o último retorno
+        let resposta = self.obter_ultimo_retorno()?;
+        RespostaESocial::ler(&resposta, self.tipo_resposta()?)
+    }
+
+    /// Grava `TipoResposta=0` quando a configuração da sessão não define o formato, para que
+    /// ele seja sempre conhecido pelo wrapper.
+    pub(super) fn definir_tipo_resposta(&self) -> Result<(), ACBrError> {
+        match self.esocial_config_ler_valor("Principal", "TipoResposta") {
+            Ok(valor) if !valor.is_empty() => Ok(()),
+            Ok(_) | Err(ACBrError::SessaoNaoEncontrada(_) | ACBrError::ChaveNaoEncontrada(_)) => {
+                self.esocial_config_gravar_valor(
+                    "Principal",
+                    "TipoResposta",
+                    TipoResposta::Ini.codigo().to_string(),
+                )
+            }
+            Err(erro) => Err(erro),
+        }
+    }
+}
+
+impl SessaoIsolada {
+    /// Equivalente de `ACBrLibEsocial::tipo_resposta`.
+    pub fn tipo_resposta(&self) -> Result<TipoResposta, ACBrError> {
+        tipo_do_valor(self.esocial_config_ler_valor("Principal", "TipoResposta")?)
+    }
+
+    /// Equivalente de `ACBrLibEsocial::enviar_lote`, com a resposta lida neste processo.
+    pub fn enviar_lote(&self, grupo: i32) -> Result<EnvioResposta, ACBrError> {
+        let tipo = self.tipo_resposta()?;
+        EnvioResposta::ler(&self.enviar_esocial(grupo)?, tipo)
+    }
+
+    /// Equivalente de `ACBrLibEsocial::consultar_lote`, com a resposta lida neste processo.
+    pub fn consultar_lote(
+        &self,
+        protocolo: impl AsRef<str>,
+    ) -> Result<ConsultaResposta, ACBrError> {
+        let tipo = self.tipo_resposta()?;
+        ConsultaResposta::ler(&self.consultar_protocolo(protocolo)?, tipo)
+    }
+
+    /// Equivalente de `ACBrLibEsocial::ultima_resposta`.
+    pub fn ultima_resposta(&self) -> Result<RespostaESocial, ACBrError> {
+        let resposta = self.obter_ultimo_retorno()?;
+        RespostaESocial::ler(&resposta, self.tipo_resposta()?)
+    }
+}
+
+impl SessaoComPrazo {
+    /// Equivalente de `ACBrLibEsocial::tipo_resposta`.
+    pub fn tipo_resposta(&self) -> Result<TipoResposta, ACBrError> {
+        self.executar(|sessao| sessao.tipo_resposta())
+    }
+
+    /// Equivalente de `ACBrLibEsocial::enviar_lote`, no prazo da sessão.
+    pub fn enviar_lote(&self, grupo: i32) -> Result<EnvioResposta, ACBrError> {
+        self.executar(move |sessao| sessao.enviar_lote(grupo))
+    }
+
+    /// Equivalente de `ACBrLibEsocial::consultar_lote`, no prazo da sessão.
+    pub fn consultar_lote(
+        &self,
+        protocolo: impl Into<String>,
+    ) -> Result<ConsultaResposta, ACBrError> {
+        let protocolo = protocolo.into();
+        self.executar(move |sessao| sessao.consultar_lote(protocolo))
+    }
+
+    /// Equivalente de `ACBrLibEsocial::ultima_resposta`.
+    pub fn ultima_resposta(&self) -> Result<RespostaESocial, ACBrError> {
+        self.executar(|sessao| sessao.ultima_resposta())
+    }
+}
+
+#[cfg(feature = "async")]
+impl SessaoAssincrona {
+    /// Equivalente assíncrono de `ACBrLibEsocial::tipo_resposta`.
+    pub async fn tipo_resposta(&self) -> Result<TipoResposta, ACBrError> {
+        self.executar(|sessao| sessao.tipo_resposta()).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::enviar_lote`.
+    pub async fn enviar_lote(&self, grupo: i32) -> Result<EnvioResposta, ACBrError> {
+        self.executar(move |sessao| sessao.enviar_lote(grupo)).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::consultar_lote`.
+    pub async fn consultar_lote(
+        &self,
+        protocolo: impl Into<String>,
+    ) -> Result<ConsultaResposta, ACBrError> {
+        let protocolo = protocolo.into();
+        self.executar(move |sessao| sessao.consultar_lote(protocolo))
+            .await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::ultima_resposta`.
+    pub async fn ultima_resposta(&self) -> Result<RespostaESocial, ACBrError> {
+        self.executar(|sessao| sessao.ultima_resposta()).await
+    }
+}
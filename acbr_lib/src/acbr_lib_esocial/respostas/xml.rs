@@ -0,0 +1,94 @@
+use roxmltree::{Document, Node};
+
+use crate::error::ACBrError;
+use crate::utils::ini::Ini;
+
+use super::{nome_aninhado, SECOES_PRINCIPAIS};
+
+/// Sessões de uma resposta no formato XML (`TipoResposta=1`).
+///
+/// Elementos com elementos filhos são sessões e os demais são chaves. O elemento raiz é
+/// a sessão principal ou, se não tiver chaves, só agrupa as sessões da resposta.
+pub(super) fn secoes(texto: &str) -> Result<Ini, ACBrError> {
+    let documento = Document::parse(texto.trim_start_matches('\u{feff}'))
+        .map_err(|erro| ACBrError::RespostaInvalida(format!("XML inválido: {}", erro)))?;
+
+    let raiz = documento.root_element();
+    let principal = SECOES_PRINCIPAIS
+        .iter()
+        .any(|nome| nome.eq_ignore_ascii_case(raiz.tag_name().name()));
+
+    let mut ini = Ini::default();
+
+    if principal || elementos(raiz).any(|filho| !e_secao(filho)) {
+        ler_secao(raiz, None, &mut ini);
+    } else {
+        for secao in elementos(raiz) {
+            ler_secao(secao, None, &mut ini);
+        }
+    }
+
+    Ok(ini)
+}
+
+fn ler_secao(elemento: Node, pai: Option<&str>, ini: &mut Ini) {
+    let nome = nome_aninhado(pai, elemento.tag_name().name());
+
+    for filho in elementos(elemento) {
+        if e_secao(filho) {
+            ler_secao(filho, Some(&nome), ini);
+        } else {
+            ini.gravar(
+                &nome,
+                filho.tag_name().name(),
+                filho.text().unwrap_or_default().trim(),
+            );
+        }
+    }
+}
+
+fn elementos<'a, 'b>(no: Node<'a, 'b>) -> impl Iterator<Item = Node<'a, 'b>> {
+    no.children().filter(Node::is_element)
+}
+
+fn e_secao(no: Node) -> bool {
+    elementos(no).next().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ConsultaResposta, EnvioResposta, RespostaESocial};
+    use super::*;
+
+    #[test]
+    fn consulta_em_xml_e_igual_a_em_ini() {
+        assert_eq!(
+            ConsultaResposta::de_xml(include_str!("amostras/consulta.xml")).unwrap(),
+            ConsultaResposta::de_ini(include_str!("amostras/consulta.ini")).unwrap()
+        );
+    }
+
+    #[test]
+    fn sessoes_agrupadas_em_um_elemento_raiz() {
+        let envio = EnvioResposta::de_xml(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <Resposta>\
+               <Envio><Codigo>402</Codigo><Mensagem>Lote &amp; eventos</Mensagem></Envio>\
+               <Ocorrencia001><Codigo>142</Codigo><Descricao>Erro</Descricao></Ocorrencia001>\
+             </Resposta>",
+        )
+        .unwrap();
+
+        assert_eq!(envio.cod_resposta, 402);
+        assert_eq!(envio.desc_resposta, "Lote & eventos");
+        assert_eq!(envio.ocorrencias[0].codigo, 142);
+    }
+
+    #[test]
+    fn xml_mal_formado_retorna_erro() {
+        assert!(matches!(
+            RespostaESocial::de_xml("<Envio><Codigo>201</Envio>"),
+            Err(ACBrError::RespostaInvalida(_))
+        ));
+    }
+}
@@ -0,0 +1,320 @@
+//! Alertas de validação dos eventos (`eSocial_Validar`).
+//!
+//! Quando a validação falha, a biblioteca devolve em `UltimoRetorno` um alerta por linha,
+//! montado com o modelo de `[eSocial] FormatoAlerta`. Os marcadores `%TAGNIVEL%`, `%ID%`,
+//! `%TAG%`, `%DESCRICAO%` e `%MSG%` do modelo são trocados pelos dados do campo com
+//! problema, e o mesmo modelo é usado aqui para ler cada linha de volta.
+
+use crate::error::ACBrError;
+
+#[cfg(feature = "async")]
+use super::SessaoAssincrona;
+use super::{ACBrLibEsocial, SessaoComPrazo, SessaoIsolada};
+
+/// Campo rejeitado pela validação de um evento.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertaValidacao {
+    /// Caminho das tags até o campo, como `<eSocial><evtAdmissao><trabalhador>` (`%TAGNIVEL%`).
+    pub caminho: String,
+    /// Identificador do campo no leiaute (`%ID%`).
+    pub id: String,
+    /// Nome da tag do campo (`%TAG%`).
+    pub tag: String,
+    /// Descrição do campo no leiaute (`%DESCRICAO%`).
+    pub descricao: String,
+    /// Motivo da rejeição (`%MSG%`).
+    pub mensagem: String,
+}
+
+/// Marcador do modelo e o campo do alerta que ele preenche.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marcador {
+    Caminho,
+    Id,
+    Tag,
+    Descricao,
+    Mensagem,
+}
+
+/// `%TAGNIVEL%` vem antes de `%TAG%`, que é o começo dele.
+const MARCADORES: [(&str, Marcador); 5] = [
+    ("%TAGNIVEL%", Marcador::Caminho),
+    ("%ID%", Marcador::Id),
+    ("%TAG%", Marcador::Tag),
+    ("%DESCRICAO%", Marcador::Descricao),
+    ("%MSG%", Marcador::Mensagem),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Parte {
+    Texto(String),
+    Marcador(Marcador),
+}
+
+/// Modelo de `[eSocial] FormatoAlerta`, usado para ler os alertas de volta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatoAlerta {
+    partes: Vec<Parte>,
+}
+
+impl FormatoAlerta {
+    /// Modelo distribuído no `config.ini` da biblioteca.
+    pub const PADRAO: &'static str = "TAG:%TAGNIVEL% ID:%ID%/%TAG%(%DESCRICAO%) - %MSG%.";
+
+    /// Modelo com os campos separados por `|`, que não aparece nos nomes de tags nem nos
+    /// identificadores. A mensagem fica por último e pode conter qualquer texto.
+    pub const ESTRUTURADO: &'static str = "%TAGNIVEL%|%ID%|%TAG%|%DESCRICAO%|%MSG%";
+
+    /// Lê o modelo; os marcadores não diferenciam maiúsculas de minúsculas, como na
+    /// biblioteca.
+    pub fn new(modelo: &str) -> Self {
+        let mut partes = Vec::new();
+        let mut texto = String::new();
+        let mut resto = modelo;
+
+        while !resto.is_empty() {
+            let marcador = MARCADORES.iter().find(|(nome, _)| {
+                resto
+                    .get(..nome.len())
+                    .is_some_and(|inicio| inicio.eq_ignore_ascii_case(nome))
+            });
+
+            match marcador {
+                Some((nome, marcador)) => {
+                    if !texto.is_empty() {
+                        partes.push(Parte::Texto(std::mem::take(&mut texto)));
+                    }
+                    partes.push(Parte::Marcador(*marcador));
+                    resto = &resto[nome.len()..];
+                }
+                None => {
+                    let caractere = resto.chars().next().unwrap_or_default();
+                    texto.push(caractere);
+                    resto = &resto[caractere.len_utf8()..];
+                }
+            }
+        }
+
+        if !texto.is_empty() {
+            partes.push(Parte::Texto(texto));
+        }
+
+        FormatoAlerta { partes }
+    }
+
+    /// Alertas das linhas que seguem o modelo; as demais linhas são ignoradas.
+    pub fn ler(&self, texto: &str) -> Vec<AlertaValidacao> {
+        texto
+            .lines()
+            .map(|linha| linha.trim_end_matches('\r'))
+            .filter_map(|linha| self.ler_linha(linha))
+            .collect()
+    }
+
+    /// Cada marcador vai até a próxima ocorrência do texto que o segue no modelo. O texto
+    /// do fim do modelo é procurado a partir do fim da linha, para que a mensagem possa
+    /// conter os mesmos separadores.
+    fn ler_linha(&self, linha: &str) -> Option<AlertaValidacao> {
+        let mut alerta = AlertaValidacao::default();
+        let mut resto = linha;
+
+        for (i, parte) in self.partes.iter().enumerate() {
+            match parte {
+                Parte::Texto(texto) => resto = resto.strip_prefix(texto.as_str())?,
+                Parte::Marcador(marcador) => {
+                    let fim = match self.partes.get(i + 1) {
+                        Some(Parte::Texto(texto)) if i + 2 == self.partes.len() => {
+                            resto.strip_suffix(texto.as_str())?.len()
+                        }
+                        Some(Parte::Texto(texto)) => resto.find(texto.as_str())?,
+                        Some(Parte::Marcador(_)) => 0,
+                        None => resto.len(),
+                    };
+
+                    let valor = resto[..fim].trim().to_string();
+                    match marcador {
+                        Marcador::Caminho => alerta.caminho = valor,
+                        Marcador::Id => alerta.id = valor,
+                        Marcador::Tag => alerta.tag = valor,
+                        Marcador::Descricao => alerta.descricao = valor,
+                        Marcador::Mensagem => alerta.mensagem = valor,
+                    }
+                    resto = &resto[fim..];
+                }
+            }
+        }
+
+        resto.is_empty().then_some(alerta)
+    }
+}
+
+impl Default for FormatoAlerta {
+    fn default() -> Self {
+        FormatoAlerta::new(FormatoAlerta::PADRAO)
+    }
+}
+
+/// Alertas do resultado de `esocial_validar`, lidos com o formato informado. Se nenhuma
+/// linha do erro seguir o modelo, o erro da biblioteca é devolvido como veio.
+fn ler_alertas(
+    resultado: Result<(), ACBrError>,
+    formato: impl FnOnce() -> Result<FormatoAlerta, ACBrError>,
+) -> Result<Vec<AlertaValidacao>, ACBrError> {
+    let mensagem = match resultado {
+        Ok(()) => return Ok(Vec::new()),
+        Err(ACBrError::Execucao(mensagem)) => mensagem,
+        Err(erro) => return Err(erro),
+    };
+
+    let alertas = formato()?.ler(&mensagem);
+    if alertas.is_empty() {
+        return Err(ACBrError::Execucao(mensagem));
+    }
+
+    Ok(alertas)
+}
+
+/// Formato a partir do valor lido de `[eSocial] FormatoAlerta`. Sem o valor, a biblioteca
+/// usa `FormatoAlerta::PADRAO`.
+fn formato_do_valor(valor: Result<String, ACBrError>) -> Result<FormatoAlerta, ACBrError> {
+    match valor {
+        Ok(modelo) if !modelo.is_empty() => Ok(FormatoAlerta::new(&modelo)),
+        Ok(_) | Err(ACBrError::SessaoNaoEncontrada(_) | ACBrError::ChaveNaoEncontrada(_)) => {
+            Ok(FormatoAlerta::default())
+        }
+        Err(erro) => Err(erro),
+    }
+}
+
+impl ACBrLibEsocial {
+    /// Valida os eventos carregados. Sem problemas, devolve uma lista vazia; quando a
+    /// validação falha, devolve um alerta para cada campo rejeitado, lido com o
+    /// `FormatoAlerta` da sessão. Se nenhuma linha do erro seguir o modelo, o erro da
+    /// biblioteca é devolvido como veio.
+    pub fn validar_esocial(&self) -> Result<Vec<AlertaValidacao>, ACBrError> {
+        ler_alertas(self.esocial_validar(), || self.formato_alerta())
+    }
+
+    /// Modelo dos alertas da sessão (`[eSocial] FormatoAlerta`). Sem o valor, a
+    /// biblioteca usa `FormatoAlerta::PADRAO`.
+    pub fn formato_alerta(&self) -> Result<FormatoAlerta, ACBrError> {
+        formato_do_valor(self.esocial_config_ler_valor("eSocial", "FormatoAlerta"))
+    }
+
+    /// Grava `FormatoAlerta::ESTRUTURADO` na sessão, para que os alertas não dependam do
+    /// modelo do arquivo de configuração.
+    pub fn usar_formato_alerta_estruturado(&self) -> Result<(), ACBrError> {
+        self.esocial_config_gravar_valor("eSocial", "FormatoAlerta", FormatoAlerta::ESTRUTURADO)
+    }
+}
+
+impl SessaoIsolada {
+    /// Equivalente de `ACBrLibEsocial::validar_esocial`, com os alertas lidos neste
+    /// processo.
+    pub fn validar_esocial(&self) -> Result<Vec<AlertaValidacao>, ACBrError> {
+        ler_alertas(self.esocial_validar(), || self.formato_alerta())
+    }
+
+    /// Equivalente de `ACBrLibEsocial::formato_alerta`.
+    pub fn formato_alerta(&self) -> Result<FormatoAlerta, ACBrError> {
+        formato_do_valor(self.esocial_config_ler_valor("eSocial", "FormatoAlerta"))
+    }
+
+    /// Equivalente de `ACBrLibEsocial::usar_formato_alerta_estruturado`.
+    pub fn usar_formato_alerta_estruturado(&self) -> Result<(), ACBrError> {
+        self.esocial_config_gravar_valor("eSocial", "FormatoAlerta", FormatoAlerta::ESTRUTURADO)
+    }
+}
+
+impl SessaoComPrazo {
+    /// Equivalente de `ACBrLibEsocial::validar_esocial`, no prazo da sessão.
+    pub fn validar_esocial(&self) -> Result<Vec<AlertaValidacao>, ACBrError> {
+        self.executar(|sessao| sessao.validar_esocial())
+    }
+
+    /// Equivalente de `ACBrLibEsocial::formato_alerta`.
+    pub fn formato_alerta(&self) -> Result<FormatoAlerta, ACBrError> {
+        self.executar(|sessao| sessao.formato_alerta())
+    }
+
+    /// Equivalente de `ACBrLibEsocial::usar_formato_alerta_estruturado`.
+    pub fn usar_formato_alerta_estruturado(&self) -> Result<(), ACBrError> {
+        self.executar(|sessao| sessao.usar_formato_alerta_estruturado())
+    }
+}
+
+#[cfg(feature = "async")]
+impl SessaoAssincrona {
+    /// Equivalente assíncrono de `ACBrLibEsocial::validar_esocial`.
+    pub async fn validar_esocial(&self) -> Result<Vec<AlertaValidacao>, ACBrError> {
+        self.executar(|sessao| sessao.validar_esocial()).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::formato_alerta`.
+    pub async fn formato_alerta(&self) -> Result<FormatoAlerta, ACBrError> {
+        self.executar(|sessao| sessao.formato_alerta()).await
+    }
+
+    /// Equivalente assíncrono de `ACBrLibEsocial::usar_formato_alerta_estruturado`.
+    pub async fn usar_formato_alerta_estruturado(&self) -> Result<(), ACBrError> {
+        self.executar(|sessao| sessao.usar_formato_alerta_estruturado())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alertas_no_modelo_padrao() {
+        let alertas = FormatoAlerta::default().ler(
+            "Falha na validação dos dados do evento\r\n\
+             TAG:<eSocial><evtAdmissao><trabalhador> ID:B03/cpfTrab(CPF do trabalhador) - Nenhum valor informado.\r\n\
+             TAG:<eSocial><evtAdmissao><trabalhador> ID:B04/nmTrab(Nome do trabalhador) - Tamanho maior que o permitido (70).",
+        );
+
+        assert_eq!(
+            alertas,
+            [
+                AlertaValidacao {
+                    caminho: "<eSocial><evtAdmissao><trabalhador>".into(),
+                    id: "B03".into(),
+                    tag: "cpfTrab".into(),
+                    descricao: "CPF do trabalhador".into(),
+                    mensagem: "Nenhum valor informado".into(),
+                },
+                AlertaValidacao {
+                    caminho: "<eSocial><evtAdmissao><trabalhador>".into(),
+                    id: "B04".into(),
+                    tag: "nmTrab".into(),
+                    descricao: "Nome do trabalhador".into(),
+                    mensagem: "Tamanho maior que o permitido (70)".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mensagem_pode_conter_os_separadores_do_modelo() {
+        let alertas = FormatoAlerta::new(FormatoAlerta::ESTRUTURADO)
+            .ler("<eSocial><evtInfoEmpregador>|A07|nrInsc|Número de inscrição|Valor | inválido");
+
+        assert_eq!(alertas[0].tag, "nrInsc");
+        assert_eq!(alertas[0].mensagem, "Valor | inválido");
+    }
+
+    #[test]
+    fn modelo_com_marcadores_em_minusculas_e_sem_todos_os_campos() {
+        let formato = FormatoAlerta::new("[%tag%] %msg%");
+
+        assert_eq!(
+            formato.ler("[cpfTrab] Nenhum valor informado\nlinha fora do modelo"),
+            [AlertaValidacao {
+                tag: "cpfTrab".into(),
+                mensagem: "Nenhum valor informado".into(),
+                ..Default::default()
+            }]
+        );
+    }
+}
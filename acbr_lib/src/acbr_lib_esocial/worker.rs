@@ -0,0 +1,82 @@
+use std::io::{self, BufRead, Write};
+
+use crate::error::ACBrError;
+
+use super::protocolo::{self, CONFIG_ARQUIVO, CONFIG_MODELO, CONFIG_TEMPORARIA};
+use super::ACBrLibEsocial;
+
+/// Laço do processo worker usado pela `SessaoIsolada`: cria uma sessão e atende as
+/// requisições recebidas pela entrada padrão, respondendo pela saída padrão.
+///
+/// Termina quando a entrada é fechada ou ao receber `finalizar`, finalizando a sessão.
+/// Retorna o código de saída do processo. O binário `acbr_esocial_worker` apenas chama
+/// esta função; um executável próprio pode fazer o mesmo.
+pub fn executar_worker() -> i32 {
+    let entrada = io::stdin().lock();
+    let saida = io::stdout().lock();
+
+    match servir(entrada, saida) {
+        Ok(()) => 0,
+        Err(erro) => {
+            eprintln!("Erro na comunicação com o processo principal: {}", erro);
+            1
+        }
+    }
+}
+
+fn servir(entrada: impl BufRead, mut saida: impl Write) -> io::Result<()> {
+    let mut linhas = entrada.lines();
+
+    let inicializacao = match linhas.next() {
+        Some(linha) => protocolo::decodificar(&linha?),
+        None => return Ok(()),
+    };
+
+    let sessao = match criar(&inicializacao) {
+        Ok(sessao) => {
+            responder(&mut saida, Ok(String::new()))?;
+            sessao
+        }
+        Err(erro) => return responder(&mut saida, Err(erro)),
+    };
+
+    for linha in linhas {
+        let campos = protocolo::decodificar(&linha?);
+        let Some((metodo, argumentos)) = campos.split_first() else {
+            responder(&mut saida, Err(protocolo::resposta_invalida("")))?;
+            continue;
+        };
+
+        if metodo == protocolo::FINALIZAR {
+            let resultado = sessao.finalizar().map(|()| String::new());
+            return responder(&mut saida, resultado);
+        }
+
+        responder(&mut saida, sessao.despachar(metodo, argumentos))?;
+    }
+
+    Ok(())
+}
+
+fn criar(campos: &[String]) -> Result<ACBrLibEsocial, ACBrError> {
+    match campos {
+        [requisicao, modo, caminho, chave] if requisicao == protocolo::INICIALIZAR => {
+            match modo.as_str() {
+                CONFIG_ARQUIVO => ACBrLibEsocial::new(caminho.as_str(), chave.as_str()),
+                CONFIG_MODELO => ACBrLibEsocial::com_modelo_config(caminho, chave),
+                CONFIG_TEMPORARIA => ACBrLibEsocial::com_config_temporaria(chave),
+                modo => Err(protocolo::resposta_invalida(modo)),
+            }
+        }
+        campos => Err(protocolo::resposta_invalida(
+            campos.first().map(String::as_str).unwrap_or_default(),
+        )),
+    }
+}
+
+fn responder(saida: &mut impl Write, resultado: Result<String, ACBrError>) -> io::Result<()> {
+    let linha = protocolo::codificar(&protocolo::codificar_resultado(resultado));
+
+    saida.write_all(linha.as_bytes())?;
+    saida.flush()
+}
@@ -0,0 +1,6 @@
+//! Processo worker da `SessaoIsolada`: carrega a ACBrLib e atende as chamadas do processo
+//! principal pela entrada e saída padrão.
+
+fn main() {
+    std::process::exit(acbr_lib::acbr_lib_esocial::executar_worker());
+}
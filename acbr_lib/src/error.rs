@@ -0,0 +1,221 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Erros retornados pelo wrapper da ACBrLib.
+///
+/// Os códigos negativos documentados da ACBrLib têm uma variante própria, sempre
+/// acompanhada do texto de `UltimoRetorno` obtido no momento da falha.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ACBrError {
+    /// A biblioteca não pôde ser carregada.
+    CarregarBiblioteca(String),
+    /// Funções que não foram encontradas na biblioteca carregada.
+    FuncaoNaoEncontrada(Vec<String>),
+    /// A biblioteca retornou sucesso, mas não devolveu o ponteiro da instância.
+    PonteiroNulo,
+    /// O texto informado no parâmetro contém um byte NUL e não pode ser enviado à biblioteca.
+    TextoInvalido(&'static str),
+    /// O texto informado no parâmetro tem um caractere que não existe na codificação da
+    /// sessão (Windows-1252, com `CodificacaoResposta=1`).
+    CaractereInvalido {
+        parametro: &'static str,
+        caractere: char,
+    },
+    /// A thread ou o processo que mantinha a sessão foi encerrado.
+    SessaoEncerrada,
+    /// A chamada não terminou dentro do prazo informado.
+    Timeout(Duration),
+    /// Uma chamada anterior passou do prazo e a sessão não aceita mais chamadas.
+    SessaoEmQuarentena,
+    /// Falha ao iniciar ou se comunicar com o processo worker, inclusive quando ele termina
+    /// de forma inesperada durante a chamada.
+    Worker(String),
+    /// Valor da configuração que não corresponde ao tipo da chave.
+    ConfigInvalida {
+        secao: String,
+        chave: String,
+        valor: String,
+    },
+    /// A resposta da biblioteca não está no formato esperado.
+    RespostaInvalida(String),
+    /// Esquema XSD com uma construção que não é suportada ou com referências que não
+    /// existem.
+    EsquemaInvalido(String),
+    /// -1: a biblioteca não foi inicializada.
+    NaoInicializada(String),
+    /// -2: a biblioteca não foi finalizada.
+    NaoFinalizada(String),
+    /// -3: a sessão informada não existe na configuração.
+    SessaoNaoEncontrada(String),
+    /// -4: a chave informada não existe na configuração.
+    ChaveNaoEncontrada(String),
+    /// -5: o arquivo informado (configuração, INI ou XML) não foi encontrado.
+    ArquivoNaoEncontrado(String),
+    /// -6: o diretório informado é inválido.
+    DiretorioInvalido(String),
+    /// -7: o método não é suportado por esta versão da biblioteca.
+    NaoImplementado(String),
+    /// -8: o evento informado não foi encontrado.
+    EventoNaoEncontrado(String),
+    /// -9: um dos parâmetros informados é inválido.
+    ParametroInvalido(String),
+    /// -10: erro durante a execução do método.
+    Execucao(String),
+    /// Código de retorno não documentado.
+    Desconhecido { codigo: i32, mensagem: String },
+}
+
+impl ACBrError {
+    /// Monta o erro correspondente ao código de retorno da ACBrLib.
+    pub fn from_codigo(codigo: i32, mensagem: String) -> Self {
+        match codigo {
+            -1 => ACBrError::NaoInicializada(mensagem),
+            -2 => ACBrError::NaoFinalizada(mensagem),
+            -3 => ACBrError::SessaoNaoEncontrada(mensagem),
+            -4 => ACBrError::ChaveNaoEncontrada(mensagem),
+            -5 => ACBrError::ArquivoNaoEncontrado(mensagem),
+            -6 => ACBrError::DiretorioInvalido(mensagem),
+            -7 => ACBrError::NaoImplementado(mensagem),
+            -8 => ACBrError::EventoNaoEncontrado(mensagem),
+            -9 => ACBrError::ParametroInvalido(mensagem),
+            -10 => ACBrError::Execucao(mensagem),
+            codigo => ACBrError::Desconhecido { codigo, mensagem },
+        }
+    }
+
+    /// Código de retorno da ACBrLib, quando o erro veio da biblioteca.
+    pub fn codigo(&self) -> Option<i32> {
+        match self {
+            ACBrError::NaoInicializada(_) => Some(-1),
+            ACBrError::NaoFinalizada(_) => Some(-2),
+            ACBrError::SessaoNaoEncontrada(_) => Some(-3),
+            ACBrError::ChaveNaoEncontrada(_) => Some(-4),
+            ACBrError::ArquivoNaoEncontrado(_) => Some(-5),
+            ACBrError::DiretorioInvalido(_) => Some(-6),
+            ACBrError::NaoImplementado(_) => Some(-7),
+            ACBrError::EventoNaoEncontrado(_) => Some(-8),
+            ACBrError::ParametroInvalido(_) => Some(-9),
+            ACBrError::Execucao(_) => Some(-10),
+            ACBrError::Desconhecido { codigo, .. } => Some(*codigo),
+            _ => None,
+        }
+    }
+
+    /// Texto de `UltimoRetorno` anexado ao erro, quando o erro veio da biblioteca.
+    pub fn mensagem(&self) -> Option<&str> {
+        match self {
+            ACBrError::NaoInicializada(mensagem)
+            | ACBrError::NaoFinalizada(mensagem)
+            | ACBrError::SessaoNaoEncontrada(mensagem)
+            | ACBrError::ChaveNaoEncontrada(mensagem)
+            | ACBrError::ArquivoNaoEncontrado(mensagem)
+            | ACBrError::DiretorioInvalido(mensagem)
+            | ACBrError::NaoImplementado(mensagem)
+            | ACBrError::EventoNaoEncontrado(mensagem)
+            | ACBrError::ParametroInvalido(mensagem)
+            | ACBrError::Execucao(mensagem)
+            | ACBrError::Desconhecido { mensagem, .. } => Some(mensagem),
+            _ => None,
+        }
+    }
+
+    /// Indica se o código deixa a instância em um estado em que não deve mais ser usada:
+    /// biblioteca não inicializada (-1) ou não finalizada (-2) e códigos não documentados.
+    pub fn codigo_fatal(codigo: i32) -> bool {
+        matches!(codigo, -2..=-1) || codigo < -10
+    }
+
+    /// Indica se, depois deste erro, a sessão deve ser descartada em vez de reutilizada.
+    pub fn fatal(&self) -> bool {
+        match self {
+            ACBrError::PonteiroNulo
+            | ACBrError::SessaoEncerrada
+            | ACBrError::Timeout(_)
+            | ACBrError::SessaoEmQuarentena
+            | ACBrError::Worker(_) => true,
+            erro => erro.codigo().is_some_and(ACBrError::codigo_fatal),
+        }
+    }
+
+    fn descricao(&self) -> &'static str {
+        match self {
+            ACBrError::NaoInicializada(_) => "Biblioteca não inicializada",
+            ACBrError::NaoFinalizada(_) => "Biblioteca não finalizada",
+            ACBrError::SessaoNaoEncontrada(_) => "Sessão não encontrada na configuração",
+            ACBrError::ChaveNaoEncontrada(_) => "Chave não encontrada na configuração",
+            ACBrError::ArquivoNaoEncontrado(_) => "Arquivo não encontrado",
+            ACBrError::DiretorioInvalido(_) => "Diretório inválido",
+            ACBrError::NaoImplementado(_) => "Método não implementado",
+            ACBrError::EventoNaoEncontrado(_) => "Evento não encontrado",
+            ACBrError::ParametroInvalido(_) => "Parâmetro inválido",
+            ACBrError::Execucao(_) => "Erro ao executar o método",
+            _ => "Erro da biblioteca",
+        }
+    }
+}
+
+impl fmt::Display for ACBrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ACBrError::CarregarBiblioteca(erro) => {
+                write!(f, "Erro ao carregar a biblioteca: {}", erro)
+            }
+            ACBrError::FuncaoNaoEncontrada(funcoes) => write!(
+                f,
+                "Funções não encontradas na biblioteca: {}",
+                funcoes.join(", ")
+            ),
+            ACBrError::PonteiroNulo => write!(
+                f,
+                "Ponteiro retornado é nulo mesmo com inicialização bem sucedida"
+            ),
+            ACBrError::SessaoEncerrada => write!(f, "A sessão foi encerrada"),
+            ACBrError::Timeout(prazo) => {
+                write!(f, "A chamada não terminou dentro do prazo de {:?}", prazo)
+            }
+            ACBrError::SessaoEmQuarentena => write!(
+                f,
+                "A sessão está em quarentena porque uma chamada passou do prazo"
+            ),
+            ACBrError::ConfigInvalida {
+                secao,
+                chave,
+                valor,
+            } => write!(
+                f,
+                "Valor inválido na configuração: [{}] {}={}",
+                secao, chave, valor
+            ),
+            ACBrError::Worker(erro) => write!(f, "Falha no processo worker: {}", erro),
+            ACBrError::RespostaInvalida(erro) => {
+                write!(f, "Resposta da biblioteca inválida: {}", erro)
+            }
+            ACBrError::EsquemaInvalido(erro) => write!(f, "Esquema XSD inválido: {}", erro),
+            ACBrError::TextoInvalido(parametro) => write!(
+                f,
+                "Falha ao converter {}: o texto contém um byte nulo",
+                parametro
+            ),
+            ACBrError::CaractereInvalido {
+                parametro,
+                caractere,
+            } => write!(
+                f,
+                "Falha ao converter {}: o caractere {:?} não existe em Windows-1252",
+                parametro, caractere
+            ),
+            erro => {
+                let codigo = erro.codigo().unwrap_or_default();
+                let mensagem = erro.mensagem().unwrap_or_default();
+
+                if mensagem.is_empty() {
+                    write!(f, "{} (código {})", erro.descricao(), codigo)
+                } else {
+                    write!(f, "{} (código {}): {}", erro.descricao(), codigo, mensagem)
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ACBrError {}
@@ -1,2 +1,5 @@
 mod utils;
-pub mod acbr_lib_esocial;
\ No newline at end of file
+pub mod acbr_lib_esocial;
+pub mod error;
+pub mod localizador;
+pub mod metricas;
@@ -0,0 +1,336 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::error::ACBrError;
+
+/// Variável de ambiente com o caminho da biblioteca do eSocial (arquivo ou diretório).
+pub const VARIAVEL_ESOCIAL: &str = "ACBRLIB_ESOCIAL_PATH";
+
+/// Define onde a biblioteca da ACBrLib é procurada.
+///
+/// A ordem de busca é: o caminho explícito, a variável de ambiente e, por fim, cada um
+/// dos diretórios da lista de busca com cada um dos nomes de arquivo configurados.
+#[derive(Debug, Clone)]
+pub struct LocalizadorBiblioteca {
+    caminho: Option<PathBuf>,
+    variavel_ambiente: Option<String>,
+    diretorios: Vec<PathBuf>,
+    nomes: Vec<String>,
+    dependencias: Option<PathBuf>,
+}
+
+impl LocalizadorBiblioteca {
+    /// Localizador padrão da ACBrLibeSocial: variável `ACBRLIB_ESOCIAL_PATH`, pasta
+    /// `resources` ao lado do executável e o nome do arquivo da plataforma atual.
+    pub fn esocial() -> Self {
+        LocalizadorBiblioteca {
+            caminho: None,
+            variavel_ambiente: Some(VARIAVEL_ESOCIAL.to_string()),
+            diretorios: diretorios_padrao(),
+            nomes: nomes_esocial()
+                .iter()
+                .map(|nome| nome.to_string())
+                .collect(),
+            dependencias: None,
+        }
+    }
+
+    /// Usa exatamente este arquivo, ignorando a variável de ambiente e a lista de busca.
+    pub fn com_caminho(mut self, caminho: impl Into<PathBuf>) -> Self {
+        self.caminho = Some(caminho.into());
+        self
+    }
+
+    /// Troca a variável de ambiente consultada antes da lista de busca.
+    pub fn com_variavel_ambiente(mut self, variavel: impl Into<String>) -> Self {
+        self.variavel_ambiente = Some(variavel.into());
+        self
+    }
+
+    pub fn sem_variavel_ambiente(mut self) -> Self {
+        self.variavel_ambiente = None;
+        self
+    }
+
+    /// Substitui a lista de diretórios de busca.
+    pub fn com_diretorios<I, P>(mut self, diretorios: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.diretorios = diretorios.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adiciona um diretório ao início da lista de busca.
+    pub fn adicionar_diretorio(mut self, diretorio: impl Into<PathBuf>) -> Self {
+        self.diretorios.insert(0, diretorio.into());
+        self
+    }
+
+    /// Substitui os nomes de arquivo procurados em cada diretório.
+    pub fn com_nomes<I, S>(mut self, nomes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.nomes = nomes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Diretório com as dependências da biblioteca (OpenSSL, libxml2...). Quando não é
+    /// informado, é usada a pasta `deps` ao lado da biblioteca, se existir.
+    pub fn com_dependencias(mut self, diretorio: impl Into<PathBuf>) -> Self {
+        self.dependencias = Some(diretorio.into());
+        self
+    }
+
+    /// Retorna o caminho da biblioteca, ou um erro com todos os caminhos verificados.
+    pub fn localizar(&self) -> Result<PathBuf, ACBrError> {
+        self.localizar_com(|variavel| env::var_os(variavel))
+    }
+
+    /// `localizar` lendo as variáveis de ambiente por `ler_variavel`.
+    fn localizar_com(
+        &self,
+        ler_variavel: impl Fn(&str) -> Option<OsString>,
+    ) -> Result<PathBuf, ACBrError> {
+        if let Some(caminho) = &self.caminho {
+            if caminho.is_file() {
+                return Ok(caminho.clone());
+            }
+
+            return Err(ACBrError::CarregarBiblioteca(format!(
+                "A biblioteca não foi encontrada no caminho: {}",
+                caminho.display()
+            )));
+        }
+
+        let mut verificados: Vec<PathBuf> = Vec::new();
+
+        if let Some(variavel) = &self.variavel_ambiente {
+            if let Some(valor) = ler_variavel(variavel).filter(|valor| !valor.is_empty()) {
+                let caminho = PathBuf::from(valor);
+
+                if caminho.is_dir() {
+                    if let Some(encontrado) = self.procurar_em(&caminho, &mut verificados) {
+                        return Ok(encontrado);
+                    }
+                } else if caminho.is_file() {
+                    return Ok(caminho);
+                } else {
+                    verificados.push(caminho);
+                }
+
+                return Err(nao_encontrada(&verificados));
+            }
+        }
+
+        for diretorio in &self.diretorios {
+            if let Some(encontrado) = self.procurar_em(diretorio, &mut verificados) {
+                return Ok(encontrado);
+            }
+        }
+
+        Err(nao_encontrada(&verificados))
+    }
+
+    /// Diretório de dependências a ser usado para a biblioteca encontrada em `biblioteca`.
+    pub fn diretorio_dependencias(&self, biblioteca: &Path) -> Option<PathBuf> {
+        if let Some(dependencias) = &self.dependencias {
+            return Some(dependencias.clone());
+        }
+
+        let deps = biblioteca.parent()?.join("deps");
+
+        deps.is_dir().then_some(deps)
+    }
+
+    fn procurar_em(&self, diretorio: &Path, verificados: &mut Vec<PathBuf>) -> Option<PathBuf> {
+        for nome in &self.nomes {
+            let caminho = diretorio.join(nome);
+
+            if caminho.is_file() {
+                return Some(caminho);
+            }
+
+            verificados.push(caminho);
+        }
+
+        None
+    }
+}
+
+impl Default for LocalizadorBiblioteca {
+    fn default() -> Self {
+        LocalizadorBiblioteca::esocial()
+    }
+}
+
+fn nao_encontrada(verificados: &[PathBuf]) -> ACBrError {
+    let caminhos: Vec<String> = verificados
+        .iter()
+        .map(|caminho| caminho.display().to_string())
+        .collect();
+
+    ACBrError::CarregarBiblioteca(format!(
+        "A biblioteca não foi encontrada. Caminhos verificados: {}",
+        caminhos.join(", ")
+    ))
+}
+
+/// Pasta `resources` ao lado do executável, o próprio diretório do executável e, no Linux,
+/// os diretórios de bibliotecas do sistema.
+fn diretorios_padrao() -> Vec<PathBuf> {
+    let mut diretorios = Vec::new();
+
+    if let Some(executavel) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        diretorios.push(executavel.join("resources"));
+        diretorios.push(executavel);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        diretorios.push(PathBuf::from("/usr/local/lib"));
+        diretorios.push(PathBuf::from("/usr/lib"));
+    }
+
+    diretorios
+}
+
+/// Nomes de arquivo da ACBrLibeSocial distribuídos para cada sistema e arquitetura.
+fn nomes_esocial() -> &'static [&'static str] {
+    if cfg!(target_os = "windows") {
+        if cfg!(target_pointer_width = "32") {
+            &["ACBreSocial32.dll"]
+        } else {
+            &["ACBreSocial64.dll"]
+        }
+    } else if cfg!(target_pointer_width = "32") {
+        &["libacbresocial32.so"]
+    } else {
+        &["libacbresocial64.so"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn diretorio_temporario(nome: &str) -> PathBuf {
+        let diretorio =
+            env::temp_dir().join(format!("acbr_localizador_{}_{}", nome, std::process::id()));
+        let _ = fs::remove_dir_all(&diretorio);
+        fs::create_dir_all(&diretorio).unwrap();
+        diretorio
+    }
+
+    #[test]
+    fn caminho_explicito_tem_prioridade() {
+        let diretorio = diretorio_temporario("explicito");
+        let arquivo = diretorio.join("minha_lib.so");
+        fs::write(&arquivo, b"").unwrap();
+
+        let localizador = LocalizadorBiblioteca::esocial()
+            .com_variavel_ambiente("ACBR_TESTE_EXPLICITO")
+            .com_caminho(&arquivo);
+
+        assert_eq!(localizador.localizar().unwrap(), arquivo);
+    }
+
+    #[test]
+    fn variavel_de_ambiente_aceita_arquivo_ou_diretorio() {
+        let diretorio = diretorio_temporario("variavel");
+        let arquivo = diretorio.join("libacbresocial64.so");
+        fs::write(&arquivo, b"").unwrap();
+
+        let ambiente = |variavel: &str| match variavel {
+            "ACBR_TESTE_VARIAVEL_ARQUIVO" => Some(arquivo.clone().into_os_string()),
+            "ACBR_TESTE_VARIAVEL_DIRETORIO" => Some(diretorio.clone().into_os_string()),
+            _ => None,
+        };
+
+        let por_arquivo = LocalizadorBiblioteca::esocial()
+            .com_variavel_ambiente("ACBR_TESTE_VARIAVEL_ARQUIVO")
+            .com_diretorios(Vec::<PathBuf>::new());
+        assert_eq!(por_arquivo.localizar_com(ambiente).unwrap(), arquivo);
+
+        let por_diretorio = LocalizadorBiblioteca::esocial()
+            .com_variavel_ambiente("ACBR_TESTE_VARIAVEL_DIRETORIO")
+            .com_nomes(["libacbresocial64.so"])
+            .com_diretorios(Vec::<PathBuf>::new());
+        assert_eq!(por_diretorio.localizar_com(ambiente).unwrap(), arquivo);
+
+        let vazia = LocalizadorBiblioteca::esocial()
+            .com_variavel_ambiente("ACBR_TESTE_VARIAVEL_VAZIA")
+            .com_diretorios([&diretorio]);
+        assert_eq!(
+            vazia.localizar_com(|_| Some(OsString::new())).unwrap(),
+            arquivo
+        );
+    }
+
+    #[test]
+    fn procura_na_lista_de_diretorios_e_nomes() {
+        let vazio = diretorio_temporario("busca_vazio");
+        let diretorio = diretorio_temporario("busca");
+        let arquivo = diretorio.join("libacbresocial32.so");
+        fs::write(&arquivo, b"").unwrap();
+
+        let localizador = LocalizadorBiblioteca::esocial()
+            .sem_variavel_ambiente()
+            .com_diretorios([&vazio, &diretorio])
+            .com_nomes(["libacbresocial64.so", "libacbresocial32.so"]);
+
+        assert_eq!(localizador.localizar().unwrap(), arquivo);
+    }
+
+    #[test]
+    fn nao_encontrada_lista_os_caminhos_verificados() {
+        let diretorio = diretorio_temporario("nao_encontrada");
+
+        let localizador = LocalizadorBiblioteca::esocial()
+            .sem_variavel_ambiente()
+            .com_diretorios([&diretorio])
+            .com_nomes(["libacbresocial64.so"]);
+
+        match localizador.localizar() {
+            Err(ACBrError::CarregarBiblioteca(mensagem)) => {
+                assert!(
+                    mensagem.contains(&diretorio.join("libacbresocial64.so").display().to_string())
+                )
+            }
+            outro => panic!("esperava erro de biblioteca não encontrada: {:?}", outro),
+        }
+    }
+
+    #[test]
+    fn dependencias_padrao_ficam_ao_lado_da_biblioteca() {
+        let diretorio = diretorio_temporario("dependencias");
+        let arquivo = diretorio.join("libacbresocial64.so");
+        fs::create_dir_all(diretorio.join("deps")).unwrap();
+
+        let localizador = LocalizadorBiblioteca::esocial();
+        assert_eq!(
+            localizador.diretorio_dependencias(&arquivo),
+            Some(diretorio.join("deps"))
+        );
+
+        let localizador = localizador.com_dependencias("/opt/acbr/deps");
+        assert_eq!(
+            localizador.diretorio_dependencias(&arquivo),
+            Some(PathBuf::from("/opt/acbr/deps"))
+        );
+    }
+
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    #[test]
+    fn nome_padrao_no_linux_64() {
+        assert_eq!(nomes_esocial(), ["libacbresocial64.so"]);
+    }
+}
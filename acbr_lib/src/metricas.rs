@@ -0,0 +1,20 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static CHAMADAS_EXPIRADAS: AtomicU64 = AtomicU64::new(0);
+
+/// Contadores do processo, desde o início da execução.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metricas {
+    /// Chamadas que passaram do prazo. Cada uma deixou a sua sessão em quarentena.
+    pub chamadas_expiradas: u64,
+}
+
+pub fn metricas() -> Metricas {
+    Metricas {
+        chamadas_expiradas: CHAMADAS_EXPIRADAS.load(Ordering::Relaxed),
+    }
+}
+
+pub(crate) fn registrar_chamada_expirada() {
+    CHAMADAS_EXPIRADAS.fetch_add(1, Ordering::Relaxed);
+}
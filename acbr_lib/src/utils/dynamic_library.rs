@@ -1,60 +1,73 @@
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::path::Path;
+#[cfg(test)]
 use std::ptr;
-use std::sync::Mutex;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard};
 use std::{
-    env,
     ffi::CString,
     os::raw::{c_char, c_void},
 };
 
+#[cfg(target_os = "windows")]
+use std::env;
+
 #[cfg(target_os = "linux")]
 use std::ffi::CStr;
 
-pub unsafe fn load_library(name: *const c_char) -> *mut c_void {
+use crate::acbr_lib_esocial::funcoes::FuncoesESocial;
+use crate::error::ACBrError;
+use crate::localizador::LocalizadorBiblioteca;
+
+/// Carrega a biblioteca, devolvendo o motivo informado pelo sistema quando não for possível.
+pub unsafe fn load_library(name: *const c_char) -> Result<*mut c_void, String> {
     #[cfg(target_os = "windows")]
     {
-        LoadLibraryA(name)
+        let handle = LoadLibraryA(name);
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(handle)
     }
 
     #[cfg(target_os = "linux")]
     {
-        use std::ffi::CStr;
-        use std::path::Path;
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
 
         let lib_name = CStr::from_ptr(name);
 
-        let lib_path = Path::new(lib_name.to_str().unwrap_or(""));
+        let lib_path = Path::new(OsStr::from_bytes(lib_name.to_bytes()));
         if !lib_path.exists() {
-            eprintln!(
-                "Erro: A biblioteca não foi encontrada no caminho: {:?}",
-                lib_path
-            );
-            return std::ptr::null_mut();
+            return Err(format!(
+                "A biblioteca não foi encontrada no caminho: {}",
+                lib_path.display()
+            ));
         }
 
         let handle = dlopen(name, RTLD_NOW);
         if handle.is_null() {
-            let error = CStr::from_ptr(dlerror()).to_string_lossy();
-            eprintln!("Erro ao carregar biblioteca no Linux: {}", error);
+            let error = dlerror();
+            if error.is_null() {
+                return Err("dlopen falhou sem informar o motivo".into());
+            }
+            return Err(CStr::from_ptr(error).to_string_lossy().into_owned());
         }
-        handle
+        Ok(handle)
     }
 }
 
-// pub fn free_library(handle: *mut c_void) {
-//     unsafe {
-//         #[cfg(target_os = "windows")]
-//         {
-//             FreeLibrary(handle);
-//         }
+pub unsafe fn free_library(handle: *mut c_void) {
+    #[cfg(target_os = "windows")]
+    {
+        FreeLibrary(handle);
+    }
 
-//         #[cfg(target_os = "linux")]
-//         {
-//             dlclose(handle);
-//         }
-//     }
-// }
+    #[cfg(target_os = "linux")]
+    {
+        dlclose(handle);
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 struct SafeHandle(*mut c_void);
@@ -62,88 +75,393 @@ struct SafeHandle(*mut c_void);
 unsafe impl Send for SafeHandle {}
 unsafe impl Sync for SafeHandle {}
 
-lazy_static! {
-    static ref DLL_HANDLES: Mutex<HashMap<ACBrLibType, SafeHandle>> = {
-        let lib_handle: HashMap<ACBrLibType, SafeHandle> = HashMap::new();
+/// Tabela de funções já resolvidas de cada tipo de biblioteca.
+pub(crate) enum FuncoesLib {
+    Esocial(FuncoesESocial),
+}
 
-        let mutex_lib_handle = Mutex::new(lib_handle);
+/// Biblioteca carregada junto com a sua tabela de funções.
+///
+/// É compartilhada por `Arc` entre o mapa global e as sessões que a utilizam, e só é
+/// descarregada (`dlclose`/`FreeLibrary`) quando a última referência é liberada.
+pub(crate) struct Biblioteca {
+    handle: SafeHandle,
+    /// Dependências pré-carregadas, liberadas depois da biblioteca.
+    dependencias: Vec<SafeHandle>,
+    pub funcoes: FuncoesLib,
+    chamadas: RwLock<()>,
+}
 
-        mutex_lib_handle
-    };
-    static ref IS_READING: Mutex<bool> = Mutex::new(false);
+impl Biblioteca {
+    /// Marca uma chamada em andamento. O `reload` aguarda até que todas terminem.
+    pub fn chamada(&self) -> Result<RwLockReadGuard<'_, ()>, ACBrError> {
+        self.chamadas.read().map_err(|_| {
+            ACBrError::CarregarBiblioteca("Não foi possivel obter o estado da DLL".into())
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn de_teste(funcoes: FuncoesLib) -> Self {
+        Biblioteca {
+            handle: SafeHandle(ptr::null_mut()),
+            dependencias: Vec::new(),
+            funcoes,
+            chamadas: RwLock::new(()),
+        }
+    }
+
+    /// Bloqueia até que as chamadas em andamento nesta biblioteca terminem.
+    fn aguardar_chamadas(&self) {
+        drop(self.chamadas.write());
+    }
+}
+
+impl Drop for Biblioteca {
+    fn drop(&mut self) {
+        if !self.handle.0.is_null() {
+            unsafe { free_library(self.handle.0) };
+        }
+
+        for dependencia in self.dependencias.iter().rev() {
+            unsafe { free_library(dependencia.0) };
+        }
+    }
+}
+
+/// Estado do carregamento de um tipo de biblioteca.
+#[derive(Default)]
+struct EstadoCarregamento {
+    biblioteca: Option<Arc<Biblioteca>>,
+    carregando: bool,
+    /// Incrementado ao fim de cada carregamento, para que quem aguardava saiba que
+    /// o carregamento que estava esperando terminou.
+    tentativa: u64,
+    /// Erro do último carregamento que falhou, junto com a tentativa em que ocorreu.
+    erro: Option<(u64, ACBrError)>,
+}
+
+/// Carregamento único e sob demanda de um tipo de biblioteca.
+///
+/// Quem chega enquanto outra thread está carregando aguarda o resultado dela, seja o
+/// sucesso ou o erro. Depois de uma falha, a próxima chamada tenta carregar de novo.
+#[derive(Default)]
+struct Carregamento {
+    estado: Mutex<EstadoCarregamento>,
+    concluido: Condvar,
 }
 
-#[derive(Eq, Hash, PartialEq)]
+impl Carregamento {
+    /// Retorna a biblioteca carregada ou a carrega com `carregar`. Com `recarregar`, uma
+    /// nova biblioteca é carregada mesmo que já exista uma, e a anterior é devolvida.
+    fn obter<F>(
+        &self,
+        recarregar: bool,
+        carregar: F,
+    ) -> Result<(Arc<Biblioteca>, Option<Arc<Biblioteca>>), ACBrError>
+    where
+        F: FnOnce() -> Result<Biblioteca, ACBrError>,
+    {
+        let mut estado = bloquear(&self.estado)?;
+
+        while estado.carregando {
+            let tentativa = estado.tentativa;
+
+            while estado.carregando && estado.tentativa == tentativa {
+                estado = self.concluido.wait(estado).map_err(|_| erro_estado())?;
+            }
+
+            // O carregamento aguardado falhou: o erro é repassado a todos que esperavam
+            if let Some((tentativa_erro, erro)) = &estado.erro {
+                if *tentativa_erro == tentativa && !recarregar {
+                    return Err(erro.clone());
+                }
+            }
+        }
+
+        if !recarregar {
+            if let Some(biblioteca) = &estado.biblioteca {
+                return Ok((Arc::clone(biblioteca), None));
+            }
+        }
+
+        estado.carregando = true;
+        let tentativa = estado.tentativa;
+        drop(estado);
+
+        // A biblioteca é carregada sem segurar o lock, quem chegar agora apenas aguarda
+        let resultado = carregar().map(Arc::new);
+
+        let mut estado = self.estado.lock().unwrap_or_else(|erro| erro.into_inner());
+        estado.carregando = false;
+        estado.tentativa += 1;
+
+        let resultado = match resultado {
+            Ok(biblioteca) => {
+                estado.erro = None;
+                let anterior = estado.biblioteca.replace(Arc::clone(&biblioteca));
+                Ok((biblioteca, anterior))
+            }
+            Err(erro) => {
+                estado.erro = Some((tentativa, erro.clone()));
+                Err(erro)
+            }
+        };
+
+        drop(estado);
+        self.concluido.notify_all();
+
+        resultado
+    }
+}
+
+lazy_static! {
+    static ref DLL_HANDLES: Mutex<HashMap<ACBrLibType, Arc<Carregamento>>> =
+        Mutex::new(HashMap::new());
+    static ref LOCALIZADORES: Mutex<HashMap<ACBrLibType, LocalizadorBiblioteca>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Eq, Hash, PartialEq, Clone, Copy)]
 pub enum ACBrLibType {
     Esocial,
 }
 
-pub fn unread_lib_file(lib: ACBrLibType) -> Result<(), String> {
-    println!("Relendo a DLL");
-    let mut handles = match DLL_HANDLES.lock() {
-        Ok(handles) => handles,
-        Err(_) => return Err("Não foi possivel obter o estado de leitura da DLL".into()),
+/// Obtém o lock sem entrar em pânico caso outra thread tenha falhado segurando o mutex.
+fn bloquear<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, ACBrError> {
+    mutex.lock().map_err(|_| erro_estado())
+}
+
+fn erro_estado() -> ACBrError {
+    ACBrError::CarregarBiblioteca("Não foi possivel obter o estado de leitura da DLL".into())
+}
+
+/// Carregamento do tipo de biblioteca, criado na primeira vez em que é pedido.
+fn carregamento(lib: ACBrLibType) -> Result<Arc<Carregamento>, ACBrError> {
+    let mut handles = bloquear(&DLL_HANDLES)?;
+
+    Ok(Arc::clone(handles.entry(lib).or_default()))
+}
+
+/// Converte o caminho para o formato esperado por `load_library`.
+fn caminho_para_cstring(caminho: &Path) -> Result<CString, ACBrError> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        caminho.as_os_str().as_bytes().to_vec()
     };
 
-    if let Some(handle) = handles.get(&lib) {
-        let _ = drop(handle);
-        handles.remove(&lib);
-    }
+    // LoadLibraryA só aceita texto, então o caminho precisa ser válido
+    #[cfg(not(unix))]
+    let bytes = caminho_para_texto(caminho)?.as_bytes().to_vec();
 
-    Ok(())
+    CString::new(bytes).map_err(|_| {
+        ACBrError::CarregarBiblioteca(format!(
+            "O caminho da biblioteca contém um byte nulo: {}",
+            caminho.display()
+        ))
+    })
+}
+
+#[cfg(any(not(unix), test))]
+fn caminho_para_texto(caminho: &Path) -> Result<&str, ACBrError> {
+    caminho.to_str().ok_or_else(|| {
+        ACBrError::CarregarBiblioteca(format!(
+            "O caminho não é um texto válido: {}",
+            caminho.display()
+        ))
+    })
 }
 
-pub fn read_lib_file(lib: ACBrLibType) -> Result<*mut c_void, String> {
-    let mut handles = DLL_HANDLES.lock().unwrap();
-    let mut is_reading = IS_READING.lock().unwrap();
+/// Define onde o tipo de biblioteca será procurado nos próximos carregamentos.
+pub fn configurar_localizador(
+    lib: ACBrLibType,
+    localizador: LocalizadorBiblioteca,
+) -> Result<(), ACBrError> {
+    bloquear(&LOCALIZADORES)?.insert(lib, localizador);
 
-    // Verifica se já está carregado
-    if let Some(handle) = handles.get(&lib) {
-        return Ok(handle.0);
+    Ok(())
+}
+
+/// Localizador configurado para o tipo de biblioteca, ou o padrão dele.
+fn localizador(lib: ACBrLibType) -> Result<LocalizadorBiblioteca, ACBrError> {
+    if let Some(localizador) = bloquear(&LOCALIZADORES)?.get(&lib) {
+        return Ok(localizador.clone());
     }
 
-    if *is_reading {
-        return Err("O arquivo da biblioteca esta sendo utilizado nesse momento!".into());
+    Ok(match lib {
+        ACBrLibType::Esocial => LocalizadorBiblioteca::esocial(),
+    })
+}
+
+/// Carrega a biblioteca novamente para as próximas sessões e aguarda o fim das chamadas
+/// em andamento na versão anterior.
+///
+/// As sessões já criadas continuam usando a versão anterior, que é descarregada quando a
+/// última delas for liberada. Enquanto a versão anterior estiver carregada, o sistema
+/// operacional pode devolver o mesmo handle para o mesmo caminho.
+pub fn reload_lib_file(lib: ACBrLibType) -> Result<(), ACBrError> {
+    let (_, anterior) = carregamento(lib)?.obter(true, || carregar_biblioteca(lib))?;
+
+    if let Some(anterior) = anterior {
+        anterior.aguardar_chamadas();
     }
 
-    *is_reading = true;
+    Ok(())
+}
+
+/// Retorna a biblioteca já carregada ou a carrega uma única vez. Chamadas concorrentes
+/// aguardam o carregamento em andamento e recebem o mesmo resultado.
+pub(crate) fn read_lib_file(lib: ACBrLibType) -> Result<Arc<Biblioteca>, ACBrError> {
+    let (biblioteca, _) = carregamento(lib)?.obter(false, || carregar_biblioteca(lib))?;
 
-    let mut resources_path = env::current_exe().unwrap();
-    resources_path.pop();
-    resources_path.push("resources");
+    Ok(biblioteca)
+}
 
-    let mut deps_path = resources_path.clone();
-    deps_path.push("deps");
+/// Localiza, carrega a biblioteca e resolve a sua tabela de funções.
+fn carregar_biblioteca(lib: ACBrLibType) -> Result<Biblioteca, ACBrError> {
+    let localizador = localizador(lib)?;
+    let caminho = localizador.localizar()?;
 
-    let deps_path_string = deps_path.to_str().expect("Erro ao converter para string");
+    let lib_name = caminho_para_cstring(&caminho)?;
 
-    let current_path = env::var("PATH").unwrap_or_default();
-    if !current_path.split(';').any(|p| p == deps_path_string) {
-        env::set_var("PATH", format!("{};{}", deps_path_string, current_path));
-    }
+    let (dependencias, ignoradas) = match localizador.diretorio_dependencias(&caminho) {
+        Some(diretorio) => carregar_dependencias(&diretorio)?,
+        None => (Vec::new(), Vec::new()),
+    };
 
-    let lib_filename = match lib {
-        ACBrLibType::Esocial => "ACBreSocial32.dll",
+    let liberar_dependencias = |dependencias: &[SafeHandle]| {
+        for dependencia in dependencias.iter().rev() {
+            unsafe { free_library(dependencia.0) };
+        }
     };
 
-    resources_path.push(lib_filename);
+    unsafe {
+        let lib_handle = match load_library(lib_name.as_ptr()) {
+            Ok(lib_handle) => lib_handle,
+            Err(erro) => {
+                liberar_dependencias(&dependencias);
+
+                let mut mensagem =
+                    format!("Erro ao carregar a DLL {}: {}", caminho.display(), erro);
+                if !ignoradas.is_empty() {
+                    mensagem.push_str(&format!(
+                        ". Dependências que não puderam ser carregadas: {}",
+                        ignoradas.join(", ")
+                    ));
+                }
+                return Err(ACBrError::CarregarBiblioteca(mensagem));
+            }
+        };
+
+        // Resolve todas as funções uma única vez, reportando as ausentes de uma só vez
+        let funcoes = match lib {
+            ACBrLibType::Esocial => match FuncoesESocial::carregar(lib_handle) {
+                Ok(funcoes) => FuncoesLib::Esocial(funcoes),
+                Err(error) => {
+                    free_library(lib_handle);
+                    liberar_dependencias(&dependencias);
+                    return Err(error);
+                }
+            },
+        };
+
+        Ok(Biblioteca {
+            handle: SafeHandle(lib_handle),
+            dependencias,
+            funcoes,
+            chamadas: RwLock::new(()),
+        })
+    }
+}
 
-    let lib_name = CString::new(resources_path.to_str().unwrap()).unwrap();
+/// Dependências pré-carregadas e os caminhos das que não puderam ser carregadas.
+type Dependencias = (Vec<SafeHandle>, Vec<String>);
 
-    unsafe {
-        let lib_handle = load_library(lib_name.as_ptr());
-        if lib_handle.is_null() {
-            return Err("Erro ao carregar a DLL.".into());
+/// No Windows as DLLs dependentes são procuradas no `PATH`, então o diretório de
+/// dependências é incluído no início dele.
+#[cfg(target_os = "windows")]
+fn carregar_dependencias(diretorio: &Path) -> Result<Dependencias, ACBrError> {
+    let path_atual = env::var_os("PATH").unwrap_or_default();
+    let mut caminhos: Vec<_> = env::split_paths(&path_atual).collect();
+
+    if !caminhos.iter().any(|caminho| caminho == diretorio) {
+        caminhos.insert(0, diretorio.to_path_buf());
+
+        let path = env::join_paths(caminhos).map_err(|error| {
+            ACBrError::CarregarBiblioteca(format!(
+                "Não foi possivel incluir {} no PATH: {}",
+                diretorio.display(),
+                error
+            ))
+        })?;
+        env::set_var("PATH", path);
+    }
+
+    Ok((Vec::new(), Vec::new()))
+}
+
+/// No Linux o `PATH` não é usado para procurar bibliotecas, e o `LD_LIBRARY_PATH` só é
+/// lido na inicialização do processo. As bibliotecas do diretório de dependências são
+/// pré-carregadas com `RTLD_GLOBAL`, para que o `dlopen` da biblioteca principal as
+/// encontre já carregadas.
+#[cfg(target_os = "linux")]
+fn carregar_dependencias(diretorio: &Path) -> Result<Dependencias, ACBrError> {
+    let entradas = std::fs::read_dir(diretorio).map_err(|error| {
+        ACBrError::CarregarBiblioteca(format!(
+            "Não foi possivel ler o diretório de dependências {}: {}",
+            diretorio.display(),
+            error
+        ))
+    })?;
+
+    let mut pendentes: Vec<CString> = Vec::new();
+    for entrada in entradas.flatten() {
+        let caminho = entrada.path();
+        let e_biblioteca = caminho
+            .file_name()
+            .and_then(|nome| nome.to_str())
+            .is_some_and(|nome| nome.ends_with(".so") || nome.contains(".so."));
+
+        if e_biblioteca && caminho.is_file() {
+            pendentes.push(caminho_para_cstring(&caminho)?);
+        }
+    }
+    pendentes.sort();
+
+    // Uma dependência pode precisar de outra ainda não carregada, então repete enquanto
+    // alguma nova for carregada
+    let mut carregadas = Vec::new();
+    loop {
+        let quantidade = pendentes.len();
+
+        pendentes.retain(|caminho| {
+            let handle = unsafe { dlopen(caminho.as_ptr(), RTLD_LAZY | RTLD_GLOBAL) };
+
+            if handle.is_null() {
+                true
+            } else {
+                carregadas.push(SafeHandle(handle));
+                false
+            }
+        });
+
+        if pendentes.is_empty() || pendentes.len() == quantidade {
+            break;
         }
+    }
 
-        // Armazena o handle no HashMap global
-        let safe_handle = SafeHandle(lib_handle);
-        handles.insert(lib, safe_handle);
+    // As que sobraram podem nem ser usadas; se forem, vão no erro ao carregar a biblioteca
+    let ignoradas = pendentes
+        .iter()
+        .map(|caminho| caminho.to_string_lossy().into_owned())
+        .collect();
 
-        *is_reading = false;
+    Ok((carregadas, ignoradas))
+}
 
-        Ok(lib_handle)
-    }
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn carregar_dependencias(_diretorio: &Path) -> Result<Dependencias, ACBrError> {
+    Ok((Vec::new(), Vec::new()))
 }
 
 pub fn get_function(dll_handle: *mut c_void, func_name: &str) -> Result<*mut c_void, String> {
@@ -167,7 +485,7 @@ extern "system" {
 #[cfg(target_os = "windows")]
 extern "system" {
     fn LoadLibraryA(name: *const c_char) -> *mut c_void;
-    // fn FreeLibrary(handle: *mut c_void) -> i32;
+    fn FreeLibrary(handle: *mut c_void) -> i32;
 }
 
 #[cfg(target_os = "linux")]
@@ -184,6 +502,9 @@ const RTLD_LAZY: i32 = 0x00001;
 #[cfg(target_os = "linux")]
 const RTLD_NOW: i32 = 0x00002;
 
+#[cfg(target_os = "linux")]
+const RTLD_GLOBAL: i32 = 0x00100;
+
 /// Função que faz o fallback para pegar o endereço da função dependendo do sistema operacional
 unsafe fn get_function_address(handle: *mut c_void, name: *const c_char) -> *mut c_void {
     #[cfg(target_os = "windows")]
@@ -196,3 +517,228 @@ unsafe fn get_function_address(handle: *mut c_void, name: *const c_char) -> *mut
         dlsym(handle, name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aguardar_chamadas_espera_as_chamadas_em_andamento() {
+        let biblioteca = Arc::new(Biblioteca::de_teste(FuncoesLib::Esocial(
+            crate::acbr_lib_esocial::tests::funcoes_de_teste(),
+        )));
+
+        let (iniciou, aguardando) = std::sync::mpsc::channel();
+        let (liberar, liberado) = std::sync::mpsc::channel::<()>();
+
+        let em_andamento = Arc::clone(&biblioteca);
+        let chamada = std::thread::spawn(move || {
+            let _chamada = em_andamento.chamada().unwrap();
+            iniciou.send(()).unwrap();
+            liberado.recv().unwrap();
+            std::time::Instant::now()
+        });
+
+        aguardando.recv().unwrap();
+
+        let recarregar = Arc::clone(&biblioteca);
+        let reload = std::thread::spawn(move || {
+            recarregar.aguardar_chamadas();
+            std::time::Instant::now()
+        });
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!reload.is_finished());
+
+        liberar.send(()).unwrap();
+
+        let fim_chamada = chamada.join().unwrap();
+        let fim_reload = reload.join().unwrap();
+        assert!(fim_reload >= fim_chamada);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn falha_ao_carregar_traz_o_motivo_do_sistema() {
+        let arquivo = std::env::temp_dir().join(format!(
+            "acbr_dynamic_library_{}_invalida.so",
+            std::process::id()
+        ));
+        std::fs::write(&arquivo, b"nao e uma biblioteca").unwrap();
+        let nome = caminho_para_cstring(&arquivo).unwrap();
+
+        let erro = unsafe { load_library(nome.as_ptr()) }.unwrap_err();
+        let _ = std::fs::remove_file(&arquivo);
+
+        // O texto do `dlerror` cita o arquivo e o motivo
+        assert!(erro.contains(arquivo.to_str().unwrap()), "{}", erro);
+
+        let inexistente = caminho_para_cstring(&arquivo.with_extension("nao-existe")).unwrap();
+        let erro = unsafe { load_library(inexistente.as_ptr()) }.unwrap_err();
+        assert!(
+            erro.starts_with("A biblioteca não foi encontrada"),
+            "{}",
+            erro
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dependencias_que_nao_carregam_sao_devolvidas() {
+        let diretorio =
+            std::env::temp_dir().join(format!("acbr_dependencias_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&diretorio);
+        std::fs::create_dir_all(&diretorio).unwrap();
+        std::fs::write(diretorio.join("libinvalida.so.1"), b"nao e uma biblioteca").unwrap();
+        std::fs::write(diretorio.join("leia-me.txt"), b"").unwrap();
+
+        let (carregadas, ignoradas) = carregar_dependencias(&diretorio).unwrap();
+        let _ = std::fs::remove_dir_all(&diretorio);
+
+        assert!(carregadas.is_empty());
+        assert_eq!(
+            ignoradas,
+            [diretorio.join("libinvalida.so.1").display().to_string()]
+        );
+    }
+
+    fn biblioteca_de_teste() -> Result<Biblioteca, ACBrError> {
+        Ok(Biblioteca::de_teste(FuncoesLib::Esocial(
+            crate::acbr_lib_esocial::tests::funcoes_de_teste(),
+        )))
+    }
+
+    /// Dispara `threads` chamadas simultâneas a `obter`, todas liberadas ao mesmo tempo.
+    fn obter_em_paralelo<F>(
+        carregamento: &Arc<Carregamento>,
+        threads: usize,
+        carregar: F,
+    ) -> Vec<Result<Arc<Biblioteca>, ACBrError>>
+    where
+        F: Fn() -> Result<Biblioteca, ACBrError> + Send + Sync + 'static,
+    {
+        let carregar = Arc::new(carregar);
+        let largada = Arc::new(std::sync::Barrier::new(threads));
+
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let carregamento = Arc::clone(carregamento);
+                let carregar = Arc::clone(&carregar);
+                let largada = Arc::clone(&largada);
+
+                std::thread::spawn(move || {
+                    largada.wait();
+                    carregamento
+                        .obter(false, || carregar())
+                        .map(|(biblioteca, _)| biblioteca)
+                })
+            })
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    }
+
+    #[test]
+    fn carregamento_concorrente_carrega_uma_unica_vez() {
+        let carregamento = Arc::new(Carregamento::default());
+        let chamadas = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let contador = Arc::clone(&chamadas);
+        let resultados = obter_em_paralelo(&carregamento, 2000, move || {
+            contador.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            biblioteca_de_teste()
+        });
+
+        assert_eq!(chamadas.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let primeira = resultados[0].as_ref().unwrap();
+        for resultado in &resultados {
+            assert!(Arc::ptr_eq(primeira, resultado.as_ref().unwrap()));
+        }
+    }
+
+    #[test]
+    fn falha_no_carregamento_e_repassada_e_pode_ser_tentada_novamente() {
+        let carregamento = Arc::new(Carregamento::default());
+        let chamadas = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let contador = Arc::clone(&chamadas);
+        let resultados = obter_em_paralelo(&carregamento, 2000, move || {
+            contador.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            Err(ACBrError::CarregarBiblioteca(
+                "Erro ao carregar a DLL.".into(),
+            ))
+        });
+
+        // Quem aguardava a carga recebe o mesmo erro em vez de tentar de novo. Apenas
+        // quem chegou depois da falha faz uma nova tentativa.
+        let falhas = resultados.iter().filter(|r| r.is_err()).count();
+        assert_eq!(falhas, 2000);
+        assert!(chamadas.load(std::sync::atomic::Ordering::SeqCst) < 2000);
+
+        let (biblioteca, _) = carregamento.obter(false, biblioteca_de_teste).unwrap();
+        let (mesma, _) = carregamento
+            .obter(false, || panic!("não deveria carregar de novo"))
+            .unwrap();
+        assert!(Arc::ptr_eq(&biblioteca, &mesma));
+    }
+
+    #[test]
+    fn recarregar_devolve_a_biblioteca_anterior() {
+        let carregamento = Carregamento::default();
+
+        let (primeira, _) = carregamento.obter(false, biblioteca_de_teste).unwrap();
+        let (segunda, anterior) = carregamento.obter(true, biblioteca_de_teste).unwrap();
+
+        assert!(!Arc::ptr_eq(&primeira, &segunda));
+        assert!(Arc::ptr_eq(&primeira, &anterior.unwrap()));
+    }
+
+    #[test]
+    fn bloquear_mutex_envenenado_retorna_erro() {
+        let mutex = Arc::new(Mutex::new(0));
+
+        let envenenar = Arc::clone(&mutex);
+        let _ = std::thread::spawn(move || {
+            let _guard = envenenar.lock();
+            panic!("envenenando o mutex");
+        })
+        .join();
+
+        assert!(mutex.is_poisoned());
+        assert!(matches!(
+            bloquear(&mutex),
+            Err(ACBrError::CarregarBiblioteca(_))
+        ));
+    }
+
+    #[test]
+    fn caminho_com_byte_nulo_retorna_erro() {
+        let caminho = Path::new("resources/ACBr\0eSocial.dll");
+
+        assert!(matches!(
+            caminho_para_cstring(caminho),
+            Err(ACBrError::CarregarBiblioteca(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn caminho_nao_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let caminho = Path::new(OsStr::from_bytes(b"resources/lib\xff\xfe.so"));
+
+        // O dlopen aceita bytes, então o caminho é repassado sem alterações
+        let cstring = caminho_para_cstring(caminho).unwrap();
+        assert_eq!(cstring.as_bytes(), b"resources/lib\xff\xfe.so");
+
+        assert!(matches!(
+            caminho_para_texto(caminho),
+            Err(ACBrError::CarregarBiblioteca(_))
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+/// Conteúdo de um arquivo INI da ACBrLib, na ordem em que aparece no texto.
+///
+/// Como no `TIniFile` usado pela biblioteca, nomes de sessões e chaves não diferenciam
+/// maiúsculas de minúsculas e a última ocorrência de uma chave repetida vale.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ini {
+    secoes: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Ini {
+    pub fn ler(texto: &str) -> Self {
+        let mut ini = Ini::default();
+        let mut secao = String::new();
+
+        for linha in texto.lines().map(str::trim) {
+            if linha.is_empty() || linha.starts_with(';') {
+                continue;
+            }
+
+            if let Some(nome) = linha.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                secao = nome.trim().to_string();
+                ini.secao_mut(&secao);
+            } else if let Some((chave, valor)) = linha.split_once('=') {
+                ini.gravar(&secao, chave.trim(), valor.trim());
+            }
+        }
+
+        ini
+    }
+
+    pub fn valor(&self, secao: &str, chave: &str) -> Option<&str> {
+        self.chaves(secao)?
+            .iter()
+            .find(|(nome, _)| nome.eq_ignore_ascii_case(chave))
+            .map(|(_, valor)| valor.as_str())
+    }
+
+    /// Chaves e valores da sessão, se ela existir.
+    pub fn chaves(&self, secao: &str) -> Option<&[(String, String)]> {
+        self.secoes
+            .iter()
+            .find(|(nome, _)| nome.eq_ignore_ascii_case(secao))
+            .map(|(_, chaves)| chaves.as_slice())
+    }
+
+    /// Nomes das sessões, na ordem do texto.
+    pub fn secoes(&self) -> impl Iterator<Item = &str> {
+        self.secoes.iter().map(|(nome, _)| nome.as_str())
+    }
+
+    pub fn gravar(&mut self, secao: &str, chave: &str, valor: impl Into<String>) {
+        let chaves = self.secao_mut(secao);
+
+        match chaves
+            .iter_mut()
+            .find(|(nome, _)| nome.eq_ignore_ascii_case(chave))
+        {
+            Some((_, atual)) => *atual = valor.into(),
+            None => chaves.push((chave.to_string(), valor.into())),
+        }
+    }
+
+    fn secao_mut(&mut self, secao: &str) -> &mut Vec<(String, String)> {
+        let posicao = match self
+            .secoes
+            .iter()
+            .position(|(nome, _)| nome.eq_ignore_ascii_case(secao))
+        {
+            Some(posicao) => posicao,
+            None => {
+                self.secoes.push((secao.to_string(), Vec::new()));
+                self.secoes.len() - 1
+            }
+        };
+
+        &mut self.secoes[posicao].1
+    }
+}
+
+impl std::fmt::Display for Ini {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (secao, chaves)) in self.secoes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "[{}]", secao)?;
+            for (chave, valor) in chaves {
+                writeln!(f, "{}={}", chave, valor)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn le_sessoes_e_chaves_sem_diferenciar_maiusculas() {
+        let ini = Ini::ler(
+            "[Principal]\r\nLogNivel=4\r\n\r\n[eSocial]\r\nFormatoAlerta=TAG:%TAG% = %MSG%\r\n",
+        );
+
+        assert_eq!(ini.valor("principal", "lognivel"), Some("4"));
+        assert_eq!(
+            ini.valor("ESOCIAL", "FormatoAlerta"),
+            Some("TAG:%TAG% = %MSG%")
+        );
+        assert_eq!(ini.valor("eSocial", "LogNivel"), None);
+        assert_eq!(ini.secoes().collect::<Vec<_>>(), ["Principal", "eSocial"]);
+    }
+
+    #[test]
+    fn texto_gerado_e_lido_de_volta() {
+        let mut ini = Ini::default();
+        ini.gravar("DFe", "UF", "SP");
+        ini.gravar("eSocial", "Ambiente", "1");
+        ini.gravar("dfe", "uf", "RJ");
+
+        assert_eq!(ini.to_string(), "[DFe]\nUF=RJ\n\n[eSocial]\nAmbiente=1\n");
+        assert_eq!(Ini::ler(&ini.to_string()), ini);
+    }
+}
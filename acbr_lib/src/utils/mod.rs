@@ -1 +1,4 @@
-pub mod dynamic_library;
\ No newline at end of file
+pub mod dynamic_library;
+pub mod ini;
+pub mod vigia;
+pub mod windows1252;
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::ACBrError;
+
+type AoExpirar = Box<dyn FnOnce() + Send>;
+
+/// Chamadas vigiadas, ordenadas pelo prazo e pelo número da chamada.
+struct Chamadas {
+    proxima: u64,
+    pendentes: BTreeMap<(Instant, u64), AoExpirar>,
+}
+
+/// Thread única que acompanha o prazo de todas as chamadas vigiadas e executa a ação de
+/// expiração das que passarem do prazo, sem depender de nenhum runtime.
+struct Vigia {
+    chamadas: Mutex<Chamadas>,
+    mudou: Condvar,
+}
+
+static VIGIA: Vigia = Vigia {
+    chamadas: Mutex::new(Chamadas {
+        proxima: 0,
+        pendentes: BTreeMap::new(),
+    }),
+    mudou: Condvar::new(),
+};
+
+/// Indica se a thread de vigia foi iniciada, na primeira chamada com prazo.
+static INICIADA: OnceLock<bool> = OnceLock::new();
+
+impl Vigia {
+    fn bloquear(&self) -> MutexGuard<'_, Chamadas> {
+        self.chamadas
+            .lock()
+            .unwrap_or_else(|erro| erro.into_inner())
+    }
+
+    fn vigiar(&self) {
+        let mut chamadas = self.bloquear();
+
+        loop {
+            let agora = Instant::now();
+
+            let expiradas: Vec<AoExpirar> = {
+                let restantes = chamadas.pendentes.split_off(&(agora, u64::MAX));
+                std::mem::replace(&mut chamadas.pendentes, restantes)
+                    .into_values()
+                    .collect()
+            };
+
+            if !expiradas.is_empty() {
+                drop(chamadas);
+                for ao_expirar in expiradas {
+                    ao_expirar();
+                }
+                chamadas = self.bloquear();
+                continue;
+            }
+
+            chamadas = match chamadas.pendentes.keys().next() {
+                Some(&(prazo, _)) => {
+                    let espera = prazo.saturating_duration_since(agora);
+                    self.mudou
+                        .wait_timeout(chamadas, espera)
+                        .map(|(chamadas, _)| chamadas)
+                        .unwrap_or_else(|erro| erro.into_inner().0)
+                }
+                None => self
+                    .mudou
+                    .wait(chamadas)
+                    .unwrap_or_else(|erro| erro.into_inner()),
+            };
+        }
+    }
+}
+
+/// Chamada em andamento sob vigia. Ao ser liberada antes do prazo, a ação de expiração
+/// é descartada sem ser executada.
+pub(crate) struct Vigilancia {
+    chave: (Instant, u64),
+}
+
+impl Drop for Vigilancia {
+    fn drop(&mut self) {
+        VIGIA.bloquear().pendentes.remove(&self.chave);
+    }
+}
+
+/// Executa `ao_expirar` na thread de vigia se a `Vigilancia` devolvida não for liberada
+/// dentro de `prazo`. Retorna `ACBrError::SessaoEncerrada` se a thread de vigia não
+/// puder ser iniciada.
+pub(crate) fn vigiar<F>(prazo: Duration, ao_expirar: F) -> Result<Vigilancia, ACBrError>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let iniciada = INICIADA.get_or_init(|| {
+        thread::Builder::new()
+            .name("acbr-vigia".into())
+            .spawn(|| VIGIA.vigiar())
+            .is_ok()
+    });
+    if !iniciada {
+        return Err(ACBrError::SessaoEncerrada);
+    }
+
+    let mut chamadas = VIGIA.bloquear();
+
+    let chave = (Instant::now() + prazo, chamadas.proxima);
+    chamadas.proxima += 1;
+    chamadas.pendentes.insert(chave, Box::new(ao_expirar));
+    drop(chamadas);
+
+    VIGIA.mudou.notify_one();
+
+    Ok(Vigilancia { chave })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn expira_apenas_as_chamadas_que_passam_do_prazo() {
+        let (expirou, expiradas) = mpsc::channel();
+
+        let lenta = {
+            let expirou = expirou.clone();
+            vigiar(Duration::from_millis(30), move || {
+                expirou.send("lenta").unwrap()
+            })
+            .unwrap()
+        };
+        let rapida = vigiar(Duration::from_millis(60), move || {
+            expirou.send("rapida").unwrap()
+        })
+        .unwrap();
+
+        // A rápida termina antes do prazo, a lenta não
+        drop(rapida);
+
+        assert_eq!(
+            expiradas.recv_timeout(Duration::from_secs(1)).unwrap(),
+            "lenta"
+        );
+        drop(lenta);
+
+        assert!(expiradas.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Conversão entre texto e Windows-1252, a codificação "ANSI" da ACBrLib
+//! (`[Principal] CodificacaoResposta=1`).
+
+/// Caracteres dos bytes 0x80 a 0x9F, a única faixa que difere do Latin-1. Os bytes sem
+/// caractere definido viram o controle C1 de mesmo código, como no Windows, para que a
+/// conversão de volta devolva os mesmos bytes.
+const FAIXA_80_9F: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+pub fn decodificar(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => FAIXA_80_9F[(byte - 0x80) as usize],
+            byte => byte as char,
+        })
+        .collect()
+}
+
+/// Falha com o primeiro caractere que não existe em Windows-1252.
+pub fn codificar(texto: &str) -> Result<Vec<u8>, char> {
+    texto
+        .chars()
+        .map(|caractere| match caractere as u32 {
+            codigo @ (0..=0x7F | 0xA0..=0xFF) => Ok(codigo as u8),
+            _ => FAIXA_80_9F
+                .iter()
+                .position(|&c| c == caractere)
+                .map(|posicao| 0x80 + posicao as u8)
+                .ok_or(caractere),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acentos_e_simbolos_usam_um_byte() {
+        let texto = "Conceição São João – R$ 1.000,00 €";
+        let bytes = codificar(texto).unwrap();
+
+        assert_eq!(bytes.len(), texto.chars().count());
+        assert_eq!(&bytes[6..8], [0xE7, 0xE3]);
+        assert_eq!(bytes[bytes.len() - 1], 0x80);
+        assert_eq!(decodificar(&bytes), texto);
+    }
+
+    #[test]
+    fn todos_os_bytes_voltam_iguais() {
+        let bytes: Vec<u8> = (0..=255).collect();
+
+        assert_eq!(codificar(&decodificar(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn caractere_fora_da_codificacao_retorna_erro() {
+        assert_eq!(codificar("Ok ✓"), Err('✓'));
+    }
+}
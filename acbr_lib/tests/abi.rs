@@ -0,0 +1,86 @@
+mod common;
+
+use acbr_lib::error::ACBrError;
+
+#[test]
+fn funcoes_sem_argumentos_devolvem_resposta() {
+    let lib = common::sessao();
+
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+    assert_eq!(lib.esocial_versao().unwrap(), "0.0.0-stub");
+}
+
+#[test]
+fn argumentos_de_texto_e_inteiro_chegam_na_ordem() {
+    let lib = common::sessao();
+
+    lib.set_tipo_empregador(2).unwrap();
+    lib.set_id_empregador("12345678").unwrap();
+
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "TipoEmpregador")
+            .unwrap(),
+        "2"
+    );
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "IdEmpregador")
+            .unwrap(),
+        "12345678"
+    );
+
+    let resposta = lib
+        .consulta_identificadores_eventos_tabela(
+            "12345678",
+            -7,
+            "chave",
+            "01/01/2025",
+            "31/01/2025",
+        )
+        .unwrap();
+    assert_eq!(
+        resposta,
+        "[ConsultaIdentificadoresEventosTabela]\nIdEmpregador=12345678\nTipoEvento=-7\nChave=chave\nDataInicial=01/01/2025\nDataFinal=31/01/2025\n"
+    );
+}
+
+#[test]
+fn codigo_de_erro_traz_o_ultimo_retorno() {
+    let lib = common::sessao();
+
+    assert_eq!(
+        lib.esocial_config_ler_valor("Inexistente", "Chave"),
+        Err(ACBrError::SessaoNaoEncontrada(
+            "[Inexistente] Chave não encontrada".into()
+        ))
+    );
+}
+
+#[test]
+fn resposta_maior_que_o_buffer_e_lida_por_completo() {
+    let lib = common::sessao();
+    let valor = "x".repeat(40 * 1024);
+
+    lib.esocial_config_gravar_valor("Stub", "Grande", &valor)
+        .unwrap();
+
+    assert_eq!(
+        lib.esocial_config_ler_valor("Stub", "Grande").unwrap(),
+        valor
+    );
+}
+
+#[test]
+fn resposta_do_tamanho_do_buffer_e_lida_por_completo() {
+    let lib = common::sessao();
+
+    for tamanho in [16 * 1024 - 1, 16 * 1024, 16 * 1024 + 1] {
+        let valor = "x".repeat(tamanho);
+        lib.esocial_config_gravar_valor("Stub", "Limite", &valor)
+            .unwrap();
+
+        assert_eq!(
+            lib.esocial_config_ler_valor("Stub", "Limite").unwrap(),
+            valor
+        );
+    }
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use acbr_lib::acbr_lib_esocial::SessaoAssincrona;
+use acbr_lib::error::ACBrError;
+
+async fn sessao() -> SessaoAssincrona {
+    common::usar_stub();
+    SessaoAssincrona::com_config_temporaria("").await.unwrap()
+}
+
+#[tokio::test]
+async fn metodos_assincronos_equivalem_aos_da_sessao() {
+    let lib = sessao().await;
+
+    assert_eq!(lib.esocial_nome().await.unwrap(), "ACBrLibeSocial");
+
+    lib.set_tipo_empregador(1).await.unwrap();
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "TipoEmpregador")
+            .await
+            .unwrap(),
+        "1"
+    );
+
+    assert_eq!(
+        lib.enviar_esocial(1).await,
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+}
+
+/// Em um runtime de uma única thread, a chamada demorada não impede as outras tarefas.
+#[tokio::test(flavor = "current_thread")]
+async fn chamada_demorada_nao_bloqueia_o_runtime() {
+    let lib = sessao().await;
+    lib.esocial_config_gravar_valor("Stub", "AtrasoVersao", "300")
+        .await
+        .unwrap();
+
+    let inicio = Instant::now();
+    let versao = tokio::spawn(async move { lib.esocial_versao().await });
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let tempo_do_timer = inicio.elapsed();
+
+    assert_eq!(versao.await.unwrap().unwrap(), "0.0.0-stub");
+    assert!(tempo_do_timer < Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn cancelar_o_future_nao_interrompe_a_chamada() {
+    let lib = sessao().await;
+    lib.esocial_config_gravar_valor("Stub", "AtrasoConfigGravarValor2", "200")
+        .await
+        .unwrap();
+
+    let cancelada = tokio::time::timeout(
+        Duration::from_millis(20),
+        lib.esocial_config_gravar_valor("eSocial", "IdEmpregador", "12345678"),
+    )
+    .await;
+    assert!(cancelada.is_err());
+
+    // A próxima chamada só roda depois que a cancelada terminou na thread da sessão
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "IdEmpregador")
+            .await
+            .unwrap(),
+        "12345678"
+    );
+}
+
+#[tokio::test]
+async fn sessao_encerrada_retorna_erro() {
+    let lib = sessao().await;
+
+    let resultado: Result<(), _> = lib
+        .executar(|_| -> Result<(), ACBrError> { panic!("falha na thread da sessão") })
+        .await;
+    assert_eq!(resultado, Err(ACBrError::SessaoEncerrada));
+
+    assert_eq!(lib.esocial_nome().await, Err(ACBrError::SessaoEncerrada));
+}
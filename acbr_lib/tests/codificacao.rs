@@ -0,0 +1,136 @@
+mod common;
+
+use acbr_lib::acbr_lib_esocial::config::{CodificacaoResposta, ConfigESocial};
+use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+use acbr_lib::error::ACBrError;
+
+fn sessao_ansi() -> ACBrLibEsocial {
+    common::usar_stub();
+
+    let mut config = ConfigESocial::default();
+    config.principal.codificacao_resposta = Some(CodificacaoResposta::Ansi);
+
+    ACBrLibEsocial::com_config(&config, "").unwrap()
+}
+
+#[test]
+fn sessao_le_a_codificacao_da_configuracao() {
+    assert_eq!(common::sessao().codificacao(), CodificacaoResposta::Utf8);
+    assert_eq!(sessao_ansi().codificacao(), CodificacaoResposta::Ansi);
+}
+
+#[test]
+fn gravar_a_codificacao_muda_a_da_sessao() {
+    let lib = common::sessao();
+
+    lib.esocial_config_gravar_valor("Principal", "CodificacaoResposta", "1")
+        .unwrap();
+    assert_eq!(lib.codificacao(), CodificacaoResposta::Ansi);
+
+    lib.esocial_config_gravar_valor("Principal", "CodificacaoResposta", "0")
+        .unwrap();
+    assert_eq!(lib.codificacao(), CodificacaoResposta::Utf8);
+}
+
+#[test]
+fn falha_ao_ler_a_codificacao_e_devolvida() {
+    let lib = common::sessao();
+
+    lib.esocial_config_gravar_valor("Stub", "ErroConfigLerValor", "-10")
+        .unwrap();
+
+    assert!(matches!(
+        lib.esocial_config_gravar_valor("Principal", "CodificacaoResposta", "1"),
+        Err(ACBrError::Execucao(_))
+    ));
+}
+
+#[test]
+fn config_lida_de_arquivo_muda_a_codificacao() {
+    let lib = common::sessao();
+
+    let arquivo = common::arquivo_temporario("config_ansi.ini");
+    std::fs::write(&arquivo, "[Principal]\nCodificacaoResposta=1\n").unwrap();
+    let resultado = lib.esocial_config_ler(arquivo.to_str().unwrap());
+    let _ = std::fs::remove_file(&arquivo);
+
+    resultado.unwrap();
+    assert_eq!(lib.codificacao(), CodificacaoResposta::Ansi);
+}
+
+#[test]
+fn acentos_vao_e_voltam_nas_duas_codificacoes() {
+    for lib in [common::sessao(), sessao_ansi()] {
+        lib.set_id_empregador("Associação São João").unwrap();
+        lib.esocial_config_gravar_valor("eSocial", "VersaoDF", "Versão – teste €")
+            .unwrap();
+
+        assert_eq!(
+            lib.esocial_config_ler_valor("eSocial", "IdEmpregador")
+                .unwrap(),
+            "Associação São João"
+        );
+        assert_eq!(
+            lib.esocial_config_ler_valor("eSocial", "VersaoDF").unwrap(),
+            "Versão – teste €"
+        );
+    }
+}
+
+#[test]
+fn biblioteca_recebe_os_textos_em_windows_1252() {
+    let lib = sessao_ansi();
+
+    lib.set_id_empregador("Conceição").unwrap();
+
+    // O stub decodifica em Windows-1252 e grava o arquivo em UTF-8: os acentos só
+    // chegam intactos se o wrapper enviou um byte por caractere
+    let arquivo = common::arquivo_temporario("config_gravada_ansi.ini");
+    lib.esocial_config_gravar(arquivo.to_str().unwrap())
+        .unwrap();
+    let gravado = std::fs::read_to_string(&arquivo);
+    let _ = std::fs::remove_file(&arquivo);
+
+    assert!(gravado.unwrap().contains("IdEmpregador=Conceição\n"));
+}
+
+#[test]
+fn resposta_com_acentos_e_lida_na_codificacao_da_sessao() {
+    let lib = sessao_ansi();
+
+    lib.carregar_xml_evento("<eSocial><nmTrab>José Conceição</nmTrab></eSocial>")
+        .unwrap();
+    lib.esocial_config_gravar_valor(
+        "Stub",
+        "RespostaConsultareSocial",
+        "[Consulta]\\nCodigo=101\\nMensagem=Lote ainda não processado; aguarde a conclusão.",
+    )
+    .unwrap();
+
+    let consulta = lib
+        .consultar_lote("1.1.202501.0000000000000000001")
+        .unwrap();
+
+    assert!(consulta.em_processamento());
+    assert_eq!(
+        consulta.desc_resposta,
+        "Lote ainda não processado; aguarde a conclusão."
+    );
+}
+
+#[test]
+fn caractere_fora_de_windows_1252_retorna_erro() {
+    let lib = sessao_ansi();
+
+    assert_eq!(
+        lib.carregar_xml_evento("<eSocial><nmTrab>Nguyễn</nmTrab></eSocial>"),
+        Err(ACBrError::CaractereInvalido {
+            parametro: "arquivo_ou_xml",
+            caractere: 'ễ',
+        })
+    );
+
+    common::sessao()
+        .carregar_xml_evento("<eSocial><nmTrab>Nguyễn</nmTrab></eSocial>")
+        .unwrap();
+}
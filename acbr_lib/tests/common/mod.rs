@@ -0,0 +1,58 @@
+// Cada arquivo de teste usa apenas parte destes utilitários
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+use acbr_lib::localizador::LocalizadorBiblioteca;
+
+/// Compila o `acbr_lib_stub` para o host e retorna o caminho da biblioteca gerada.
+///
+/// Usa um diretório de target próprio para não disputar o lock com o `cargo test` que
+/// está rodando os testes.
+pub fn biblioteca_stub() -> PathBuf {
+    static CAMINHO: OnceLock<PathBuf> = OnceLock::new();
+
+    CAMINHO
+        .get_or_init(|| {
+            let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+            let target = raiz.join("target").join("stub");
+
+            let status = Command::new(env!("CARGO"))
+                .current_dir(&raiz)
+                .args(["build", "-p", "acbr_lib_stub", "--target-dir"])
+                .arg(&target)
+                .status()
+                .expect("não foi possivel executar o cargo");
+            assert!(status.success(), "falha ao compilar o acbr_lib_stub");
+
+            target.join("debug").join(format!(
+                "{}acbr_lib_stub{}",
+                std::env::consts::DLL_PREFIX,
+                std::env::consts::DLL_SUFFIX
+            ))
+        })
+        .clone()
+}
+
+/// Aponta o `ACBrLibEsocial` para o stub.
+pub fn usar_stub() {
+    ACBrLibEsocial::configurar_biblioteca(
+        LocalizadorBiblioteca::esocial().com_caminho(biblioteca_stub()),
+    )
+    .unwrap();
+}
+
+/// Cria uma sessão do stub com configuração temporária.
+pub fn sessao() -> ACBrLibEsocial {
+    usar_stub();
+
+    ACBrLibEsocial::com_config_temporaria("").unwrap()
+}
+
+/// Caminho exclusivo no diretório temporário para arquivos criados pelos testes.
+pub fn arquivo_temporario(nome: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("acbr_teste_{}_{}", std::process::id(), nome))
+}
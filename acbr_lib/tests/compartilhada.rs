@@ -0,0 +1,51 @@
+mod common;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use acbr_lib::acbr_lib_esocial::SessaoCompartilhada;
+
+#[test]
+fn chamadas_na_mesma_sessao_sao_serializadas() {
+    let sessao = SessaoCompartilhada::new(common::sessao());
+
+    sessao
+        .executar(|lib| lib.esocial_config_gravar_valor("Stub", "AtrasoNome", "20"))
+        .unwrap();
+
+    let inicio = Instant::now();
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let sessao = sessao.clone();
+            thread::spawn(move || {
+                (0..3)
+                    .map(|_| sessao.executar(|lib| lib.esocial_nome()))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        for resultado in thread.join().unwrap() {
+            // O stub recusa uma chamada que chega enquanto outra está em andamento
+            assert_eq!(resultado.unwrap(), "ACBrLibeSocial");
+        }
+    }
+
+    // 24 chamadas de 20 ms, uma de cada vez
+    assert!(inicio.elapsed() >= Duration::from_millis(24 * 20));
+}
+
+#[test]
+fn sessao_continua_disponivel_apos_panico() {
+    let sessao = SessaoCompartilhada::new(common::sessao());
+
+    let com_panico = sessao.clone();
+    let _ = thread::spawn(move || com_panico.executar(|_| panic!("falha no processamento"))).join();
+
+    assert_eq!(
+        sessao.executar(|lib| lib.esocial_versao()).unwrap(),
+        "0.0.0-stub"
+    );
+}
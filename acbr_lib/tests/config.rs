@@ -0,0 +1,120 @@
+mod common;
+
+use std::path::PathBuf;
+
+use acbr_lib::acbr_lib_esocial::config::{Ambiente, ConfigESocial, LogNivel, VersaoDF};
+use acbr_lib::acbr_lib_esocial::{ACBrLibEsocial, ConfigPool, ESocialPool};
+
+#[test]
+fn config_tipada_e_gravada_na_sessao() {
+    let lib = common::sessao();
+
+    let mut config = ConfigESocial::default();
+    config.principal.log_nivel = Some(LogNivel::Completo);
+    config.esocial.ambiente = Some(Ambiente::Producao);
+    config.esocial.versao_df = Some(VersaoDF::S01_02_00);
+    config.esocial.path_schemas = Some(PathBuf::from("resources/temp/schemas"));
+    config.esocial.id_empregador = Some("12345678".into());
+    config.dfe.verificar_validade = Some(false);
+
+    lib.aplicar_config(&config).unwrap();
+
+    let valor = |secao, chave| lib.esocial_config_ler_valor(secao, chave).unwrap();
+    assert_eq!(valor("Principal", "LogNivel"), "3");
+    assert_eq!(valor("eSocial", "Ambiente"), "0");
+    assert_eq!(valor("eSocial", "VersaoDF"), "5");
+    assert_eq!(valor("eSocial", "PathSchemas"), "resources/temp/schemas");
+    assert_eq!(valor("eSocial", "IdEmpregador"), "12345678");
+    assert_eq!(valor("DFe", "VerificarValidade"), "0");
+}
+
+#[test]
+fn config_exportada_pela_sessao_e_lida_no_modelo() {
+    let lib = common::sessao();
+    lib.esocial_config_gravar_valor("eSocial", "Ambiente", "1")
+        .unwrap();
+    lib.esocial_config_gravar_valor("eSocial", "Timeout", "8000")
+        .unwrap();
+
+    let config = ConfigESocial::de_ini(&lib.esocial_config_exportar().unwrap()).unwrap();
+
+    assert_eq!(config.esocial.ambiente, Some(Ambiente::Homologacao));
+    assert_eq!(config.esocial.timeout, Some(8000));
+}
+
+#[test]
+fn config_em_memoria_nao_grava_a_senha_em_disco() {
+    common::usar_stub();
+
+    let mut config = ConfigESocial::default();
+    config.dfe.senha = Some("senha-do-certificado-123".into());
+    config.esocial.id_empregador = Some("12345678".into());
+
+    let lib = ACBrLibEsocial::com_config(&config, "").unwrap();
+    let diretorio = lib.arquivo_config().parent().unwrap().to_path_buf();
+
+    assert_eq!(
+        lib.esocial_config_ler_valor("DFe", "Senha").unwrap(),
+        "senha-do-certificado-123"
+    );
+
+    for arquivo in std::fs::read_dir(&diretorio).unwrap().flatten() {
+        let conteudo = std::fs::read_to_string(arquivo.path()).unwrap_or_default();
+        assert!(!conteudo.contains("senha-do-certificado-123"));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let permissoes = std::fs::metadata(&diretorio).unwrap().permissions();
+        assert_eq!(permissoes.mode() & 0o777, 0o700);
+    }
+
+    drop(lib);
+    assert!(!diretorio.exists());
+}
+
+#[test]
+fn pool_aplica_a_config_em_memoria_nas_sessoes() {
+    common::usar_stub();
+
+    let mut config = ConfigESocial::default();
+    config.esocial.ambiente = Some(Ambiente::Producao);
+    let pool = ESocialPool::new(ConfigPool::new(2).com_config(config));
+
+    let ambiente = pool
+        .executar(|lib| lib.esocial_config_ler_valor("eSocial", "Ambiente"))
+        .unwrap();
+
+    assert_eq!(ambiente, "0");
+}
+
+#[test]
+fn verificar_config_aponta_valores_sobrescritos_pela_biblioteca() {
+    let lib = common::sessao();
+
+    let mut esperada = ConfigESocial::default();
+    esperada.esocial.ambiente = Some(Ambiente::Producao);
+    esperada.esocial.path_schemas = Some(PathBuf::from("resources/temp/schemas"));
+
+    lib.aplicar_config(&esperada).unwrap();
+    assert!(lib.verificar_config(&esperada).unwrap().is_empty());
+
+    // Um arquivo lido depois volta a sessão para homologação
+    let arquivo = common::arquivo_temporario("config_homologacao.ini");
+    std::fs::write(&arquivo, "[eSocial]\nAmbiente=1\n").unwrap();
+    lib.esocial_config_ler(arquivo.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&arquivo);
+
+    let diferencas = lib.verificar_config(&esperada).unwrap();
+    assert_eq!(diferencas.len(), 1);
+    assert_eq!(
+        diferencas[0].to_string(),
+        "[eSocial] Ambiente: esperado \"0\", efetivo \"1\""
+    );
+    assert_eq!(
+        lib.config_efetiva().unwrap().esocial.ambiente,
+        Some(Ambiente::Homologacao)
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use acbr_lib::acbr_lib_esocial::config::{
+    Ambiente, CarregadorConfig, ConfigESocial, OrigemConfig, PerfilEmpregador,
+};
+use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+
+// Fica em um binário de teste próprio porque altera as variáveis de ambiente do processo,
+// que os outros testes rodando em paralelo poderiam ler.
+#[test]
+fn config_em_camadas_cria_a_sessao_com_a_origem_de_cada_valor() {
+    common::usar_stub();
+
+    let padrao = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/config.ini");
+    std::env::set_var("ACBRLIB_ESOCIAL_CONFIG__ESOCIAL__TENTATIVAS", "3");
+
+    let mut codigo = ConfigESocial::default();
+    codigo.esocial.ambiente = Some(Ambiente::Producao);
+
+    let config = CarregadorConfig::new()
+        .com_arquivo_padrao(padrao)
+        .unwrap()
+        .com_perfil(PerfilEmpregador::new("12345678").com_certificado_pfx("empresa.pfx", "senha"))
+        .com_variaveis_ambiente()
+        .com_valores(&codigo)
+        .carregar()
+        .unwrap();
+
+    assert_eq!(
+        config.origem("DFe", "ArquivoPFX"),
+        Some(&OrigemConfig::Perfil("12345678".into()))
+    );
+    assert_eq!(
+        config.origem("eSocial", "Tentativas").unwrap().to_string(),
+        "variável de ambiente ACBRLIB_ESOCIAL_CONFIG__ESOCIAL__TENTATIVAS"
+    );
+    assert_eq!(
+        config.origem("eSocial", "Ambiente"),
+        Some(&OrigemConfig::Codigo)
+    );
+    assert_eq!(
+        config.origem("eSocial", "VersaoDF"),
+        Some(&OrigemConfig::Padrao(padrao.into()))
+    );
+
+    let lib = ACBrLibEsocial::com_config(&config, "").unwrap();
+    let valor = |secao, chave| lib.esocial_config_ler_valor(secao, chave).unwrap();
+    assert_eq!(valor("eSocial", "IdEmpregador"), "12345678");
+    assert_eq!(valor("eSocial", "Tentativas"), "3");
+    assert_eq!(valor("eSocial", "Ambiente"), "0");
+    assert!(lib.verificar_config(&config).unwrap().is_empty());
+}
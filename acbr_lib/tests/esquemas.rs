@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+
+use acbr_lib::acbr_lib_esocial::esquemas::{ErroEsquema, Esquema, ValidadorEsquemas};
+use acbr_lib::error::ACBrError;
+
+const S1000: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<eSocial xmlns="http://www.esocial.gov.br/schema/evt/evtInfoEmpregador/v_S_01_03_00">
+  <evtInfoEmpregador Id="ID1123456780000002024010100000000001">
+    <ideEvento>
+      <tpAmb>2</tpAmb>
+      <procEmi>1</procEmi>
+      <verProc>1.0</verProc>
+    </ideEvento>
+    <ideEmpregador>
+      <tpInsc>1</tpInsc>
+      <nrInsc>12345678</nrInsc>
+    </ideEmpregador>
+    <infoEmpregador>
+      <exclusao>
+        <idePeriodo>
+          <iniValid>2024-01</iniValid>
+        </idePeriodo>
+      </exclusao>
+    </infoEmpregador>
+  </evtInfoEmpregador>
+  <Signature xmlns="http://www.w3.org/2000/09/xmldsig#">
+    <SignedInfo>
+      <CanonicalizationMethod Algorithm="http://www.w3.org/TR/2001/REC-xml-c14n-20010315"/>
+      <SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"/>
+      <Reference URI="#ID1123456780000002024010100000000001">
+        <DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"/>
+        <DigestValue>AAAA</DigestValue>
+      </Reference>
+    </SignedInfo>
+    <SignatureValue>AAAA</SignatureValue>
+  </Signature>
+</eSocial>"##;
+
+fn schemas() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../resources/temp/schemas")
+}
+
+fn esquema_s1000() -> Esquema {
+    Esquema::carregar(schemas().join("evtInfoEmpregador-v_S_01_03_00.xsd")).unwrap()
+}
+
+#[test]
+fn evento_valido_nao_tem_erros() {
+    assert_eq!(esquema_s1000().validar(S1000), []);
+}
+
+#[test]
+fn valor_invalido_informa_linha_e_coluna() {
+    let xml = S1000.replace("<nrInsc>12345678</nrInsc>", "<nrInsc>1234</nrInsc>");
+
+    let erros = esquema_s1000().validar(&xml);
+
+    assert_eq!(erros.len(), 1, "{:?}", erros);
+    assert_eq!((erros[0].linha, erros[0].coluna), (11, 7));
+    assert_eq!(
+        erros[0].caminho,
+        "/eSocial/evtInfoEmpregador/ideEmpregador/nrInsc"
+    );
+    assert!(erros[0].mensagem.contains("padrão"), "{}", erros[0]);
+}
+
+#[test]
+fn atributo_invalido_e_posicionado_no_atributo() {
+    let xml = S1000.replace("Id=\"ID1123456780000002024010100000000001\"", "Id=\"ID1\"");
+
+    let erros = esquema_s1000().validar(&xml);
+
+    assert_eq!(erros.len(), 1, "{:?}", erros);
+    assert_eq!((erros[0].linha, erros[0].coluna), (3, 22));
+    assert_eq!(erros[0].caminho, "/eSocial/evtInfoEmpregador/@Id");
+}
+
+#[test]
+fn elemento_fora_de_ordem_ou_faltando() {
+    let esquema = esquema_s1000();
+
+    let sem_verproc = S1000.replace("<verProc>1.0</verProc>", "");
+    assert_eq!(
+        esquema.validar(&sem_verproc),
+        [ErroEsquema {
+            linha: 4,
+            coluna: 5,
+            caminho: "/eSocial/evtInfoEmpregador/ideEvento".into(),
+            mensagem: "o elemento 'ideEvento' está incompleto; esperado: verProc".into(),
+        }]
+    );
+
+    let invertido = S1000.replace(
+        "<tpAmb>2</tpAmb>\n      <procEmi>1</procEmi>",
+        "<procEmi>1</procEmi>\n      <tpAmb>2</tpAmb>",
+    );
+    let erros = esquema.validar(&invertido);
+    assert_eq!(erros[0].linha, 5);
+    assert_eq!(
+        erros[0].mensagem,
+        "o elemento 'procEmi' não era esperado; esperado: tpAmb"
+    );
+}
+
+#[test]
+fn erros_dos_filhos_continuam_sendo_informados() {
+    let xml = S1000
+        .replace("<tpAmb>2</tpAmb>", "<tpAmb>5</tpAmb><extra/>")
+        .replace(
+            "<iniValid>2024-01</iniValid>",
+            "<iniValid>2024-13</iniValid>",
+        );
+
+    let caminhos: Vec<String> = esquema_s1000()
+        .validar(&xml)
+        .into_iter()
+        .map(|erro| erro.caminho)
+        .collect();
+
+    assert_eq!(
+        caminhos,
+        [
+            "/eSocial/evtInfoEmpregador/ideEvento/extra",
+            "/eSocial/evtInfoEmpregador/ideEvento/tpAmb",
+            "/eSocial/evtInfoEmpregador/infoEmpregador/exclusao/idePeriodo/iniValid",
+        ]
+    );
+}
+
+#[test]
+fn xml_mal_formado() {
+    let erros = esquema_s1000().validar("<eSocial>\n  <evtInfoEmpregador>\n</eSocial>");
+
+    assert_eq!(erros.len(), 1);
+    assert_eq!(erros[0].linha, 3);
+    assert!(erros[0].mensagem.starts_with("XML mal formado"));
+}
+
+#[test]
+fn validador_escolhe_o_esquema_pelo_namespace() {
+    let validador = ValidadorEsquemas::new(schemas()).unwrap();
+
+    assert_eq!(validador.validar(S1000).unwrap(), []);
+    assert!(validador
+        .esquema("http://www.esocial.gov.br/schema/evt/evtInfoEmpregador/v_S_01_03_00")
+        .unwrap()
+        .is_some());
+
+    let desconhecido = S1000.replace("v_S_01_03_00", "v_S_09_00_00");
+    let erros = validador.validar(&desconhecido).unwrap();
+    assert_eq!(erros.len(), 1);
+    assert_eq!((erros[0].linha, erros[0].coluna), (2, 1));
+    assert!(erros[0].mensagem.starts_with("nenhum esquema"));
+}
+
+#[test]
+fn diretorio_inexistente() {
+    assert!(matches!(
+        ValidadorEsquemas::new(schemas().join("nao-existe")),
+        Err(ACBrError::DiretorioInvalido(_))
+    ));
+}
+
+/// Esquemas de leiautes antigos que usam tipos removidos do `tipos.xsd` distribuído, que é
+/// o do leiaute atual. `Esquema::carregar` recusa esses arquivos com
+/// `ACBrError::EsquemaInvalido`.
+const ESQUEMAS_SEM_TIPOS: [&str; 7] = [
+    // T_ideEvento_retorno_trab
+    "evtBasesTrab-v_S_01_00_00.xsd",
+    "evtBasesTrab-v_S_01_01_00.xsd",
+    "evtBasesTrab-v_S_01_02_00.xsd",
+    "evtBasesFGTS-v_S_01_00_00.xsd",
+    "evtBasesFGTS-v_S_01_01_00.xsd",
+    "evtBasesFGTS-v_S_01_02_00.xsd",
+    // TS_matricula_retorno
+    "evtFGTSProcTrab.xsd",
+];
+
+#[test]
+fn todos_os_esquemas_distribuidos_sao_lidos() {
+    let mut arquivos: Vec<PathBuf> = std::fs::read_dir(schemas())
+        .unwrap()
+        .map(|entrada| entrada.unwrap().path())
+        .filter(|caminho| {
+            caminho
+                .extension()
+                .is_some_and(|extensao| extensao == "xsd")
+        })
+        .collect();
+    arquivos.sort();
+    assert!(arquivos.len() > 300);
+
+    for arquivo in arquivos {
+        let nome = arquivo.file_name().unwrap().to_str().unwrap();
+        let resultado = Esquema::carregar(&arquivo);
+
+        if ESQUEMAS_SEM_TIPOS.contains(&nome) {
+            assert!(
+                matches!(resultado, Err(ACBrError::EsquemaInvalido(_))),
+                "{}",
+                nome
+            );
+        } else if let Err(erro) = resultado {
+            panic!("{}: {}", nome, erro);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+
+use acbr_lib::acbr_lib_esocial::{ConfigWorker, SessaoIsolada};
+use acbr_lib::error::ACBrError;
+
+fn config() -> ConfigWorker {
+    ConfigWorker::new()
+        .com_executavel(env!("CARGO_BIN_EXE_acbr_esocial_worker"))
+        .com_biblioteca(common::biblioteca_stub())
+}
+
+fn sessao() -> SessaoIsolada {
+    SessaoIsolada::new(config()).unwrap()
+}
+
+#[test]
+fn worker_responde_como_a_sessao() {
+    let lib = sessao();
+
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+
+    lib.set_tipo_empregador(1).unwrap();
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "TipoEmpregador")
+            .unwrap(),
+        "1"
+    );
+
+    // Respostas com várias linhas e erros da biblioteca atravessam o protocolo
+    let consulta = lib
+        .consultar_protocolo("1.1.202501.0000000000000000001")
+        .unwrap();
+    assert!(consulta.starts_with("[Consulta]\n"));
+    assert_eq!(
+        lib.enviar_esocial(1),
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+
+    lib.finalizar().unwrap();
+}
+
+#[test]
+fn texto_com_byte_nulo_e_recusado_antes_do_envio() {
+    let lib = sessao();
+
+    assert_eq!(
+        lib.set_id_empregador("123\0"),
+        Err(ACBrError::TextoInvalido("id_empregador"))
+    );
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+}
+
+#[test]
+fn queda_do_worker_falha_apenas_a_chamada_afetada() {
+    let lib = sessao();
+    lib.esocial_config_gravar_valor("Stub", "FalhaVersao", "1")
+        .unwrap();
+
+    assert!(matches!(lib.esocial_versao(), Err(ACBrError::Worker(_))));
+    assert_eq!(lib.reinicios(), 0);
+
+    // O novo worker começa com a configuração original, sem a falha injetada
+    assert_eq!(lib.esocial_versao().unwrap(), "0.0.0-stub");
+    assert_eq!(lib.reinicios(), 1);
+}
+
+#[test]
+fn queda_de_um_worker_nao_afeta_os_outros() {
+    let estavel = Arc::new(sessao());
+    let instavel = sessao();
+    instavel
+        .esocial_config_gravar_valor("Stub", "FalhaNome", "1")
+        .unwrap();
+
+    let chamadas = {
+        let estavel = Arc::clone(&estavel);
+        thread::spawn(move || (0..50).map(|_| estavel.esocial_nome()).collect::<Vec<_>>())
+    };
+
+    assert!(matches!(instavel.esocial_nome(), Err(ACBrError::Worker(_))));
+
+    for nome in chamadas.join().unwrap() {
+        assert_eq!(nome.unwrap(), "ACBrLibeSocial");
+    }
+    assert_eq!(estavel.reinicios(), 0);
+}
+
+#[test]
+fn erro_ao_criar_a_sessao_vem_do_worker() {
+    let config = config().com_modelo_config(common::arquivo_temporario("modelo_inexistente.ini"));
+
+    assert!(matches!(
+        SessaoIsolada::new(config),
+        Err(ACBrError::ArquivoNaoEncontrado(_))
+    ));
+}
+
+#[test]
+fn falha_na_inicializacao_chega_com_o_codigo_original() {
+    let modelo = common::arquivo_temporario("isolada_inicializar.ini");
+    std::fs::write(&modelo, "[Stub]\nErroInicializar=-6\n").unwrap();
+
+    let resultado = SessaoIsolada::new(config().com_modelo_config(&modelo)).map(|_| ());
+    let _ = std::fs::remove_file(&modelo);
+
+    let erro = resultado.unwrap_err();
+    assert!(
+        matches!(erro, ACBrError::DiretorioInvalido(_)),
+        "{:?}",
+        erro
+    );
+    assert_eq!(erro.codigo(), Some(-6));
+}
+
+#[test]
+fn executavel_inexistente_retorna_erro_do_worker() {
+    let config = config().com_executavel(common::arquivo_temporario("worker_inexistente"));
+
+    assert!(matches!(
+        SessaoIsolada::new(config),
+        Err(ACBrError::Worker(_))
+    ));
+}
+
+#[test]
+fn debug_da_configuracao_oculta_a_chave() {
+    let debug = format!("{:?}", config().com_chave_criptografia("segredo"));
+
+    assert!(!debug.contains("segredo"), "{}", debug);
+    assert!(debug.contains("chave_criptografia: \"***\""), "{}", debug);
+}
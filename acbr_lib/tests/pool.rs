@@ -0,0 +1,149 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use acbr_lib::acbr_lib_esocial::{ConfigPool, ESocialPool};
+use acbr_lib::error::ACBrError;
+
+fn pool(tamanho_maximo: usize) -> ESocialPool {
+    common::usar_stub();
+    ESocialPool::new(ConfigPool::new(tamanho_maximo))
+}
+
+#[test]
+fn sessoes_sao_criadas_sob_demanda_e_reaproveitadas() {
+    let pool = pool(4);
+    assert_eq!(pool.estatisticas().abertas, 0);
+
+    for _ in 0..10 {
+        assert_eq!(
+            pool.obter().unwrap().esocial_nome().unwrap(),
+            "ACBrLibeSocial"
+        );
+    }
+
+    let estatisticas = pool.estatisticas();
+    assert_eq!(estatisticas.criadas, 1);
+    assert_eq!(estatisticas.reaproveitadas, 9);
+    assert_eq!(estatisticas.livres, 1);
+}
+
+#[test]
+fn tamanho_maximo_e_respeitado_com_muitas_threads() {
+    let pool = pool(3);
+    let em_uso = Arc::new(AtomicUsize::new(0));
+    let maximo = Arc::new(AtomicUsize::new(0));
+
+    let threads: Vec<_> = (0..32)
+        .map(|_| {
+            let (pool, em_uso, maximo) = (pool.clone(), em_uso.clone(), maximo.clone());
+
+            thread::spawn(move || {
+                for _ in 0..5 {
+                    let sessao = pool.obter().unwrap();
+                    let atual = em_uso.fetch_add(1, Ordering::SeqCst) + 1;
+                    maximo.fetch_max(atual, Ordering::SeqCst);
+
+                    sessao.esocial_versao().unwrap();
+
+                    em_uso.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert!(maximo.load(Ordering::SeqCst) <= 3);
+    assert!(pool.estatisticas().criadas <= 3);
+    assert_eq!(pool.estatisticas().abertas, pool.estatisticas().livres);
+}
+
+#[test]
+fn sessoes_usam_o_modelo_de_configuracao() {
+    common::usar_stub();
+
+    let modelo = common::arquivo_temporario("modelo_pool.ini");
+    std::fs::write(&modelo, "[eSocial]\nIdEmpregador=12345678\n").unwrap();
+
+    let pool = ESocialPool::new(ConfigPool::new(2).com_modelo_config(&modelo));
+
+    let primeira = pool.obter().unwrap();
+    let segunda = pool.obter().unwrap();
+    for sessao in [&primeira, &segunda] {
+        assert_eq!(
+            sessao
+                .esocial_config_ler_valor("eSocial", "IdEmpregador")
+                .unwrap(),
+            "12345678"
+        );
+    }
+    drop((primeira, segunda));
+
+    let _ = std::fs::remove_file(&modelo);
+}
+
+#[test]
+fn sessao_com_codigo_fatal_e_descartada() {
+    let pool = pool(1);
+
+    {
+        let sessao = pool.obter().unwrap();
+        sessao
+            .esocial_config_gravar_valor("Stub", "ErroEnviareSocial", "-1")
+            .unwrap();
+        sessao.carregar_xml_evento("<eSocial/>").unwrap();
+
+        let erro = sessao.enviar_esocial(1).unwrap_err();
+        assert!(erro.fatal());
+    }
+
+    let estatisticas = pool.estatisticas();
+    assert_eq!(estatisticas.descartadas, 1);
+    assert_eq!(estatisticas.abertas, 0);
+
+    // A vaga foi liberada e uma nova sessão é criada
+    pool.obter().unwrap().esocial_nome().unwrap();
+    assert_eq!(pool.estatisticas().criadas, 2);
+}
+
+#[test]
+fn erro_nao_fatal_mantem_a_sessao() {
+    let pool = pool(1);
+
+    assert_eq!(
+        pool.executar(|sessao| sessao.enviar_esocial(1)),
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+
+    assert_eq!(pool.estatisticas().descartadas, 0);
+    assert_eq!(pool.estatisticas().livres, 1);
+}
+
+#[test]
+fn sessao_que_falha_na_verificacao_e_substituida() {
+    let pool = pool(1);
+
+    pool.executar(|sessao| sessao.esocial_config_gravar_valor("Stub", "ErroNome", "-10"))
+        .unwrap();
+
+    // A sessão livre não responde ao esocial_nome, então outra é criada no lugar
+    pool.executar(|sessao| sessao.esocial_versao()).unwrap();
+
+    let estatisticas = pool.estatisticas();
+    assert_eq!(estatisticas.descartadas, 1);
+    assert_eq!(estatisticas.criadas, 2);
+}
+
+#[test]
+fn debug_da_configuracao_oculta_a_chave() {
+    let config = ConfigPool::new(2).com_chave_criptografia("segredo");
+
+    let debug = format!("{:?}", config);
+    assert!(!debug.contains("segredo"), "{}", debug);
+    assert!(debug.contains("chave_criptografia: \"***\""), "{}", debug);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use acbr_lib::acbr_lib_esocial::SessaoComPrazo;
+use acbr_lib::error::ACBrError;
+use acbr_lib::metricas;
+
+fn sessao(prazo: Duration) -> SessaoComPrazo {
+    common::usar_stub();
+    SessaoComPrazo::com_config_temporaria("", prazo).unwrap()
+}
+
+#[test]
+fn chamada_dentro_do_prazo_retorna_normalmente() {
+    let lib = sessao(Duration::from_secs(5));
+
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+
+    lib.set_tipo_empregador(1).unwrap();
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "TipoEmpregador")
+            .unwrap(),
+        "1"
+    );
+    assert!(!lib.em_quarentena());
+}
+
+#[test]
+fn chamada_que_passa_do_prazo_coloca_a_sessao_em_quarentena() {
+    let lib = sessao(Duration::from_millis(100));
+    lib.esocial_config_gravar_valor("Stub", "AtrasoVersao", "1000")
+        .unwrap();
+
+    let expiradas = metricas::metricas().chamadas_expiradas;
+    let inicio = Instant::now();
+
+    assert_eq!(
+        lib.esocial_versao(),
+        Err(ACBrError::Timeout(Duration::from_millis(100)))
+    );
+    assert!(inicio.elapsed() < Duration::from_millis(800));
+
+    assert!(lib.em_quarentena());
+    assert!(metricas::metricas().chamadas_expiradas > expiradas);
+    assert_eq!(lib.esocial_nome(), Err(ACBrError::SessaoEmQuarentena));
+}
+
+#[test]
+fn prazo_da_chamada_substitui_o_da_sessao() {
+    let lib = sessao(Duration::from_millis(50));
+    lib.esocial_config_gravar_valor("Stub", "AtrasoVersao", "200")
+        .unwrap();
+
+    let versao = lib
+        .executar_com_prazo(Duration::from_secs(5), |sessao| sessao.esocial_versao())
+        .unwrap();
+
+    assert_eq!(versao, "0.0.0-stub");
+    assert!(!lib.em_quarentena());
+}
+
+#[test]
+fn erro_da_chamada_nao_coloca_a_sessao_em_quarentena() {
+    let lib = sessao(Duration::from_secs(5));
+
+    assert_eq!(
+        lib.enviar_esocial(1),
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+    assert!(!lib.em_quarentena());
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+}
+
+#[test]
+fn prazo_conta_enquanto_a_chamada_espera_na_fila() {
+    let lib = sessao(Duration::from_millis(100));
+    lib.esocial_config_gravar_valor("Stub", "AtrasoVersao", "1000")
+        .unwrap();
+
+    // O primeiro clone ocupa a sessão por mais tempo que o prazo do segundo
+    let ocupada = lib.clone();
+    let lenta = std::thread::spawn(move || {
+        ocupada.executar_com_prazo(Duration::from_secs(5), |sessao| sessao.esocial_versao())
+    });
+    std::thread::sleep(Duration::from_millis(50));
+
+    let inicio = Instant::now();
+    assert_eq!(
+        lib.esocial_nome(),
+        Err(ACBrError::Timeout(Duration::from_millis(100)))
+    );
+    assert!(inicio.elapsed() < Duration::from_millis(800));
+
+    // A chamada descartada na fila não coloca a sessão em quarentena
+    assert_eq!(lenta.join().unwrap().unwrap(), "0.0.0-stub");
+    assert!(!lib.em_quarentena());
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn sessao_assincrona_respeita_o_prazo() {
+    use acbr_lib::acbr_lib_esocial::SessaoAssincrona;
+
+    common::usar_stub();
+    let lib = SessaoAssincrona::com_config_temporaria("")
+        .await
+        .unwrap()
+        .com_prazo(Duration::from_millis(100));
+    lib.esocial_config_gravar_valor("Stub", "AtrasoVersao", "1000")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        lib.esocial_versao().await,
+        Err(ACBrError::Timeout(Duration::from_millis(100)))
+    );
+    assert_eq!(lib.esocial_nome().await, Err(ACBrError::SessaoEmQuarentena));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+use acbr_lib::error::ACBrError;
+
+#[test]
+fn config_temporaria_e_apagada_ao_liberar_a_sessao() {
+    let lib = common::sessao();
+    let arquivo = lib.arquivo_config().to_path_buf();
+
+    lib.esocial_config_gravar(arquivo.to_str().unwrap())
+        .unwrap();
+    assert!(arquivo.exists());
+
+    drop(lib);
+    assert!(!arquivo.exists());
+    assert!(!arquivo.parent().unwrap().exists());
+}
+
+#[test]
+fn modelo_de_config_e_copiado_e_preservado() {
+    common::usar_stub();
+
+    let modelo = common::arquivo_temporario("modelo.ini");
+    std::fs::write(&modelo, "[eSocial]\nVersaoDF=S01_02_00\n").unwrap();
+
+    let lib = ACBrLibEsocial::com_modelo_config(&modelo, "").unwrap();
+    let arquivo = lib.arquivo_config().to_path_buf();
+
+    assert_ne!(arquivo, modelo);
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "VersaoDF").unwrap(),
+        "S01_02_00"
+    );
+
+    lib.finalizar().unwrap();
+
+    assert!(!arquivo.exists());
+    assert!(modelo.exists());
+    let _ = std::fs::remove_file(&modelo);
+}
+
+#[test]
+fn falha_na_inicializacao_apaga_a_config_temporaria() {
+    common::usar_stub();
+
+    let modelo = common::arquivo_temporario("modelo_com_erro.ini");
+    std::fs::write(&modelo, modelo_conteudo()).unwrap();
+
+    let resultado = ACBrLibEsocial::com_modelo_config(&modelo, "").map(|_| ());
+    let _ = std::fs::remove_file(&modelo);
+
+    match resultado {
+        Err(ACBrError::Execucao(mensagem)) => {
+            assert!(
+                mensagem.starts_with("eSocial_Inicializar falhou"),
+                "{}",
+                mensagem
+            );
+            assert!(mensagem.ends_with("config.ini"), "{}", mensagem);
+        }
+        resultado => panic!("resultado inesperado: {:?}", resultado),
+    }
+    assert!(copias_do_modelo(&modelo_conteudo()).is_empty());
+}
+
+#[test]
+fn config_externa_nao_e_apagada() {
+    common::usar_stub();
+
+    let arquivo = common::arquivo_temporario("externa.ini");
+    std::fs::write(&arquivo, "[eSocial]\nVersaoDF=S01_03_00\n").unwrap();
+
+    let lib = ACBrLibEsocial::new(arquivo.to_str().unwrap(), "").unwrap();
+    drop(lib);
+
+    assert!(arquivo.exists());
+    let _ = std::fs::remove_file(&arquivo);
+}
+
+fn modelo_conteudo() -> String {
+    format!(
+        "[Stub]\nErroInicializar=-10\nProcesso={}\n",
+        std::process::id()
+    )
+}
+
+/// Configurações temporárias de sessões deste processo com o conteúdo informado.
+fn copias_do_modelo(conteudo: &str) -> Vec<std::path::PathBuf> {
+    let prefixo = format!("acbr_esocial_{}_", std::process::id());
+
+    std::fs::read_dir(std::env::temp_dir())
+        .unwrap()
+        .flatten()
+        .filter(|entrada| entrada.file_name().to_string_lossy().starts_with(&prefixo))
+        .map(|entrada| entrada.path().join("config.ini"))
+        .filter(|caminho| std::fs::read_to_string(caminho).is_ok_and(|texto| texto == conteudo))
+        .collect()
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use acbr_lib::acbr_lib_esocial::config::{ConfigESocial, TipoResposta};
+use acbr_lib::acbr_lib_esocial::respostas::{ConsultaResposta, EnvioResposta, RespostaESocial};
+use acbr_lib::acbr_lib_esocial::{ACBrLibEsocial, ConfigWorker, SessaoComPrazo, SessaoIsolada};
+use acbr_lib::error::ACBrError;
+
+#[test]
+fn enviar_sem_eventos_retorna_erro_de_execucao() {
+    let lib = common::sessao();
+
+    assert_eq!(
+        lib.enviar_esocial(1),
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+}
+
+#[test]
+fn enviar_devolve_o_protocolo_do_lote() {
+    let lib = common::sessao();
+
+    lib.set_id_empregador("12345678").unwrap();
+    lib.carregar_xml_evento("<eSocial/>").unwrap();
+    lib.carregar_xml_evento("<eSocial/>").unwrap();
+
+    let resposta = lib.enviar_esocial(1).unwrap();
+
+    assert!(resposta.starts_with("[Envio]\nCodigo=201\n"));
+    assert!(resposta.contains("NrInscEmpreg=12345678\n"));
+    assert!(resposta.contains("QtdeEventos=2\n"));
+    assert!(resposta.contains("Protocolo=1.1.202501.0000000000000000001\n"));
+}
+
+#[test]
+fn respostas_do_envio_e_da_consulta_sao_lidas_nos_tipos() {
+    let lib = common::sessao();
+
+    lib.set_id_empregador("12345678").unwrap();
+    lib.carregar_xml_evento("<eSocial/>").unwrap();
+
+    let envio = EnvioResposta::de_ini(&lib.enviar_esocial(1).unwrap()).unwrap();
+    assert!(envio.recebido());
+    assert_eq!(envio.nr_insc_empreg.as_deref(), Some("12345678"));
+    assert_eq!(envio.qtde_eventos, Some(1));
+
+    let protocolo = envio.protocolo.unwrap();
+    let consulta = ConsultaResposta::de_ini(&lib.consultar_protocolo(&protocolo).unwrap()).unwrap();
+    assert!(consulta.processado());
+    assert_eq!(consulta.protocolo, Some(protocolo));
+    assert_eq!(consulta.eventos.len(), 1);
+    assert!(consulta.eventos[0].aceito());
+    assert_eq!(
+        consulta.eventos[0].nr_recibo.as_deref(),
+        Some("1.1.0000000000000000001")
+    );
+    assert_eq!(
+        ConsultaResposta::de_ini(&lib.obter_ultimo_retorno().unwrap()).unwrap(),
+        consulta
+    );
+}
+
+#[test]
+fn respostas_em_xml_e_json_sao_lidas_nos_mesmos_tipos() {
+    common::usar_stub();
+
+    let mut resultados = Vec::new();
+
+    for tipo in [TipoResposta::Ini, TipoResposta::Xml, TipoResposta::Json] {
+        let mut config = ConfigESocial::default();
+        config.principal.tipo_resposta = Some(tipo);
+        config.esocial.id_empregador = Some("12345678".into());
+
+        let lib = ACBrLibEsocial::com_config(&config, "").unwrap();
+        assert_eq!(lib.tipo_resposta().unwrap(), tipo);
+
+        lib.carregar_xml_evento("<eSocial/>").unwrap();
+        let envio = lib.enviar_lote(1).unwrap();
+        let consulta = lib
+            .consultar_lote(envio.protocolo.as_deref().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            lib.ultima_resposta().unwrap(),
+            RespostaESocial::Consulta(consulta.clone())
+        );
+        resultados.push((envio, consulta));
+    }
+
+    assert_eq!(resultados[0], resultados[1]);
+    assert_eq!(resultados[0], resultados[2]);
+    assert!(resultados[0].0.recebido());
+    assert_eq!(resultados[0].1.eventos.len(), 1);
+}
+
+#[test]
+fn respostas_tipadas_nas_outras_sessoes() {
+    common::usar_stub();
+
+    let modelo = common::arquivo_temporario("respostas_json.ini");
+    std::fs::write(&modelo, "[Principal]\nTipoResposta=2\n").unwrap();
+
+    let isolada = SessaoIsolada::new(
+        ConfigWorker::new()
+            .com_executavel(env!("CARGO_BIN_EXE_acbr_esocial_worker"))
+            .com_biblioteca(common::biblioteca_stub())
+            .com_modelo_config(&modelo),
+    )
+    .unwrap();
+    let com_prazo = SessaoComPrazo::com_modelo_config(&modelo, "", Duration::from_secs(5));
+    let _ = std::fs::remove_file(&modelo);
+    let com_prazo = com_prazo.unwrap();
+
+    assert_eq!(isolada.tipo_resposta().unwrap(), TipoResposta::Json);
+    isolada.carregar_xml_evento("<eSocial/>").unwrap();
+    let envio = isolada.enviar_lote(1).unwrap();
+    assert!(envio.recebido());
+    let consulta = isolada
+        .consultar_lote(envio.protocolo.as_deref().unwrap())
+        .unwrap();
+    assert_eq!(
+        isolada.ultima_resposta().unwrap(),
+        RespostaESocial::Consulta(consulta)
+    );
+
+    assert_eq!(com_prazo.tipo_resposta().unwrap(), TipoResposta::Json);
+    com_prazo.carregar_xml_evento("<eSocial/>").unwrap();
+    let envio = com_prazo.enviar_lote(1).unwrap();
+    let consulta = com_prazo.consultar_lote(envio.protocolo.unwrap()).unwrap();
+    assert_eq!(
+        com_prazo.ultima_resposta().unwrap(),
+        RespostaESocial::Consulta(consulta)
+    );
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn respostas_tipadas_na_sessao_assincrona() {
+    use acbr_lib::acbr_lib_esocial::SessaoAssincrona;
+
+    common::usar_stub();
+
+    let mut config = ConfigESocial::default();
+    config.principal.tipo_resposta = Some(TipoResposta::Xml);
+    let lib = SessaoAssincrona::com_config(config, "").await.unwrap();
+
+    assert_eq!(lib.tipo_resposta().await.unwrap(), TipoResposta::Xml);
+    lib.carregar_xml_evento("<eSocial/>").await.unwrap();
+    let envio = lib.enviar_lote(1).await.unwrap();
+    let consulta = lib.consultar_lote(envio.protocolo.unwrap()).await.unwrap();
+    assert_eq!(
+        lib.ultima_resposta().await.unwrap(),
+        RespostaESocial::Consulta(consulta)
+    );
+}
+
+#[test]
+fn sessao_sem_tipo_resposta_na_configuracao_usa_ini() {
+    common::usar_stub();
+
+    let arquivo = common::arquivo_temporario("config_sem_tipo_resposta.ini");
+    std::fs::write(&arquivo, "[Principal]\nTipoResposta=\n").unwrap();
+    let lib = ACBrLibEsocial::new(arquivo.to_str().unwrap(), "").unwrap();
+    let _ = std::fs::remove_file(&arquivo);
+
+    assert_eq!(lib.tipo_resposta().unwrap(), TipoResposta::Ini);
+    assert_eq!(
+        lib.esocial_config_ler_valor("Principal", "TipoResposta")
+            .unwrap(),
+        "0"
+    );
+}
+
+#[test]
+fn respostas_roteirizadas_por_chamada() {
+    let lib = common::sessao();
+
+    lib.esocial_config_gravar_valor(
+        "Stub",
+        "RespostaConsultareSocial1",
+        "[Consulta]\\nCodigo=101\\nMensagem=Lote aguardando processamento.",
+    )
+    .unwrap();
+    lib.esocial_config_gravar_valor(
+        "Stub",
+        "RespostaConsultareSocial",
+        "[Consulta]\\nCodigo=201\\nMensagem=Lote processado com sucesso.",
+    )
+    .unwrap();
+
+    assert_eq!(
+        lib.consultar_protocolo("1.1.202501.0000000000000000001")
+            .unwrap(),
+        "[Consulta]\nCodigo=101\nMensagem=Lote aguardando processamento."
+    );
+    assert_eq!(
+        lib.consultar_protocolo("1.1.202501.0000000000000000001")
+            .unwrap(),
+        "[Consulta]\nCodigo=201\nMensagem=Lote processado com sucesso."
+    );
+}
+
+#[test]
+fn erro_injetado_traz_a_mensagem_configurada() {
+    let lib = common::sessao();
+
+    lib.carregar_xml_evento("<eSocial/>").unwrap();
+    lib.esocial_config_gravar_valor("Stub", "ErroEnviareSocial2", "-10")
+        .unwrap();
+    lib.esocial_config_gravar_valor("Stub", "MensagemErroEnviareSocial", "Falha na conexão")
+        .unwrap();
+
+    assert!(lib.enviar_esocial(1).is_ok());
+    assert_eq!(
+        lib.enviar_esocial(1),
+        Err(ACBrError::Execucao("Falha na conexão".into()))
+    );
+    assert!(lib.enviar_esocial(1).is_ok());
+}
+
+#[test]
+fn atraso_injetado_segura_a_chamada() {
+    let lib = common::sessao();
+
+    lib.esocial_config_gravar_valor("Stub", "AtrasoNome", "200")
+        .unwrap();
+
+    let inicio = Instant::now();
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+    assert!(inicio.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn roteiro_lido_do_arquivo_de_configuracao() {
+    common::usar_stub();
+
+    let arquivo = common::arquivo_temporario("inicializar.ini");
+    std::fs::write(&arquivo, "[Stub]\nErroInicializar=-5\n").unwrap();
+
+    let resultado = ACBrLibEsocial::new(arquivo.to_str().unwrap(), "").map(|_| ());
+    let _ = std::fs::remove_file(&arquivo);
+
+    assert_eq!(
+        resultado,
+        Err(ACBrError::ArquivoNaoEncontrado(format!(
+            "eSocial_Inicializar falhou com o arquivo de configuração {}",
+            arquivo.display()
+        )))
+    );
+}
+
+#[test]
+fn config_exportar_devolve_a_configuracao_em_memoria() {
+    let lib = common::sessao();
+
+    lib.esocial_config_gravar_valor("eSocial", "VersaoDF", "S01_03_00")
+        .unwrap();
+
+    assert_eq!(
+        lib.esocial_config_exportar().unwrap(),
+        "[eSocial]\nVersaoDF=S01_03_00\n\n"
+    );
+}
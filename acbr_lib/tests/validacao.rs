@@ -0,0 +1,133 @@
+mod common;
+
+use std::time::Duration;
+
+use acbr_lib::acbr_lib_esocial::config::ConfigESocial;
+use acbr_lib::acbr_lib_esocial::validacao::{AlertaValidacao, FormatoAlerta};
+use acbr_lib::acbr_lib_esocial::{ACBrLibEsocial, ConfigWorker, SessaoComPrazo, SessaoIsolada};
+use acbr_lib::error::ACBrError;
+
+const EVENTO_INCOMPLETO: &str = "<?xml version=\"1.0\"?>\
+    <eSocial><evtAdmissao Id=\"ID1\"><trabalhador>\
+    <cpfTrab/><nmTrab>José</nmTrab><sexo></sexo>\
+    </trabalhador></evtAdmissao></eSocial>";
+
+fn alertas_esperados() -> Vec<AlertaValidacao> {
+    let alerta = |id: &str, tag: &str| AlertaValidacao {
+        caminho: "<eSocial><evtAdmissao><trabalhador>".into(),
+        id: id.into(),
+        tag: tag.into(),
+        descricao: tag.into(),
+        mensagem: "Nenhum valor informado".into(),
+    };
+
+    vec![alerta("4", "cpfTrab"), alerta("6", "sexo")]
+}
+
+#[test]
+fn evento_valido_nao_tem_alertas() {
+    let lib = common::sessao();
+
+    lib.carregar_xml_evento(
+        "<eSocial><evtAdmissao><cpfTrab>12345678909</cpfTrab></evtAdmissao></eSocial>",
+    )
+    .unwrap();
+
+    assert_eq!(lib.validar_esocial().unwrap(), []);
+}
+
+#[test]
+fn alertas_lidos_com_o_formato_padrao() {
+    let lib = common::sessao();
+
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).unwrap();
+
+    assert_eq!(lib.formato_alerta().unwrap(), FormatoAlerta::default());
+    assert_eq!(lib.validar_esocial().unwrap(), alertas_esperados());
+}
+
+#[test]
+fn alertas_lidos_com_o_formato_estruturado() {
+    let lib = common::sessao();
+
+    lib.usar_formato_alerta_estruturado().unwrap();
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).unwrap();
+
+    assert_eq!(
+        lib.esocial_config_ler_valor("eSocial", "FormatoAlerta")
+            .unwrap(),
+        FormatoAlerta::ESTRUTURADO
+    );
+    assert_eq!(lib.validar_esocial().unwrap(), alertas_esperados());
+}
+
+#[test]
+fn alertas_lidos_com_o_formato_da_configuracao() {
+    common::usar_stub();
+
+    let mut config = ConfigESocial::default();
+    config.esocial.formato_alerta = Some("%TAG% [%ID%]: %MSG%".into());
+    let lib = ACBrLibEsocial::com_config(&config, "").unwrap();
+
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).unwrap();
+
+    let alertas = lib.validar_esocial().unwrap();
+    assert_eq!(alertas.len(), 2);
+    assert_eq!(alertas[0].tag, "cpfTrab");
+    assert_eq!(alertas[0].id, "4");
+    assert_eq!(alertas[0].caminho, "");
+}
+
+#[test]
+fn erro_fora_do_formato_e_devolvido_como_veio() {
+    let lib = common::sessao();
+
+    assert_eq!(
+        lib.validar_esocial(),
+        Err(ACBrError::Execucao("Nenhum evento carregado".into()))
+    );
+}
+
+#[test]
+fn alertas_na_sessao_isolada() {
+    let lib = SessaoIsolada::new(
+        ConfigWorker::new()
+            .com_executavel(env!("CARGO_BIN_EXE_acbr_esocial_worker"))
+            .com_biblioteca(common::biblioteca_stub()),
+    )
+    .unwrap();
+
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).unwrap();
+
+    assert_eq!(lib.validar_esocial().unwrap(), alertas_esperados());
+    lib.usar_formato_alerta_estruturado().unwrap();
+    assert_eq!(
+        lib.formato_alerta().unwrap(),
+        FormatoAlerta::new(FormatoAlerta::ESTRUTURADO)
+    );
+    assert_eq!(lib.validar_esocial().unwrap(), alertas_esperados());
+}
+
+#[test]
+fn alertas_na_sessao_com_prazo() {
+    common::usar_stub();
+    let lib = SessaoComPrazo::com_config_temporaria("", Duration::from_secs(5)).unwrap();
+
+    lib.usar_formato_alerta_estruturado().unwrap();
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).unwrap();
+
+    assert_eq!(lib.validar_esocial().unwrap(), alertas_esperados());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn alertas_na_sessao_assincrona() {
+    use acbr_lib::acbr_lib_esocial::SessaoAssincrona;
+
+    common::usar_stub();
+    let lib = SessaoAssincrona::com_config_temporaria("").await.unwrap();
+
+    lib.carregar_xml_evento(EVENTO_INCOMPLETO).await.unwrap();
+
+    assert_eq!(lib.validar_esocial().await.unwrap(), alertas_esperados());
+}
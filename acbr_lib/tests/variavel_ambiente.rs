@@ -0,0 +1,14 @@
+mod common;
+
+use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+use acbr_lib::localizador::VARIAVEL_ESOCIAL;
+
+/// Sem configurar o localizador, a biblioteca é encontrada pela variável de ambiente.
+#[test]
+fn biblioteca_apontada_pela_variavel_de_ambiente() {
+    std::env::set_var(VARIAVEL_ESOCIAL, common::biblioteca_stub());
+
+    let lib = ACBrLibEsocial::com_config_temporaria("").unwrap();
+
+    assert_eq!(lib.esocial_nome().unwrap(), "ACBrLibeSocial");
+}
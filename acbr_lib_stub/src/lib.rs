@@ -0,0 +1,899 @@
+//! Implementação de teste da ACBrLibeSocial.
+//!
+//! Exporta as mesmas funções `eSocial_*` da biblioteca original, na mesma convenção de
+//! chamada (`extern "system"`: `stdcall` no Windows 32 bits e `cdecl` nas demais), para
+//! que o `acbr_lib` possa ser testado sem a DLL proprietária. Não acessa o webservice:
+//! a configuração fica em memória e as respostas são fixas ou roteirizadas.
+//!
+//! O comportamento é controlado pela sessão `[Stub]` da configuração, gravada com
+//! `eSocial_ConfigGravarValor` ou lida do arquivo INI em `eSocial_Inicializar`. `<Funcao>`
+//! é o nome exportado sem o prefixo `eSocial_` (ex.: `EnviareSocial`):
+//!
+//! - `Resposta<Funcao>`: resposta devolvida pela função, com `\n` para quebras de linha;
+//! - `Erro<Funcao>` e `MensagemErro<Funcao>`: código de retorno e texto de `UltimoRetorno`;
+//! - `Atraso<Funcao>` ou `Atraso`: espera, em milissegundos, antes de executar a função;
+//! - `Falha<Funcao>`: com qualquer valor, derruba o processo com `abort`, simulando uma
+//!   falha de acesso à memória dentro da biblioteca.
+//!
+//! As respostas padrão de `EnviareSocial` e `ConsultareSocial` seguem o formato de
+//! `[Principal] TipoResposta` (0 INI, 1 XML e 2 JSON).
+//!
+//! `eSocial_Validar` rejeita cada elemento vazio dos eventos carregados com um alerta no
+//! modelo de `[eSocial] FormatoAlerta`: `%ID%` é a posição do elemento no XML, e
+//! `%DESCRICAO%` repete o nome da tag.
+//!
+//! Com `[Principal] CodificacaoResposta=1`, os textos recebidos e devolvidos são convertidos
+//! de e para Windows-1252, como na biblioteca original.
+//!
+//! Com um número no fim da chave (ex.: `RespostaConsultareSocial2`), o valor vale apenas
+//! para aquela chamada da função, contada a partir de 1 em cada instância.
+#![allow(clippy::missing_safety_doc)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_char, c_int, c_void};
+use std::process;
+use std::ptr;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+const OK: c_int = 0;
+const ERRO_SESSAO: c_int = -3;
+const ERRO_CHAVE: c_int = -4;
+const ERRO_ARQUIVO: c_int = -5;
+const ERRO_PARAMETRO: c_int = -9;
+const ERRO_EXECUCAO: c_int = -10;
+
+pub const NOME: &str = "ACBrLibeSocial";
+pub const VERSAO: &str = "0.0.0-stub";
+
+/// Valores padrão das chaves que o wrapper consulta sem ter gravado. Como na biblioteca
+/// original, valem enquanto a chave não estiver na configuração.
+const PADROES: &[(&str, &str, &str)] = &[
+    ("Principal", "TipoResposta", "0"),
+    (
+        "eSocial",
+        "FormatoAlerta",
+        "TAG:%TAGNIVEL% ID:%ID%/%TAG%(%DESCRICAO%) - %MSG%.",
+    ),
+];
+
+/// Estado de uma instância criada por `eSocial_Inicializar`.
+#[derive(Default)]
+struct Instancia {
+    /// Sessões e chaves da configuração, como no arquivo INI.
+    config: BTreeMap<String, BTreeMap<String, String>>,
+    ultimo_retorno: String,
+    eventos: Vec<String>,
+    /// Quantidade de chamadas de cada função, para os valores roteirizados por chamada.
+    chamadas: HashMap<&'static str, u32>,
+    lotes: u32,
+}
+
+impl Instancia {
+    fn ler_valor(&self, sessao: &str, chave: &str) -> Result<&str, c_int> {
+        let valor = match self.config.get(sessao) {
+            Some(chaves) => chaves.get(chave).map(String::as_str).ok_or(ERRO_CHAVE),
+            None => Err(ERRO_SESSAO),
+        };
+
+        valor.or_else(|erro| {
+            PADROES
+                .iter()
+                .find(|(s, c, _)| *s == sessao && *c == chave)
+                .map(|(_, _, padrao)| *padrao)
+                .ok_or(erro)
+        })
+    }
+
+    fn gravar_valor(&mut self, sessao: &str, chave: &str, valor: &str) {
+        self.config
+            .entry(sessao.to_string())
+            .or_default()
+            .insert(chave.to_string(), valor.to_string());
+    }
+
+    fn ler_ini(&mut self, conteudo: &str) {
+        let mut sessao = String::new();
+
+        for linha in conteudo.lines().map(str::trim) {
+            if let Some(nome) = linha.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sessao = nome.to_string();
+            } else if let Some((chave, valor)) = linha.split_once('=') {
+                self.gravar_valor(&sessao, chave.trim(), valor.trim());
+            }
+        }
+    }
+
+    fn exportar_ini(&self) -> String {
+        let mut ini = String::new();
+
+        for (sessao, chaves) in &self.config {
+            ini.push_str(&format!("[{}]\n", sessao));
+            for (chave, valor) in chaves {
+                ini.push_str(&format!("{}={}\n", chave, valor));
+            }
+            ini.push('\n');
+        }
+
+        ini
+    }
+
+    /// Valor da sessão `[Stub]` para a chamada atual de `funcao`: primeiro a chave com o
+    /// número da chamada, depois a chave sem número.
+    fn roteiro(&self, chave: &str, funcao: &'static str) -> Option<String> {
+        let stub = self.config.get("Stub")?;
+        let chamada = self.chamadas.get(funcao).copied().unwrap_or_default();
+
+        stub.get(&format!("{}{}{}", chave, funcao, chamada))
+            .or_else(|| stub.get(&format!("{}{}", chave, funcao)))
+            .map(|valor| valor.replace("\\n", "\n"))
+    }
+
+    /// Conta a chamada e aplica o atraso, a falha e o erro configurados para a função.
+    fn injetar(&mut self, funcao: &'static str) -> Result<(), c_int> {
+        *self.chamadas.entry(funcao).or_default() += 1;
+
+        let atraso = self
+            .roteiro("Atraso", funcao)
+            .or_else(|| self.config.get("Stub")?.get("Atraso").cloned());
+        if let Some(atraso) = atraso.and_then(|atraso| atraso.parse().ok()) {
+            thread::sleep(Duration::from_millis(atraso));
+        }
+
+        if self.roteiro("Falha", funcao).is_some() {
+            eprintln!("Falha injetada em eSocial_{}", funcao);
+            process::abort();
+        }
+
+        if let Some(codigo) = self
+            .roteiro("Erro", funcao)
+            .and_then(|codigo| codigo.parse::<c_int>().ok())
+        {
+            let mensagem = self
+                .roteiro("MensagemErro", funcao)
+                .unwrap_or_else(|| format!("Erro injetado em eSocial_{}", funcao));
+            return Err(self.erro(codigo, mensagem));
+        }
+
+        Ok(())
+    }
+
+    /// Resposta roteirizada da função ou, se não houver, a resposta padrão.
+    fn resposta(&self, funcao: &'static str, padrao: impl FnOnce() -> String) -> String {
+        self.roteiro("Resposta", funcao).unwrap_or_else(padrao)
+    }
+
+    /// Monta a resposta no formato de `[Principal] TipoResposta`: INI, XML com as demais
+    /// sessões aninhadas na primeira, ou JSON com uma propriedade por sessão.
+    fn formatar(&self, secoes: &[(&str, Vec<(&str, String)>)]) -> String {
+        match self.ler_valor("Principal", "TipoResposta") {
+            Ok("1") => {
+                let elemento = |(nome, chaves): &(&str, Vec<(&str, String)>)| {
+                    let valores: String = chaves
+                        .iter()
+                        .map(|(chave, valor)| format!("<{0}>{1}</{0}>", chave, escapar_xml(valor)))
+                        .collect();
+                    (nome.to_string(), valores)
+                };
+
+                let (principal, mut xml) = elemento(&secoes[0]);
+                for secao in &secoes[1..] {
+                    let (nome, valores) = elemento(secao);
+                    xml.push_str(&format!("<{0}>{1}</{0}>", nome, valores));
+                }
+
+                format!("<{0}>{1}</{0}>", principal, xml)
+            }
+            Ok("2") => {
+                let objetos: Vec<String> = secoes
+                    .iter()
+                    .map(|(nome, chaves)| {
+                        let valores: Vec<String> = chaves
+                            .iter()
+                            .map(|(chave, valor)| format!("{:?}:{:?}", chave, valor))
+                            .collect();
+                        format!("{:?}:{{{}}}", nome, valores.join(","))
+                    })
+                    .collect();
+
+                format!("{{{}}}", objetos.join(","))
+            }
+            _ => secoes
+                .iter()
+                .map(|(nome, chaves)| {
+                    let valores: String = chaves
+                        .iter()
+                        .map(|(chave, valor)| format!("{}={}\n", chave, valor))
+                        .collect();
+                    format!("[{}]\n{}", nome, valores)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// Com `[Principal] CodificacaoResposta=1`, os textos são trocados em Windows-1252.
+    fn ansi(&self) -> bool {
+        self.ler_valor("Principal", "CodificacaoResposta") == Ok("1")
+    }
+
+    /// Texto recebido da aplicação, na codificação configurada.
+    unsafe fn texto(&self, valor: *const c_char) -> String {
+        if valor.is_null() {
+            return String::new();
+        }
+
+        let bytes = CStr::from_ptr(valor).to_bytes();
+        if self.ansi() {
+            bytes.iter().map(|&byte| de_windows1252(byte)).collect()
+        } else {
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+    }
+
+    /// Bytes do texto devolvido à aplicação, na codificação configurada.
+    fn codificar(&self, texto: &str) -> Vec<u8> {
+        if self.ansi() {
+            texto.chars().map(para_windows1252).collect()
+        } else {
+            texto.as_bytes().to_vec()
+        }
+    }
+
+    /// Guarda o erro em `UltimoRetorno` e devolve o código.
+    fn erro(&mut self, codigo: c_int, mensagem: impl Into<String>) -> c_int {
+        self.ultimo_retorno = mensagem.into();
+        codigo
+    }
+}
+
+/// Caracteres de Windows-1252 nos bytes 0x80 a 0x9F; os demais bytes coincidem com o
+/// Latin-1. Os bytes sem caractere definido ficam com o controle C1 de mesmo código.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+fn de_windows1252(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252[(byte - 0x80) as usize],
+        byte => byte as char,
+    }
+}
+
+/// Caracteres que não existem em Windows-1252 viram `?`, como na biblioteca original.
+fn para_windows1252(caractere: char) -> u8 {
+    match caractere as u32 {
+        codigo @ (0..=0x7F | 0xA0..=0xFF) => codigo as u8,
+        _ => WINDOWS_1252
+            .iter()
+            .position(|&c| c == caractere)
+            .map_or(b'?', |posicao| 0x80 + posicao as u8),
+    }
+}
+
+fn escapar_xml(valor: &str) -> String {
+    valor
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Protocolo de envio no formato do eSocial, numerado pela quantidade de lotes enviados.
+fn protocolo(lote: u32) -> String {
+    format!("1.1.202501.{:019}", lote)
+}
+
+unsafe fn texto(valor: *const c_char) -> String {
+    if valor.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(valor).to_string_lossy().into_owned()
+}
+
+unsafe fn instancia<'a>(handle: *mut c_void) -> Option<&'a mut Instancia> {
+    (handle as *mut Instancia).as_mut()
+}
+
+/// Protocolo de buffer da ACBrLib: `tamanho` chega com quantos caracteres cabem no buffer,
+/// além do NUL final, e volta com o tamanho completo da resposta, que fica guardada em
+/// `UltimoRetorno`.
+unsafe fn responder(
+    instancia: &mut Instancia,
+    resposta: String,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    if tamanho.is_null() {
+        return ERRO_PARAMETRO;
+    }
+
+    instancia.ultimo_retorno = resposta;
+    copiar_ultimo_retorno(instancia, buffer, tamanho)
+}
+
+unsafe fn copiar_ultimo_retorno(
+    instancia: &Instancia,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    if tamanho.is_null() {
+        return ERRO_PARAMETRO;
+    }
+
+    let bytes = instancia.codificar(&instancia.ultimo_retorno);
+    let capacidade = (*tamanho).max(0) as usize;
+
+    if !buffer.is_null() {
+        let copiar = bytes.len().min(capacidade);
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buffer as *mut u8, copiar);
+
+        // Como o `StrPLCopy` da ACBrLib, o NUL vai depois dos caracteres copiados, mesmo
+        // quando eles ocupam os `esTamanho` informados
+        *buffer.add(copiar) = 0;
+    }
+
+    *tamanho = bytes.len() as c_int;
+    OK
+}
+
+/// Instâncias com uma chamada em andamento. A ACBrLib não aceita chamadas simultâneas na
+/// mesma instância, então o stub recusa a segunda com um erro.
+static EM_EXECUCAO: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+const MENSAGEM_CONCORRENTE: &str = "Chamada simultânea na mesma instância";
+
+/// Marca a instância como em execução até ser liberado.
+struct Execucao(usize);
+
+impl Execucao {
+    fn iniciar(handle: *mut c_void) -> Option<Self> {
+        let mut em_execucao = EM_EXECUCAO.lock().unwrap_or_else(|erro| erro.into_inner());
+
+        if em_execucao.contains(&(handle as usize)) {
+            return None;
+        }
+
+        em_execucao.push(handle as usize);
+        Some(Execucao(handle as usize))
+    }
+}
+
+impl Drop for Execucao {
+    fn drop(&mut self) {
+        let mut em_execucao = EM_EXECUCAO.lock().unwrap_or_else(|erro| erro.into_inner());
+        em_execucao.retain(|&handle| handle != self.0);
+    }
+}
+
+/// Obtém a instância e aplica o roteiro da função, retornando o código de biblioteca não
+/// inicializada, de chamada simultânea ou o erro injetado. A marca de execução devolvida
+/// junto com a instância precisa ser mantida até o fim da função.
+macro_rules! instancia {
+    ($handle:expr, $funcao:literal) => {{
+        if $handle.is_null() {
+            return -1;
+        }
+
+        let execucao = match Execucao::iniciar($handle) {
+            Some(execucao) => execucao,
+            None => {
+                eprintln!("{}: eSocial_{}", MENSAGEM_CONCORRENTE, $funcao);
+                return ERRO_EXECUCAO;
+            }
+        };
+
+        match instancia($handle) {
+            Some(instancia) => {
+                if let Err(codigo) = instancia.injetar($funcao) {
+                    return codigo;
+                }
+                (execucao, instancia)
+            }
+            None => return -1,
+        }
+    }};
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_Inicializar(
+    handle: *mut *mut c_void,
+    arquivo_config: *const c_char,
+    _chave_crypt: *const c_char,
+) -> c_int {
+    if handle.is_null() {
+        return ERRO_PARAMETRO;
+    }
+
+    let mut instancia = Instancia::default();
+
+    let arquivo_config = texto(arquivo_config);
+    if !arquivo_config.is_empty() {
+        if let Ok(conteudo) = fs::read_to_string(&arquivo_config) {
+            instancia.ler_ini(&conteudo);
+        }
+    }
+
+    // Permite simular falha ou demora na inicialização pelo arquivo de configuração
+    if let Err(codigo) = instancia.injetar("Inicializar") {
+        return codigo;
+    }
+
+    *handle = Box::into_raw(Box::new(instancia)) as *mut c_void;
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_Finalizar(handle: *mut c_void) -> c_int {
+    if handle.is_null() {
+        return -1;
+    }
+
+    drop(Box::from_raw(handle as *mut Instancia));
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_UltimoRetorno(
+    handle: *mut c_void,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "UltimoRetorno");
+    copiar_ultimo_retorno(instancia, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_Nome(
+    handle: *mut c_void,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "Nome");
+    responder(instancia, NOME.to_string(), buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_Versao(
+    handle: *mut c_void,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "Versao");
+    responder(instancia, VERSAO.to_string(), buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigLer(
+    handle: *mut c_void,
+    arquivo_config: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigLer");
+    ler_config(instancia, instancia.texto(arquivo_config))
+}
+
+unsafe fn ler_config(instancia: &mut Instancia, arquivo_config: String) -> c_int {
+    match fs::read_to_string(&arquivo_config) {
+        Ok(conteudo) => {
+            instancia.ler_ini(&conteudo);
+            OK
+        }
+        Err(erro) => instancia.erro(ERRO_ARQUIVO, format!("{}: {}", arquivo_config, erro)),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigGravar(
+    handle: *mut c_void,
+    arquivo_config: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigGravar");
+    let arquivo_config = instancia.texto(arquivo_config);
+
+    match fs::write(&arquivo_config, instancia.exportar_ini()) {
+        Ok(()) => OK,
+        Err(erro) => instancia.erro(ERRO_ARQUIVO, format!("{}: {}", arquivo_config, erro)),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigLerValor(
+    handle: *mut c_void,
+    sessao: *const c_char,
+    chave: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigLerValor");
+    let (sessao, chave) = (instancia.texto(sessao), instancia.texto(chave));
+
+    match instancia.ler_valor(&sessao, &chave) {
+        Ok(valor) => {
+            let valor = valor.to_string();
+            responder(instancia, valor, buffer, tamanho)
+        }
+        Err(codigo) => instancia.erro(codigo, format!("[{}] {} não encontrada", sessao, chave)),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigGravarValor(
+    handle: *mut c_void,
+    sessao: *const c_char,
+    chave: *const c_char,
+    valor: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigGravarValor");
+    instancia.gravar_valor(
+        &instancia.texto(sessao),
+        &instancia.texto(chave),
+        &instancia.texto(valor),
+    );
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigImportar(
+    handle: *mut c_void,
+    arquivo_config: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigImportar");
+    ler_config(instancia, instancia.texto(arquivo_config))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConfigExportar(
+    handle: *mut c_void,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConfigExportar");
+    let ini = instancia.exportar_ini();
+    responder(instancia, ini, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_CriarEventoeSocial(
+    handle: *mut c_void,
+    arquivo_ini: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "CriarEventoeSocial");
+    instancia.eventos.push(instancia.texto(arquivo_ini));
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_EnviareSocial(
+    handle: *mut c_void,
+    grupo: c_int,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "EnviareSocial");
+
+    if instancia.eventos.is_empty() {
+        return instancia.erro(ERRO_EXECUCAO, "Nenhum evento carregado");
+    }
+
+    instancia.lotes += 1;
+
+    let resposta = instancia.resposta("EnviareSocial", || {
+        instancia.formatar(&[(
+            "Envio",
+            vec![
+                ("Codigo", "201".into()),
+                ("Mensagem", "Lote Recebido com Sucesso.".into()),
+                (
+                    "NrInscEmpreg",
+                    instancia
+                        .ler_valor("eSocial", "IdEmpregador")
+                        .unwrap_or_default()
+                        .into(),
+                ),
+                (
+                    "NrInscTransm",
+                    instancia
+                        .ler_valor("eSocial", "IdTransmissor")
+                        .unwrap_or_default()
+                        .into(),
+                ),
+                ("Grupo", grupo.to_string()),
+                ("QtdeEventos", instancia.eventos.len().to_string()),
+                ("Protocolo", protocolo(instancia.lotes)),
+            ],
+        )])
+    });
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConsultareSocial(
+    handle: *mut c_void,
+    protocolo: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConsultareSocial");
+    let protocolo = instancia.texto(protocolo);
+
+    let resposta = instancia.resposta("ConsultareSocial", || {
+        instancia.formatar(&[
+            (
+                "Consulta",
+                vec![
+                    ("Codigo", "201".into()),
+                    ("Mensagem", "Lote processado com sucesso.".into()),
+                    ("Protocolo", protocolo),
+                    ("QtdeEventos", "1".into()),
+                ],
+            ),
+            (
+                "Evento001",
+                vec![
+                    ("Id", "ID1000000000000002025010100000000000001".into()),
+                    ("Codigo", "201".into()),
+                    ("Descricao", "Sucesso.".into()),
+                    ("NrRecibo", "1.1.0000000000000000001".into()),
+                ],
+            ),
+        ])
+    });
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_CriarEnviareSocial(
+    handle: *mut c_void,
+    arquivo_ini: *const c_char,
+    _grupo: c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "CriarEnviareSocial");
+    instancia.eventos.push(instancia.texto(arquivo_ini));
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_LimpareSocial(handle: *mut c_void) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "LimpareSocial");
+    instancia.eventos.clear();
+    OK
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_CarregarXMLEventoeSocial(
+    handle: *mut c_void,
+    arquivo_ou_xml: *const c_char,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "CarregarXMLEventoeSocial");
+    instancia.eventos.push(instancia.texto(arquivo_ou_xml));
+    OK
+}
+
+/// As funções `Set*` gravam o valor na sessão `eSocial`, onde pode ser conferido com
+/// `eSocial_ConfigLerValor`.
+macro_rules! set_esocial {
+    ($nome:ident, $funcao:literal, $chave:literal, texto) => {
+        #[no_mangle]
+        pub unsafe extern "system" fn $nome(handle: *mut c_void, valor: *const c_char) -> c_int {
+            let (_execucao, instancia) = instancia!(handle, $funcao);
+            let valor = instancia.texto(valor);
+            instancia.gravar_valor("eSocial", $chave, &valor);
+            OK
+        }
+    };
+    ($nome:ident, $funcao:literal, $chave:literal, inteiro) => {
+        #[no_mangle]
+        pub unsafe extern "system" fn $nome(handle: *mut c_void, valor: c_int) -> c_int {
+            let (_execucao, instancia) = instancia!(handle, $funcao);
+            instancia.gravar_valor("eSocial", $chave, &valor.to_string());
+            OK
+        }
+    };
+}
+
+set_esocial!(
+    eSocial_SetIDEmpregador,
+    "SetIDEmpregador",
+    "IdEmpregador",
+    texto
+);
+set_esocial!(
+    eSocial_SetIDTransmissor,
+    "SetIDTransmissor",
+    "IdTransmissor",
+    texto
+);
+set_esocial!(
+    eSocial_SetTipoEmpregador,
+    "SetTipoEmpregador",
+    "TipoEmpregador",
+    inteiro
+);
+set_esocial!(eSocial_SetVersaoDF, "SetVersaoDF", "VersaoDF", texto);
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConsultaIdentificadoresEventosEmpregador(
+    handle: *mut c_void,
+    id_empregador: *const c_char,
+    tipo_evento: c_int,
+    periodo_apuracao: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConsultaIdentificadoresEventosEmpregador");
+    let resposta = format!(
+        "[ConsultaIdentificadoresEventosEmpregador]\nIdEmpregador={}\nTipoEvento={}\nPeriodoApuracao={}\n",
+        instancia.texto(id_empregador),
+        tipo_evento,
+        instancia.texto(periodo_apuracao)
+    );
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConsultaIdentificadoresEventosTabela(
+    handle: *mut c_void,
+    id_empregador: *const c_char,
+    tipo_evento: c_int,
+    chave: *const c_char,
+    data_inicial: *const c_char,
+    data_final: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConsultaIdentificadoresEventosTabela");
+    let resposta = format!(
+        "[ConsultaIdentificadoresEventosTabela]\nIdEmpregador={}\nTipoEvento={}\nChave={}\nDataInicial={}\nDataFinal={}\n",
+        instancia.texto(id_empregador),
+        tipo_evento,
+        instancia.texto(chave),
+        instancia.texto(data_inicial),
+        instancia.texto(data_final)
+    );
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ConsultaIdentificadoresEventosTrabalhador(
+    handle: *mut c_void,
+    id_empregador: *const c_char,
+    cpf_trabalhador: *const c_char,
+    data_inicial: *const c_char,
+    data_final: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ConsultaIdentificadoresEventosTrabalhador");
+    let resposta = format!(
+        "[ConsultaIdentificadoresEventosTrabalhador]\nIdEmpregador={}\nCpfTrabalhador={}\nDataInicial={}\nDataFinal={}\n",
+        instancia.texto(id_empregador),
+        instancia.texto(cpf_trabalhador),
+        instancia.texto(data_inicial),
+        instancia.texto(data_final)
+    );
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_DownloadEventos(
+    handle: *mut c_void,
+    id_empregador: *const c_char,
+    cpf_trabalhador: *const c_char,
+    data_inicial: *const c_char,
+    data_final: *const c_char,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "DownloadEventos");
+    let resposta = format!(
+        "[DownloadEventos]\nIdEmpregador={}\nCpfTrabalhador={}\nDataInicial={}\nDataFinal={}\n",
+        instancia.texto(id_empregador),
+        instancia.texto(cpf_trabalhador),
+        instancia.texto(data_inicial),
+        instancia.texto(data_final)
+    );
+    responder(instancia, resposta, buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_ObterCertificados(
+    handle: *mut c_void,
+    buffer: *mut c_char,
+    tamanho: *mut c_int,
+) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "ObterCertificados");
+    responder(instancia, String::new(), buffer, tamanho)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn eSocial_Validar(handle: *mut c_void) -> c_int {
+    let (_execucao, instancia) = instancia!(handle, "Validar");
+
+    if instancia.eventos.is_empty() {
+        return instancia.erro(ERRO_EXECUCAO, "Nenhum evento carregado");
+    }
+
+    let formato = instancia
+        .ler_valor("eSocial", "FormatoAlerta")
+        .unwrap_or_default()
+        .to_string();
+    let alertas: Vec<String> = instancia
+        .eventos
+        .iter()
+        .flat_map(|evento| elementos_vazios(evento))
+        .map(|(caminho, id, tag)| {
+            formato
+                .replace("%TAGNIVEL%", &caminho)
+                .replace("%ID%", &id.to_string())
+                .replace("%TAG%", &tag)
+                .replace("%DESCRICAO%", &tag)
+                .replace("%MSG%", "Nenhum valor informado")
+        })
+        .collect();
+
+    if !alertas.is_empty() {
+        let mensagem = format!("Falha na validação dos eventos\n{}", alertas.join("\n"));
+        return instancia.erro(ERRO_EXECUCAO, mensagem);
+    }
+
+    OK
+}
+
+/// Elementos sem conteúdo do XML (`<tag/>` ou `<tag></tag>`), com o caminho das tags
+/// acima deles, a posição do elemento no XML e o nome da tag.
+fn elementos_vazios(xml: &str) -> Vec<(String, usize, String)> {
+    let mut vazios = Vec::new();
+    let mut abertas: Vec<&str> = Vec::new();
+    let mut posicao = 0;
+    let mut aberta_vazia = false;
+    let mut resto = xml;
+
+    while let Some(inicio) = resto.find('<') {
+        let conteudo = &resto[..inicio];
+        let Some(fim) = resto[inicio..].find('>') else {
+            break;
+        };
+        let tag = &resto[inicio + 1..inicio + fim];
+        resto = &resto[inicio + fim + 1..];
+
+        if !conteudo.trim().is_empty() {
+            aberta_vazia = false;
+        }
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(fechada) = tag.strip_prefix('/') {
+            abertas.pop();
+            if aberta_vazia {
+                let caminho: String = abertas.iter().map(|t| format!("<{}>", t)).collect();
+                vazios.push((caminho, posicao, fechada.trim().to_string()));
+            }
+            aberta_vazia = false;
+            continue;
+        }
+
+        posicao += 1;
+        let nome = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+
+        if tag.ends_with('/') {
+            let caminho: String = abertas.iter().map(|t| format!("<{}>", t)).collect();
+            vazios.push((caminho, posicao, nome.to_string()));
+            aberta_vazia = false;
+        } else {
+            abertas.push(nome);
+            aberta_vazia = true;
+        }
+    }
+
+    vazios
+}
@@ -1,18 +1,42 @@
 use std::env;
+use std::error::Error;
+use std::time::Instant;
 
-use acbr_lib::acbr_lib_esocial::ACBrLibEsocial;
+use acbr_lib::acbr_lib_esocial::config::{CarregadorConfig, ConfigESocial, TipoResposta};
+use acbr_lib::acbr_lib_esocial::{ACBrLibEsocial, ConfigPool, ESocialPool, SessaoAssincrona};
 use futures::future::join_all;
-use uuid::Uuid;
+
+const TAREFAS: usize = 5000;
 
 #[tokio::main]
 async fn main() {
+    // `cargo run -- pool 16` usa um ESocialPool com 16 sessões; sem argumentos cada
+    // tarefa cria e finaliza a sua própria sessão
+    let mut args = env::args().skip(1);
+    let config = match config() {
+        Ok(config) => config,
+        Err(err) => return eprintln!("Erro ao montar a configuração: {}", err),
+    };
+    let pool = match args.next().as_deref() {
+        Some("pool") => {
+            let tamanho = args.next().and_then(|t| t.parse().ok()).unwrap_or(16);
+            Some(ESocialPool::new(
+                ConfigPool::new(tamanho).com_config(config.clone()),
+            ))
+        }
+        _ => None,
+    };
+
+    let inicio = Instant::now();
     let mut handles = Vec::new();
 
-    for i in 1..5000 {
+    for i in 1..=TAREFAS {
         println!("Abrindo a thread1: {}", i);
 
+        let pool = pool.clone();
+        let config = config.clone();
         let handle = tokio::spawn(async move {
-            match execute().await {
+            match execute(pool.as_ref(), config).await {
                 Ok(result) => println!("Resultado thread1 {}: {}", i, result),
                 Err(err) => {
                     eprintln!("Erro Thread1 {}: {}", i, err)
@@ -41,49 +65,80 @@ async fn main() {
     let results = join_all(handles).await;
     let errors_count = results.iter().filter(|r| r.is_err()).count();
 
+    let tempo = inicio.elapsed();
+
     println!("Todas as threads foram concluídas!");
     println!("Total de threads com erro: {}", errors_count);
+    println!(
+        "Tempo total: {:.2?} ({:.1} tarefas/s)",
+        tempo,
+        TAREFAS as f64 / tempo.as_secs_f64()
+    );
+
+    if let Some(pool) = pool {
+        println!("Pool: {:?}", pool.estatisticas());
+    }
 }
 
-async fn execute() -> Result<String, String> {
-    let mut lib = ACBrLibEsocial::new()?;
-
-    let id = Uuid::new_v4().to_string();
-
-    
-    let base_path_config = env::current_dir().map_err(|err| err.to_string())?;
-
-    let schems_path_directory = base_path_config.join("resources/temp/schemas");
-    let schemas_caminho = schems_path_directory.to_str().unwrap();
-
-    
-    let random_path = format!("resources/temp_ini/config_{id}.ini");
-    let current_path_config = env::current_dir().map_err(|err| err.to_string())?;
-    let config_directory = current_path_config.join(random_path);
-    let caminho_config = config_directory.to_str().unwrap();
+/// Configuração de todas as sessões, aplicada em memória sem gerar arquivos.
+fn config() -> Result<ConfigESocial, Box<dyn Error>> {
+    let base_path_config = env::current_dir()?;
 
-    let _ = lib.esocial_inicializar(caminho_config, "")?;
+    let mut caminhos = ConfigESocial::default();
+    caminhos.principal.log_path = Some(base_path_config.join("logs"));
+    caminhos.esocial.path_schemas = Some(base_path_config.join("resources/temp/schemas"));
+    // Qualquer formato serve: `enviar_lote` lê a resposta nos mesmos tipos
+    caminhos.principal.tipo_resposta = Some(TipoResposta::Json);
 
-    let current_path_logs = env::current_dir().map_err(|err| err.to_string())?;
-    let logs_directory = current_path_logs.join("logs");
-    let caminho_logs = logs_directory.to_str().unwrap();
+    let config = CarregadorConfig::new()
+        .com_arquivo_padrao(base_path_config.join("resources/config.ini"))?
+        .com_variaveis_ambiente()
+        .com_valores(&caminhos)
+        .carregar()?;
 
-    lib.esocial_config_gravar_valor("Principal", "LogPath", caminho_logs)?;
-    lib.esocial_config_gravar_valor("eSocial", "PathSchemas", schemas_caminho)?;
-
-    lib.esocial_config_gravar(caminho_config)?;
+    Ok(config.into_config())
+}
 
+async fn execute(
+    pool: Option<&ESocialPool>,
+    config: ConfigESocial,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
     let xml_content = std::fs::read_to_string("evento.xml")
         .map_err(|e| format!("Erro ao ler arquivo evento.xml: {}", e))?;
 
-    let versao = lib.esocial_versao()?;
-
-    lib.carregar_xml_evento(xml_content)?;
-
-    lib.enviar_esocial(1)?;
-
-    let ultimo_retorno = lib.obter_ultimo_retorno()?;
-    println!("ultimo retorno: {}", ultimo_retorno);
+    let enviar = move |lib: &mut ACBrLibEsocial| {
+        let _versao = lib.esocial_versao()?;
+
+        // Uma sessão do pool pode ter eventos do uso anterior
+        lib.limpar_esocial()?;
+        lib.carregar_xml_evento(xml_content)?;
+
+        lib.enviar_lote(1)
+    };
+
+    // As chamadas à biblioteca bloqueiam, então não rodam nas threads do tokio
+    let envio = match pool {
+        Some(pool) => {
+            let pool = pool.clone();
+            tokio::task::spawn_blocking(move || pool.executar(enviar)).await??
+        }
+        None => {
+            // A configuração fica em memória; o arquivo que a biblioteca exige é temporário
+            let lib = SessaoAssincrona::com_config(config, "").await?;
+            lib.executar(enviar).await?
+        }
+    };
+
+    if !envio.recebido() {
+        return Err(format!(
+            "Lote rejeitado: {} - {}",
+            envio.cod_resposta, envio.desc_resposta
+        )
+        .into());
+    }
 
-    Ok("Debug finalizado!!!!".into())
+    Ok(format!(
+        "Lote recebido, protocolo {}",
+        envio.protocolo.unwrap_or_default()
+    ))
 }